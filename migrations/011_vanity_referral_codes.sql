@@ -0,0 +1,4 @@
+-- Tracks when an address last had its referral code replaced by a vanity code.
+-- Users may request one vanity code; admins can grant codes at any time.
+ALTER TABLE addresses
+ADD COLUMN IF NOT EXISTS vanity_code_set_at TIMESTAMPTZ;
@@ -0,0 +1,28 @@
+-- Runtime overrides for a whitelisted subset of the file configuration.
+-- Keys are dotted config paths (e.g. 'jwt.exp_in_hours'), values are stored as JSON.
+CREATE TABLE IF NOT EXISTS settings (
+    key VARCHAR(255) PRIMARY KEY,
+    value JSONB NOT NULL,
+    updated_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+DROP TRIGGER IF EXISTS set_timestamp ON settings;
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON settings
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+-- Append-only audit trail of every override change. A NULL new_value means the override was removed.
+CREATE TABLE IF NOT EXISTS settings_audit (
+    id BIGSERIAL PRIMARY KEY,
+    key VARCHAR(255) NOT NULL,
+    old_value JSONB,
+    new_value JSONB,
+    changed_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_settings_audit_key ON settings_audit (key);
+CREATE INDEX IF NOT EXISTS idx_settings_audit_created_at ON settings_audit (created_at);
@@ -0,0 +1,11 @@
+-- Records every indexer transfer that has been ingested by the transfer sync,
+-- so re-syncs skip known transfers and ingestion can be audited per transfer id.
+CREATE TABLE IF NOT EXISTS processed_transfers (
+    transfer_id VARCHAR(255) PRIMARY KEY,
+    from_address VARCHAR(64) NOT NULL,
+    to_address VARCHAR(64) NOT NULL,
+    amount VARCHAR(78) NOT NULL,
+    processed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_processed_transfers_processed_at ON processed_transfers (processed_at);
@@ -0,0 +1,5 @@
+-- Several raids (e.g. one per region) may now run at the same time,
+-- so the single-active-raid exclusion constraint is dropped.
+ALTER TABLE raid_quests DROP CONSTRAINT IF EXISTS enforce_one_active_raid;
+
+CREATE INDEX IF NOT EXISTS idx_raid_quests_active ON raid_quests (start_date) WHERE end_date IS NULL;
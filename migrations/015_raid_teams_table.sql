@@ -0,0 +1,32 @@
+-- Teams are scoped to a single raid. A raider can be a member of at most one team per raid.
+CREATE TABLE IF NOT EXISTS raid_teams (
+    id INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    raid_id INTEGER NOT NULL REFERENCES raid_quests (id) ON DELETE CASCADE,
+    name VARCHAR(64) NOT NULL,
+    created_by VARCHAR(64) NOT NULL REFERENCES addresses (quan_address) ON DELETE NO ACTION,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Lets members reference (team, raid) so a membership can't point at another raid's team
+    CONSTRAINT raid_teams_id_raid_id_key UNIQUE (id, raid_id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_raid_teams_raid_name ON raid_teams (raid_id, LOWER(name));
+
+DROP TRIGGER IF EXISTS set_timestamp_raid_teams ON raid_teams;
+
+CREATE TRIGGER set_timestamp_raid_teams BEFORE
+UPDATE
+    ON raid_teams FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp ();
+
+CREATE TABLE IF NOT EXISTS raid_team_members (
+    raid_id INTEGER NOT NULL,
+    team_id INTEGER NOT NULL,
+    quan_address VARCHAR(64) NOT NULL REFERENCES addresses (quan_address) ON DELETE CASCADE,
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (raid_id, quan_address),
+    FOREIGN KEY (team_id, raid_id) REFERENCES raid_teams (id, raid_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_raid_team_members_team_id ON raid_team_members (team_id);
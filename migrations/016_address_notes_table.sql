@@ -0,0 +1,32 @@
+-- Free-form admin notes on addresses, optionally linked to an external support ticket.
+CREATE TABLE IF NOT EXISTS address_notes (
+    id INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    quan_address VARCHAR(64) NOT NULL REFERENCES addresses (quan_address) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    ticket_ref VARCHAR(255),
+    author VARCHAR(50) NOT NULL,
+    updated_by VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_address_notes_quan_address ON address_notes (quan_address, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_address_notes_ticket_ref ON address_notes (ticket_ref);
+
+DROP TRIGGER IF EXISTS set_timestamp_address_notes ON address_notes;
+
+CREATE TRIGGER set_timestamp_address_notes BEFORE
+UPDATE
+    ON address_notes FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp ();
+
+-- Previous versions of a note, written every time the note is edited.
+CREATE TABLE IF NOT EXISTS address_note_revisions (
+    id INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    note_id INTEGER NOT NULL REFERENCES address_notes (id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    ticket_ref VARCHAR(255),
+    edited_by VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_address_note_revisions_note_id ON address_note_revisions (note_id);
@@ -0,0 +1,19 @@
+-- Feature flags for gradual rollout of new endpoints.
+CREATE TABLE IF NOT EXISTS feature_flags (
+    key VARCHAR(64) PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT '',
+    enabled BOOLEAN NOT NULL DEFAULT false,
+    -- Share of addresses (by hash bucket) the flag is enabled for, 0-100
+    rollout_percentage SMALLINT NOT NULL DEFAULT 0 CHECK (rollout_percentage BETWEEN 0 AND 100),
+    -- Addresses that always get the feature while it is enabled, regardless of rollout
+    allowlist TEXT[] NOT NULL DEFAULT '{}',
+    updated_by VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+DROP TRIGGER IF EXISTS set_timestamp_feature_flags ON feature_flags;
+
+CREATE TRIGGER set_timestamp_feature_flags BEFORE
+UPDATE
+    ON feature_flags FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp ();
@@ -0,0 +1,14 @@
+-- Checkpoints of incremental syncs against external sources, one row per sync.
+-- The cursor is opaque and only meaningful to the source that issued it.
+CREATE TABLE IF NOT EXISTS sync_state (
+    name VARCHAR(64) PRIMARY KEY,
+    cursor TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+DROP TRIGGER IF EXISTS set_timestamp_sync_state ON sync_state;
+
+CREATE TRIGGER set_timestamp_sync_state BEFORE
+UPDATE
+    ON sync_state FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp ();
@@ -0,0 +1,66 @@
+-- Opt-in counts per day and referral source, kept up to date by triggers on opt_ins so reports
+-- don't have to aggregate the live table. The referral source is the referrer's address at the
+-- time of the opt-in, or 'direct' when the address wasn't referred.
+ALTER TABLE opt_ins ADD COLUMN IF NOT EXISTS referral_source VARCHAR(64);
+
+CREATE TABLE IF NOT EXISTS opt_in_daily_stats (
+    day DATE NOT NULL,
+    referral_source VARCHAR(64) NOT NULL,
+    opt_ins INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (day, referral_source)
+);
+
+CREATE OR REPLACE FUNCTION trigger_set_opt_in_referral_source()
+RETURNS TRIGGER AS $$
+BEGIN
+  NEW.referral_source = COALESCE(
+    (SELECT referrer_address FROM referrals WHERE referee_address = NEW.quan_address),
+    'direct'
+  );
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION trigger_count_opt_in()
+RETURNS TRIGGER AS $$
+BEGIN
+  IF TG_OP = 'INSERT' THEN
+    INSERT INTO opt_in_daily_stats (day, referral_source, opt_ins)
+    VALUES ((NEW.created_at AT TIME ZONE 'UTC')::DATE, NEW.referral_source, 1)
+    ON CONFLICT (day, referral_source) DO UPDATE SET opt_ins = opt_in_daily_stats.opt_ins + 1;
+    RETURN NEW;
+  END IF;
+
+  UPDATE opt_in_daily_stats
+  SET opt_ins = opt_ins - 1
+  WHERE day = (OLD.created_at AT TIME ZONE 'UTC')::DATE AND referral_source = OLD.referral_source;
+  RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS set_opt_in_referral_source ON opt_ins;
+
+CREATE TRIGGER set_opt_in_referral_source BEFORE
+INSERT
+    ON opt_ins FOR EACH ROW EXECUTE PROCEDURE trigger_set_opt_in_referral_source ();
+
+DROP TRIGGER IF EXISTS count_opt_in ON opt_ins;
+
+CREATE TRIGGER count_opt_in AFTER
+INSERT
+    OR DELETE ON opt_ins FOR EACH ROW EXECUTE PROCEDURE trigger_count_opt_in ();
+
+-- Backfill opt-ins recorded before the stats existed.
+UPDATE opt_ins o
+SET referral_source = COALESCE(
+    (SELECT referrer_address FROM referrals r WHERE r.referee_address = o.quan_address),
+    'direct'
+)
+WHERE referral_source IS NULL;
+
+INSERT INTO opt_in_daily_stats (day, referral_source, opt_ins)
+SELECT (created_at AT TIME ZONE 'UTC')::DATE, referral_source, COUNT(*)
+FROM opt_ins
+GROUP BY 1, 2
+ON CONFLICT (day, referral_source) DO NOTHING;
@@ -0,0 +1,9 @@
+-- Login challenges, previously only kept in process memory. Rows are deleted once the
+-- challenge is used, stale ones are cleaned up when new challenges are issued.
+CREATE TABLE IF NOT EXISTS auth_challenges (
+    temp_session_id VARCHAR(64) PRIMARY KEY,
+    challenge VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_auth_challenges_created_at ON auth_challenges (created_at);
@@ -0,0 +1,48 @@
+-- Admin defined fraud rules. The definition is a tagged JSON object (see FraudRuleDefinition),
+-- every change bumps the version and is kept in fraud_rule_versions.
+CREATE TABLE IF NOT EXISTS fraud_rules (
+    id INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    name VARCHAR(64) NOT NULL UNIQUE,
+    definition JSONB NOT NULL,
+    action VARCHAR(16) NOT NULL CHECK (action IN ('flag', 'quarantine')),
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    version INTEGER NOT NULL DEFAULT 1,
+    hit_count BIGINT NOT NULL DEFAULT 0,
+    last_hit_at TIMESTAMPTZ,
+    updated_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+DROP TRIGGER IF EXISTS set_timestamp_fraud_rules ON fraud_rules;
+
+CREATE TRIGGER set_timestamp_fraud_rules BEFORE
+UPDATE
+    ON fraud_rules FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp ();
+
+CREATE TABLE IF NOT EXISTS fraud_rule_versions (
+    rule_id INTEGER NOT NULL REFERENCES fraud_rules (id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    definition JSONB NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    changed_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (rule_id, version)
+);
+
+-- One row per hit. submission_id is NULL for rules that flag addresses rather than submissions.
+CREATE TABLE IF NOT EXISTS fraud_flags (
+    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    rule_id INTEGER NOT NULL REFERENCES fraud_rules (id) ON DELETE CASCADE,
+    rule_version INTEGER NOT NULL,
+    quan_address VARCHAR(64) NOT NULL REFERENCES addresses (quan_address) ON DELETE CASCADE,
+    submission_id VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- A rule version flags the same address/submission only once, however often it is evaluated.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_fraud_flags_unique_hit ON fraud_flags (rule_id, rule_version, quan_address, COALESCE(submission_id, ''));
+
+CREATE INDEX IF NOT EXISTS idx_fraud_flags_quan_address ON fraud_flags (quan_address);
@@ -0,0 +1,14 @@
+-- Responses of mutating requests sent with an Idempotency-Key header, replayed when the same key is
+-- sent again. status_code is NULL while the first request is still being handled.
+CREATE TABLE IF NOT EXISTS idempotency_records (
+    owner VARCHAR(64) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_hash VARCHAR(64) NOT NULL,
+    status_code SMALLINT,
+    response_body BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (owner, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_records_created_at ON idempotency_records (created_at);
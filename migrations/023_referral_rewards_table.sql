@@ -0,0 +1,17 @@
+-- One reward per referee, granted to its referrer once the referee is active on chain.
+CREATE TABLE IF NOT EXISTS referral_rewards (
+    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    referrer_address VARCHAR(64) NOT NULL REFERENCES addresses (quan_address) ON DELETE CASCADE,
+    referee_address VARCHAR(64) NOT NULL UNIQUE REFERENCES addresses (quan_address) ON DELETE CASCADE,
+    -- Base units, same format as processed_transfers.amount
+    amount VARCHAR(78) NOT NULL,
+    paid_at TIMESTAMPTZ,
+    paid_by VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_referral_rewards_referrer ON referral_rewards (referrer_address, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_referral_rewards_unpaid ON referral_rewards (referrer_address) WHERE paid_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_processed_transfers_from_address ON processed_transfers (from_address);
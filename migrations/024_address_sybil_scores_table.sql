@@ -0,0 +1,15 @@
+-- Sybil risk per registered address, recomputed from the synced transfer graph after each transfer sync.
+CREATE TABLE IF NOT EXISTS address_sybil_scores (
+    quan_address VARCHAR(64) PRIMARY KEY REFERENCES addresses (quan_address) ON DELETE CASCADE,
+    -- 0 (no signal) to 100
+    score SMALLINT NOT NULL,
+    -- Addresses first funded by the same address, including this one
+    funding_cluster_size BIGINT NOT NULL,
+    -- Other addresses of the funding cluster registered around the same time
+    burst_size BIGINT NOT NULL,
+    -- Counterparties this address both sent to and received from
+    circular_counterparties BIGINT NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_processed_transfers_to_address ON processed_transfers (to_address, processed_at);
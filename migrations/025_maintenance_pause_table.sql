@@ -0,0 +1,8 @@
+-- Admin controlled pause of background jobs, at most one row. No row means not paused.
+CREATE TABLE IF NOT EXISTS maintenance_pause (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    paused BOOLEAN NOT NULL,
+    reason TEXT,
+    updated_by VARCHAR(255) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Banned addresses are soft deleted: kept for history and foreign keys, hidden from everything user facing.
+ALTER TABLE addresses ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE addresses ADD COLUMN IF NOT EXISTS deleted_by VARCHAR(255);
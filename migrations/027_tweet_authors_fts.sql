@@ -0,0 +1,6 @@
+-- Full-text search over author handles and display names. The 'simple' configuration keeps names as written
+-- instead of stemming them as English words.
+ALTER TABLE tweet_authors ADD COLUMN IF NOT EXISTS search_fts tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', username || ' ' || name)) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_tweet_authors_fts ON tweet_authors USING GIN(search_fts);
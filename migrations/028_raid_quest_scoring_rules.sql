@@ -0,0 +1,57 @@
+-- Per-raid scoring rules. The defaults score a submission by its impressions only,
+-- which is how raids were ranked before.
+ALTER TABLE raid_quests
+    ADD COLUMN IF NOT EXISTS impression_weight DOUBLE PRECISION NOT NULL DEFAULT 1,
+    ADD COLUMN IF NOT EXISTS like_weight DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS reply_weight DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS retweet_weight DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS max_submission_score DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS min_follower_count INTEGER NOT NULL DEFAULT 0;
+
+-- Raider leaderboard with the weighted score of each raider. Submissions of raiders
+-- below the raid's follower threshold don't count, follower counts come from the
+-- tweet author record of the raider's linked X account.
+DROP MATERIALIZED VIEW IF EXISTS raid_leaderboards;
+
+CREATE MATERIALIZED VIEW raid_leaderboards AS
+SELECT
+    s.raid_id,
+    s.raider_id,
+    COUNT(s.id) as total_submissions,
+    SUM(s.impression_count) as total_impressions,
+    SUM(s.reply_count) as total_replies,
+    SUM(s.retweet_count) as total_retweets,
+    SUM(s.like_count) as total_likes,
+    SUM(
+        LEAST(
+            rq.impression_weight * COALESCE(s.impression_count, 0)
+                + rq.like_weight * COALESCE(s.like_count, 0)
+                + rq.reply_weight * COALESCE(s.reply_count, 0)
+                + rq.retweet_weight * COALESCE(s.retweet_count, 0),
+            rq.max_submission_score
+        )
+    ) as score,
+    MAX(s.updated_at) as last_activity
+FROM
+    raid_submissions s
+    JOIN raid_quests rq ON rq.id = s.raid_id
+WHERE
+    s.is_invalid = false
+    AND (
+        rq.min_follower_count = 0
+        OR EXISTS (
+            SELECT 1
+            FROM x_associations x
+            JOIN tweet_authors ta ON LOWER(ta.username) = LOWER(x.username)
+            WHERE x.quan_address = s.raider_id AND ta.followers_count >= rq.min_follower_count
+        )
+    )
+GROUP BY
+    s.raid_id,
+    s.raider_id;
+
+CREATE UNIQUE INDEX idx_mv_raid_raider_unique ON raid_leaderboards (raid_id, raider_id);
+
+CREATE INDEX idx_mv_rank_impressions ON raid_leaderboards (raid_id, total_impressions DESC);
+
+CREATE INDEX idx_mv_rank_score ON raid_leaderboards (raid_id, score DESC);
@@ -0,0 +1,17 @@
+-- Prize of a raider in a finished raid, recorded from the final raider leaderboard.
+CREATE TABLE IF NOT EXISTS raid_payouts (
+    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    raid_id INTEGER NOT NULL REFERENCES raid_quests (id) ON DELETE CASCADE,
+    raider_id VARCHAR(64) NOT NULL REFERENCES addresses (quan_address) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    -- Base units, same format as referral_rewards.amount
+    amount VARCHAR(78) NOT NULL,
+    paid_at TIMESTAMPTZ,
+    paid_by VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_raid_payout UNIQUE (raid_id, raider_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_raid_payouts_unpaid ON raid_payouts (raid_id) WHERE paid_at IS NULL;
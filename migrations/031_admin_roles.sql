@@ -0,0 +1,6 @@
+-- Admin roles. Admins created before roles existed keep full access.
+ALTER TABLE admins
+    ADD COLUMN IF NOT EXISTS role VARCHAR(20) NOT NULL DEFAULT 'superadmin'
+        CHECK (role IN ('superadmin', 'operator', 'viewer')),
+    -- Disabled admins can't log in and their tokens are rejected
+    ADD COLUMN IF NOT EXISTS disabled_at TIMESTAMPTZ;
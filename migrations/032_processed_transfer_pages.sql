@@ -0,0 +1,12 @@
+-- Indexer pages stored by the transfer sync with the cursor each one was fetched after. A page fetched after
+-- a cursor no stored page ended at means the sync skipped the transfers in between.
+CREATE TABLE IF NOT EXISTS processed_transfer_pages (
+    id BIGSERIAL PRIMARY KEY,
+    after_cursor VARCHAR(255),
+    end_cursor VARCHAR(255) NOT NULL,
+    first_transfer_id VARCHAR(255) NOT NULL,
+    last_transfer_id VARCHAR(255) NOT NULL,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_processed_transfer_pages_end_cursor ON processed_transfer_pages (end_cursor);
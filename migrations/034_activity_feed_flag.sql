@@ -0,0 +1,4 @@
+-- The activity timeline ships enabled for every address. Admins can narrow or turn it off through the flag.
+INSERT INTO feature_flags (key, description, enabled, rollout_percentage, updated_by)
+VALUES ('activity_feed', 'Activity timeline of the signed-in address', true, 100, 'migration')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,3 @@
+-- Content-Type of the stored response, so replays match the original (e.g. problem+json errors).
+-- Records from before this column are JSON.
+ALTER TABLE idempotency_records ADD COLUMN IF NOT EXISTS content_type VARCHAR(255);
@@ -0,0 +1,3 @@
+-- New admins start as viewers and are promoted explicitly. Admins from before roles existed
+-- were already made superadmin when 031 added the column, their rows keep that value.
+ALTER TABLE admins ALTER COLUMN role SET DEFAULT 'viewer';
@@ -45,8 +45,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let result = sqlx::query(
         r#"
-        INSERT INTO admins (username, password)
-        VALUES ($1, $2)
+        INSERT INTO admins (username, password, role)
+        VALUES ($1, $2, 'superadmin')
         RETURNING id
         "#,
     )
@@ -62,6 +62,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("✅ Success! Admin created.");
             println!("ID: {}", id);
             println!("Username: {}", username);
+            println!("Role: superadmin");
         }
         Err(e) => {
             if e.to_string().contains("duplicate key") || e.to_string().contains("unique constraint") {
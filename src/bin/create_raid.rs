@@ -19,7 +19,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let name = name.trim();
 
     println!("Inserting raid into database...");
-    let new_quest = CreateRaidQuest { name: name.to_string() };
+    let new_quest = CreateRaidQuest {
+        name: name.to_string(),
+        scoring: Default::default(),
+    };
 
     let result = db.raid_quests.create(&new_quest).await;
 
@@ -0,0 +1,44 @@
+use clap::Parser;
+use task_master::mock_indexer::{mock_indexer_router, MockIndexerOptions};
+
+/// Serves a fake GraphQL indexer with synthetic transfers. Point `candidates.graphql_url` at
+/// `http://<bind>/graphql` to run TaskMaster locally without a chain indexer.
+#[derive(Parser, Debug)]
+#[command(name = "mock_indexer")]
+struct MockIndexerArgs {
+    #[arg(long, default_value = "127.0.0.1:4350")]
+    bind: String,
+
+    /// Number of distinct accounts
+    #[arg(long, default_value_t = 20)]
+    accounts: usize,
+
+    /// Number of transfers served
+    #[arg(long, default_value_t = 100)]
+    transfers: usize,
+
+    /// Seed for the synthetic data, the same seed always yields the same transfers
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = MockIndexerArgs::parse();
+
+    let router = mock_indexer_router(MockIndexerOptions {
+        accounts: args.accounts,
+        transfers: args.transfers,
+        seed: args.seed,
+    });
+
+    println!(
+        "Mock indexer serving {} transfers between {} accounts on http://{}/graphql",
+        args.transfers, args.accounts, args.bind
+    );
+
+    let listener = tokio::net::TcpListener::bind(&args.bind).await?;
+    axum::serve(listener, router).await?;
+
+    Ok(())
+}
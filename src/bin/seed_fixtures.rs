@@ -0,0 +1,52 @@
+use clap::Parser;
+use task_master::{
+    db_persistence::DbPersistence,
+    fixtures::{FixtureSet, FIXTURE_SETS},
+    AppError, Config,
+};
+
+/// Seeds a named fixture set into the database. Safe to run repeatedly, fixture rows that drifted are reset.
+#[derive(Parser, Debug)]
+#[command(name = "seed_fixtures")]
+struct SeedFixturesArgs {
+    /// Configuration file path
+    #[arg(short, long, default_value = "config/default.toml")]
+    config: String,
+
+    /// Name of the fixture set to seed
+    #[arg(short, long, default_value = "demo")]
+    set: String,
+
+    /// List the available fixture sets and exit
+    #[arg(long)]
+    list: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = SeedFixturesArgs::parse();
+
+    if args.list {
+        for (name, _) in FIXTURE_SETS {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    let set = FixtureSet::load(&args.set)?;
+
+    let config = Config::load(&args.config).map_err(AppError::Config)?;
+    let db = DbPersistence::new(config.get_database_url()).await?;
+
+    println!("Seeding fixture set '{}'...", args.set);
+    let report = set.seed(&db.pool).await?;
+
+    println!("✅ Done. Rows changed:");
+    println!("  addresses:        {}", report.addresses);
+    println!("  opt-ins:          {}", report.opt_ins);
+    println!("  eth associations: {}", report.eth_associations);
+    println!("  x associations:   {}", report.x_associations);
+    println!("  raids:            {}", report.raids);
+
+    Ok(())
+}
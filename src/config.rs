@@ -1,8 +1,20 @@
-use std::path::Path;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use axum::http::HeaderValue;
 use rusx::config::OauthConfig;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    models::validation::{Validate, ValidationErrors},
+    services::secrets::SecretSource,
+    utils::retry::RetryPolicy,
+};
+
+/// Names the profile layered over the base file, e.g. `production` loads `production.toml` next to it.
+pub const PROFILE_ENV: &str = "TASKMASTER_PROFILE";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -15,6 +27,17 @@ pub struct Config {
     pub remote_configs: RemoteConfigsConfig,
     pub risk_checker: RiskCheckerConfig,
     pub exchange_rate: ExchangeRateConfig,
+    pub referral_codes: ReferralCodesConfig,
+    pub request_logging: RequestLoggingConfig,
+    pub retry: RetryConfig,
+    pub auth: AuthConfig,
+    pub rate_limit: RateLimitConfig,
+    pub referral_rewards: ReferralRewardsConfig,
+    pub sybil_detector: SybilDetectorConfig,
+    pub raid_payout: RaidPayoutConfig,
+    pub leaderboard_cache: LeaderboardCacheConfig,
+    pub idempotency: IdempotencyConfig,
+    pub secrets: SecretsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,23 +55,48 @@ pub struct ServerConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CandidatesConfig {
     pub graphql_url: String,
+    /// Transfers requested per page when syncing from the indexer.
+    pub sync_page_size: u32,
+    /// Indexer requests in flight at once, 0 for no limit.
+    #[serde(default)]
+    pub max_concurrent_requests: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataConfig {
     pub database_url: String,
+    /// Replica that serves list, leaderboard and stats queries, the primary serves them when unset.
+    #[serde(default)]
+    pub read_replica_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggingConfig {
     pub level: String,
+    #[serde(default)]
+    pub format: LogFormat,
+}
+
+/// Output format of the logs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Human readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line, with the fields of the enclosing spans such as `request_id`.
+    Json,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JwtConfig {
+    /// Signing secret, or where to read it from, see [`SecretSource`].
     pub secret: String,
     pub admin_secret: String,
+    /// Lifetime of access tokens, keep it short since they are only checked against their session.
     pub exp_in_hours: i64,
+    /// Lifetime of a session without a refresh, each refresh extends it by this much again.
+    pub refresh_exp_in_hours: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,31 +115,204 @@ pub struct ExchangeRateConfig {
     pub api_key: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferralCodesConfig {
+    /// Vanity codes starting with one of these prefixes can only be granted by an admin.
+    pub reserved_prefixes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferralRewardsConfig {
+    /// Outgoing transfers a referee needs before its referrer is rewarded.
+    pub min_referee_transfers: u32,
+    /// Reward per active referee in base units, 0 disables rewards.
+    pub reward_amount: u64,
+    /// Referees with a higher sybil score don't earn their referrer a reward.
+    pub max_referee_sybil_score: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SybilDetectorConfig {
+    /// Addresses of a funding cluster registered within this many minutes of each other count as a burst.
+    pub burst_window_minutes: u32,
+}
+
+/// How a raid's prize pool is split among its top raiders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutCurve {
+    /// Every winner gets the same amount.
+    Equal,
+    /// Shares fall linearly with rank, first place gets `winners` parts and last place one.
+    Linear,
+    /// Shares follow the winners' scores.
+    Proportional,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaidPayoutConfig {
+    /// Prize pool of each raid in base units, 0 disables raid payouts.
+    pub prize_pool: u64,
+    /// Number of top raiders that share the prize pool.
+    pub winners: u32,
+    pub curve: PayoutCurve,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardCacheConfig {
+    /// How long a computed leaderboard is served from memory, 0 disables caching.
+    pub ttl_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyConfig {
+    /// A keyed request still pending after this long is taken as abandoned, its key can be claimed again.
+    pub pending_lease_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    /// How often secrets read from files or Vault are re-read, so rotated values apply without a restart.
+    pub refresh_interval_secs: u64,
+    /// How long the value a secret rotated from is still accepted when verifying tokens.
+    pub rotation_grace_secs: u64,
+    /// Vault server for `vault:` secrets, the token is taken from `VAULT_TOKEN`.
+    #[serde(default)]
+    pub vault_addr: Option<String>,
+}
+
+/// How much of a request/response is logged for a route group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestLogMode {
+    None,
+    /// Method, path, status and latency.
+    Metadata,
+    /// Metadata plus request and response bodies, with secret fields redacted.
+    Full,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestLoggingConfig {
+    /// Mode for route groups without an explicit entry in `groups`.
+    pub default: RequestLogMode,
+    /// Per route group modes, keyed by the first path segment under `/api` (e.g. `auth`, `raid-quests`).
+    pub groups: HashMap<String, RequestLogMode>,
+    /// JSON fields and query parameters whose values are never logged. Matched case-insensitively.
+    pub redacted_fields: Vec<String>,
+}
+
+/// Where login challenges are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeStoreMode {
+    /// Process memory only, lost on restart and not shared between instances.
+    Memory,
+    /// Written to both, read from the database first. Used while migrating.
+    Dual,
+    Database,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    pub challenge_store: ChallengeStoreMode,
+    /// Challenges older than this can't be used to log in.
+    pub challenge_ttl_secs: u64,
+    /// Challenges kept in memory before the oldest are dropped, 0 for no limit.
+    pub max_memory_challenges: usize,
+}
+
+/// Token bucket of a rate limited route.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitRule {
+    /// Requests that can be made in a burst.
+    pub capacity: u32,
+    /// Tokens added back per minute.
+    pub refill_per_minute: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Buckets keyed by route name, see the `RATE_LIMITED_*` constants in `middlewares::rate_limit`. Routes
+    /// without an entry are not limited.
+    pub routes: HashMap<String, RateLimitRule>,
+    /// Take the client IP from the first `X-Forwarded-For` entry. Only enable behind a proxy that sets it.
+    pub trust_forwarded_for: bool,
+}
+
+/// Retry policies for outbound integrations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Chain indexer used by the transfer sync.
+    pub graphql: RetryPolicy,
+    pub exchange_rate: RetryPolicy,
+    /// Etherscan, used by the risk checker.
+    pub etherscan: RetryPolicy,
+    /// X API, used when adding tweet authors.
+    pub x_api: RetryPolicy,
+}
+
+impl RequestLoggingConfig {
+    pub fn mode_for(&self, group: &str) -> RequestLogMode {
+        self.groups.get(group).copied().unwrap_or(self.default)
+    }
+}
+
 impl Config {
+    /// Loads `config_path`, then the profile named by [`PROFILE_ENV`] if set, then `TASKMASTER_*` environment
+    /// variables with `__` between section and key (e.g. `TASKMASTER_DATA__DATABASE_URL`). Every missing or
+    /// invalid setting is reported in one error.
     pub fn load(config_path: &str) -> Result<Self, config::ConfigError> {
-        let settings = config::Config::builder()
-            .add_source(config::File::new(config_path, config::FileFormat::Toml))
-            .add_source(config::Environment::with_prefix("TASKMASTER"))
+        let profile = std::env::var(PROFILE_ENV).ok().filter(|profile| !profile.is_empty());
+        Self::load_profile(config_path, profile.as_deref())
+    }
+
+    pub fn load_profile(config_path: &str, profile: Option<&str>) -> Result<Self, config::ConfigError> {
+        let mut files = vec![PathBuf::from(config_path)];
+        if let Some(profile) = profile {
+            files.push(Path::new(config_path).with_file_name(format!("{}.toml", profile)));
+        }
+
+        let mut builder = config::Config::builder();
+        let mut errors = ValidationErrors::new();
+        for file in &files {
+            match read_interpolated(file, |name| std::env::var(name).ok()) {
+                Ok(contents) => {
+                    builder = builder.add_source(config::File::from_str(&contents, config::FileFormat::Toml))
+                }
+                Err(file_errors) => errors.nest(&file.to_string_lossy(), Err(file_errors)),
+            }
+        }
+        errors.into_result().map_err(invalid_config)?;
+
+        let settings = builder
+            .add_source(
+                config::Environment::with_prefix("TASKMASTER")
+                    .prefix_separator("_")
+                    .separator("__"),
+            )
             .build()?;
 
-        let mut config: Self = settings.try_deserialize()?;
+        let mut config: Self = match settings.clone().try_deserialize() {
+            Ok(config) => config,
+            Err(e) => {
+                let errors = section_errors(&settings);
+                return Err(if errors.errors.is_empty() {
+                    e
+                } else {
+                    invalid_config(errors)
+                });
+            }
+        };
         config.resolve_relative_paths(config_path);
+        config.validate().map_err(invalid_config)?;
+
         Ok(config)
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-util"))]
     pub fn load_test_env() -> Result<Self, config::ConfigError> {
-        let test_config_path = "config/test.toml";
-        let settings = config::Config::builder()
-            // Load the test-specific configuration file
-            .add_source(config::File::new(test_config_path, config::FileFormat::Toml))
-            // You can still layer environment variables for testing if you need to
-            .add_source(config::Environment::with_prefix("TASKMASTER"))
-            .build()?;
-
-        let mut config: Self = settings.try_deserialize()?;
-        config.resolve_relative_paths(test_config_path);
-        Ok(config)
+        Self::load_profile("config/test.toml", None)
     }
 
     pub fn get_database_url(&self) -> &str {
@@ -106,6 +327,10 @@ impl Config {
         chrono::Duration::hours(self.jwt.exp_in_hours)
     }
 
+    pub fn get_refresh_token_expiration(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.jwt.refresh_exp_in_hours)
+    }
+
     pub fn get_cors_allowed_origins(&self) -> Vec<HeaderValue> {
         self.server
             .cors_allowed_origins
@@ -129,3 +354,245 @@ impl Config {
         self.remote_configs.wallet_configs_file = base_dir.join(wallet_configs_path).to_string_lossy().to_string();
     }
 }
+
+impl Validate for Config {
+    /// Checks the values serde can't, such as empty secrets and zero sizes.
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+
+        errors.check(
+            !self.data.database_url.is_empty(),
+            "data.database_url",
+            "must not be empty",
+        );
+        errors.check(
+            !self.candidates.graphql_url.is_empty(),
+            "candidates.graphql_url",
+            "must not be empty",
+        );
+        errors.check(
+            self.candidates.sync_page_size > 0,
+            "candidates.sync_page_size",
+            "must be greater than 0",
+        );
+        errors.check(
+            matches!(
+                self.logging.level.to_lowercase().as_str(),
+                "error" | "warn" | "info" | "debug" | "trace"
+            ),
+            "logging.level",
+            "must be one of error, warn, info, debug, trace",
+        );
+        for (field, secret) in [
+            ("jwt.secret", &self.jwt.secret),
+            ("jwt.admin_secret", &self.jwt.admin_secret),
+        ] {
+            errors.check(!secret.is_empty(), field, "must not be empty");
+            if let Err(e) = SecretSource::parse(secret) {
+                errors.add(field, e.to_string());
+            }
+        }
+        errors.check(self.jwt.exp_in_hours > 0, "jwt.exp_in_hours", "must be greater than 0");
+        errors.check(
+            self.jwt.refresh_exp_in_hours >= self.jwt.exp_in_hours,
+            "jwt.refresh_exp_in_hours",
+            "must not be shorter than jwt.exp_in_hours",
+        );
+        for origin in &self.server.cors_allowed_origins {
+            errors.check(
+                HeaderValue::from_str(origin).is_ok(),
+                "server.cors_allowed_origins",
+                format!("{:?} is not a valid origin", origin),
+            );
+        }
+        errors.check(
+            self.auth.challenge_ttl_secs > 0,
+            "auth.challenge_ttl_secs",
+            "must be greater than 0",
+        );
+        errors.check(
+            self.idempotency.pending_lease_secs > 0,
+            "idempotency.pending_lease_secs",
+            "must be greater than 0",
+        );
+        for (route, rule) in &self.rate_limit.routes {
+            errors.check(
+                rule.capacity > 0 && rule.refill_per_minute > 0,
+                &format!("rate_limit.routes.{}", route),
+                "capacity and refill_per_minute must be greater than 0",
+            );
+        }
+        for (name, policy) in [
+            ("graphql", &self.retry.graphql),
+            ("exchange_rate", &self.retry.exchange_rate),
+            ("etherscan", &self.retry.etherscan),
+            ("x_api", &self.retry.x_api),
+        ] {
+            errors.check(
+                policy.max_attempts > 0,
+                &format!("retry.{}.max_attempts", name),
+                "must be greater than 0",
+            );
+        }
+
+        errors.into_result()
+    }
+}
+
+fn invalid_config(errors: ValidationErrors) -> config::ConfigError {
+    let problems: Vec<String> = errors
+        .errors
+        .iter()
+        .map(|e| format!("  {}: {}", e.field, e.message))
+        .collect();
+
+    config::ConfigError::Message(format!("Invalid configuration:\n{}", problems.join("\n")))
+}
+
+/// Reads a config file with `${VAR}` replaced by the variable's value, so secrets can stay out of the file.
+/// Values are escaped for TOML strings, and comment lines are left alone. Every unset variable is reported.
+fn read_interpolated(path: &Path, lookup: impl Fn(&str) -> Option<String>) -> Result<String, ValidationErrors> {
+    let contents = std::fs::read_to_string(path).map_err(|e| ValidationErrors::single("file", e.to_string()))?;
+    interpolate(&contents, lookup)
+}
+
+fn interpolate(contents: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String, ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+    let mut output = String::with_capacity(contents.len());
+
+    for (index, line) in contents.split_inclusive('\n').enumerate() {
+        if line.trim_start().starts_with('#') {
+            output.push_str(line);
+            continue;
+        }
+
+        let field = format!("line {}", index + 1);
+        let mut rest = line;
+        while let Some(start) = rest.find("${") {
+            output.push_str(&rest[..start]);
+            let Some(len) = rest[start + 2..].find('}') else {
+                errors.add(&field, "unterminated ${");
+                output.push_str(&rest[start..]);
+                rest = "";
+                break;
+            };
+
+            let name = &rest[start + 2..start + 2 + len];
+            match lookup(name) {
+                Some(value) => output.push_str(&value.replace('\\', "\\\\").replace('"', "\\\"")),
+                None => errors.add(&field, format!("environment variable {} is not set", name)),
+            }
+            rest = &rest[start + 3 + len..];
+        }
+        output.push_str(rest);
+    }
+
+    errors.into_result().map(|_| output)
+}
+
+/// Deserializes each section on its own, so all broken sections are reported instead of the first.
+fn section_errors(settings: &config::Config) -> ValidationErrors {
+    fn check<T: DeserializeOwned>(settings: &config::Config, key: &str, errors: &mut ValidationErrors) {
+        if let Err(e) = settings.get::<T>(key) {
+            errors.add(key, e.to_string());
+        }
+    }
+
+    let mut errors = ValidationErrors::new();
+    check::<ServerConfig>(settings, "server", &mut errors);
+    check::<CandidatesConfig>(settings, "candidates", &mut errors);
+    check::<DataConfig>(settings, "data", &mut errors);
+    check::<LoggingConfig>(settings, "logging", &mut errors);
+    check::<JwtConfig>(settings, "jwt", &mut errors);
+    check::<OauthConfig>(settings, "x_oauth", &mut errors);
+    check::<RemoteConfigsConfig>(settings, "remote_configs", &mut errors);
+    check::<RiskCheckerConfig>(settings, "risk_checker", &mut errors);
+    check::<ExchangeRateConfig>(settings, "exchange_rate", &mut errors);
+    check::<ReferralCodesConfig>(settings, "referral_codes", &mut errors);
+    check::<RequestLoggingConfig>(settings, "request_logging", &mut errors);
+    check::<RetryConfig>(settings, "retry", &mut errors);
+    check::<AuthConfig>(settings, "auth", &mut errors);
+    check::<RateLimitConfig>(settings, "rate_limit", &mut errors);
+    check::<ReferralRewardsConfig>(settings, "referral_rewards", &mut errors);
+    check::<SybilDetectorConfig>(settings, "sybil_detector", &mut errors);
+    check::<RaidPayoutConfig>(settings, "raid_payout", &mut errors);
+    check::<LeaderboardCacheConfig>(settings, "leaderboard_cache", &mut errors);
+    check::<SecretsConfig>(settings, "secrets", &mut errors);
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate_env_variables() {
+        let lookup = |name: &str| match name {
+            "DB_PASSWORD" => Some("p\"w".to_string()),
+            "JWT_SECRET" => Some("secret".to_string()),
+            _ => None,
+        };
+
+        let contents =
+            "# uses ${UNSET} in a comment\nurl = \"postgres://app:${DB_PASSWORD}@db\"\nsecret = \"${JWT_SECRET}\"\n";
+        assert_eq!(
+            interpolate(contents, lookup).unwrap(),
+            "# uses ${UNSET} in a comment\nurl = \"postgres://app:p\\\"w@db\"\nsecret = \"secret\"\n"
+        );
+
+        let err = interpolate(
+            "a = \"${MISSING_A}\"\nb = \"${MISSING_B}\"\nc = \"${JWT_SECRET\"",
+            lookup,
+        )
+        .unwrap_err();
+        assert_eq!(err.fields(), vec!["line 1", "line 2", "line 3"]);
+    }
+
+    #[test]
+    fn test_validation_reports_every_problem() {
+        let mut config = Config::load_test_env().unwrap();
+        assert!(config.validate().is_ok());
+
+        config.jwt.secret.clear();
+        // Missing its #key, it must not be taken as the secret itself
+        config.jwt.admin_secret = "vault:secret/data/task-master".to_string();
+        config.logging.level = "loud".to_string();
+        config.candidates.sync_page_size = 0;
+        let err = config.validate().unwrap_err();
+        assert_eq!(
+            err.fields(),
+            vec![
+                "candidates.sync_page_size",
+                "logging.level",
+                "jwt.secret",
+                "jwt.admin_secret"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_profile_overrides_base_file() {
+        let dir = std::env::temp_dir().join(format!("task-master-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("config/test.toml", dir.join("base.toml")).unwrap();
+        std::fs::write(dir.join("staging.toml"), "[logging]\nlevel = \"debug\"\n").unwrap();
+        std::fs::write(
+            dir.join("broken.toml"),
+            "[jwt]\nexp_in_hours = \"soon\"\n[candidates]\nsync_page_size = -1\n",
+        )
+        .unwrap();
+        let base = dir.join("base.toml");
+        let base = base.to_str().unwrap();
+
+        let config = Config::load_profile(base, Some("staging")).unwrap();
+        assert_eq!(config.logging.level, "debug");
+
+        let err = Config::load_profile(base, Some("broken")).unwrap_err().to_string();
+        assert!(err.contains("jwt"), "{}", err);
+        assert!(err.contains("candidates"), "{}", err);
+
+        assert!(Config::load_profile(base, Some("missing")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
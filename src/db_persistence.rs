@@ -1,11 +1,28 @@
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{postgres::PgPoolOptions, PgConnection, PgPool, Postgres, Transaction};
 
+use crate::repositories::activity::ActivityRepository;
+use crate::repositories::address_note::AddressNoteRepository;
 use crate::repositories::admin::AdminRepository;
+use crate::repositories::auth_challenge::AuthChallengeRepository;
+use crate::repositories::feature_flag::FeatureFlagRepository;
+use crate::repositories::fraud_rule::FraudRuleRepository;
+use crate::repositories::idempotency_record::IdempotencyRecordRepository;
+use crate::repositories::maintenance::MaintenanceRepository;
+use crate::repositories::opt_in_stat::OptInStatRepository;
+use crate::repositories::processed_transfer::ProcessedTransferRepository;
+use crate::repositories::raid_payout::RaidPayoutRepository;
 use crate::repositories::raid_quest::RaidQuestRepository;
+use crate::repositories::raid_team::RaidTeamRepository;
+use crate::repositories::referral_reward::ReferralRewardRepository;
 use crate::repositories::relevant_tweet::RelevantTweetRepository;
+use crate::repositories::session::SessionRepository;
+use crate::repositories::setting::SettingRepository;
+use crate::repositories::sybil_score::SybilScoreRepository;
+use crate::repositories::sync_state::SyncStateRepository;
 use crate::repositories::tweet_author::TweetAuthorRepository;
 use crate::repositories::DbResult;
 use crate::repositories::{address::AddressRepository, referral::ReferralRepository};
+use crate::services::event_bus::EventBus;
 
 #[derive(Debug, thiserror::Error)]
 pub enum DbError {
@@ -19,6 +36,8 @@ pub enum DbError {
     RecordNotFound(String),
     #[error("Conflict error: {0}")]
     UniqueViolation(String),
+    #[error("Raid Quest {0} already has paid payouts")]
+    PayoutsAlreadyPaid(i32),
 }
 
 #[derive(Debug, Clone)]
@@ -29,6 +48,24 @@ pub struct DbPersistence {
     pub relevant_tweets: RelevantTweetRepository,
     pub tweet_authors: TweetAuthorRepository,
     pub raid_quests: RaidQuestRepository,
+    pub settings: SettingRepository,
+    pub processed_transfers: ProcessedTransferRepository,
+    pub raid_teams: RaidTeamRepository,
+    pub address_notes: AddressNoteRepository,
+    pub feature_flags: FeatureFlagRepository,
+    pub sync_state: SyncStateRepository,
+    pub opt_in_stats: OptInStatRepository,
+    pub auth_challenges: AuthChallengeRepository,
+    pub fraud_rules: FraudRuleRepository,
+    pub idempotency_records: IdempotencyRecordRepository,
+    pub referral_rewards: ReferralRewardRepository,
+    pub sybil_scores: SybilScoreRepository,
+    pub maintenance: MaintenanceRepository,
+    pub raid_payouts: RaidPayoutRepository,
+    pub sessions: SessionRepository,
+    pub activity: ActivityRepository,
+    /// Changes published by the repositories after their writes.
+    pub events: EventBus,
 
     /// Used by the `create_admin` binary and integration tests (not the main server binary).
     #[allow(dead_code)]
@@ -37,16 +74,49 @@ pub struct DbPersistence {
 
 impl DbPersistence {
     pub async fn new(database_url: &str) -> DbResult<Self> {
+        Self::with_read_replica(database_url, None).await
+    }
+
+    /// Connects to the primary, and to a read replica when `read_replica_url` is set. Lists, leaderboards and stats
+    /// are read from the replica, everything else uses the primary. Migrations only run on the primary.
+    pub async fn with_read_replica(database_url: &str, read_replica_url: Option<&str>) -> DbResult<Self> {
         let pool = PgPoolOptions::new().max_connections(10).connect(database_url).await?;
+        let read_pool = match read_replica_url {
+            Some(url) => Some(PgPoolOptions::new().max_connections(10).connect(url).await?),
+            None => None,
+        };
+
+        Self::from_pools(pool, read_pool).await
+    }
 
+    /// Migrates `pool` and builds the repositories on it, reading from `read_pool` where a replica is allowed.
+    pub async fn from_pools(pool: PgPool, read_pool: Option<PgPool>) -> DbResult<Self> {
         sqlx::migrate!("./migrations").run(&pool).await?;
+        let read_pool = read_pool.unwrap_or_else(|| pool.clone());
 
-        let addresses = AddressRepository::new(&pool);
+        let events = EventBus::new();
+        let addresses = AddressRepository::new(&pool).with_read_pool(&read_pool);
         let referrals = ReferralRepository::new(&pool);
         let admin = AdminRepository::new(&pool);
-        let relevant_tweets = RelevantTweetRepository::new(&pool);
-        let tweet_authors = TweetAuthorRepository::new(&pool);
-        let raid_quests = RaidQuestRepository::new(&pool);
+        let relevant_tweets = RelevantTweetRepository::new(&pool).with_read_pool(&read_pool);
+        let tweet_authors = TweetAuthorRepository::new(&pool).with_read_pool(&read_pool);
+        let raid_quests = RaidQuestRepository::new(&pool, &events).with_read_pool(&read_pool);
+        let settings = SettingRepository::new(&pool);
+        let processed_transfers = ProcessedTransferRepository::new(&pool);
+        let raid_teams = RaidTeamRepository::new(&pool, &events).with_read_pool(&read_pool);
+        let address_notes = AddressNoteRepository::new(&pool);
+        let feature_flags = FeatureFlagRepository::new(&pool);
+        let sync_state = SyncStateRepository::new(&pool);
+        let opt_in_stats = OptInStatRepository::new(&pool).with_read_pool(&read_pool);
+        let auth_challenges = AuthChallengeRepository::new(&pool);
+        let fraud_rules = FraudRuleRepository::new(&pool);
+        let idempotency_records = IdempotencyRecordRepository::new(&pool);
+        let referral_rewards = ReferralRewardRepository::new(&pool);
+        let sybil_scores = SybilScoreRepository::new(&pool);
+        let maintenance = MaintenanceRepository::new(&pool);
+        let raid_payouts = RaidPayoutRepository::new(&pool);
+        let sessions = SessionRepository::new(&pool);
+        let activity = ActivityRepository::new(&pool).with_read_pool(&read_pool);
 
         Ok(Self {
             pool,
@@ -56,6 +126,48 @@ impl DbPersistence {
             relevant_tweets,
             tweet_authors,
             raid_quests,
+            settings,
+            processed_transfers,
+            raid_teams,
+            address_notes,
+            feature_flags,
+            sync_state,
+            opt_in_stats,
+            auth_challenges,
+            fraud_rules,
+            idempotency_records,
+            referral_rewards,
+            sybil_scores,
+            maintenance,
+            raid_payouts,
+            sessions,
+            activity,
+            events,
+        })
+    }
+
+    /// Starts a unit of work for handlers and services that write more than once.
+    pub async fn begin(&self) -> DbResult<UnitOfWork> {
+        Ok(UnitOfWork {
+            tx: self.pool.begin().await?,
         })
     }
 }
+
+/// A database transaction spanning several repository calls. Pass [`UnitOfWork::conn`] to the repositories'
+/// `*_with` methods. Nothing is persisted until [`UnitOfWork::commit`], dropping it rolls everything back.
+#[derive(Debug)]
+pub struct UnitOfWork {
+    tx: Transaction<'static, Postgres>,
+}
+
+impl UnitOfWork {
+    pub fn conn(&mut self) -> &mut PgConnection {
+        &mut self.tx
+    }
+
+    pub async fn commit(self) -> DbResult<()> {
+        self.tx.commit().await?;
+        Ok(())
+    }
+}
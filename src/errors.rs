@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -9,12 +9,18 @@ use tracing::error;
 
 use crate::{
     db_persistence::DbError,
-    handlers::{auth::AuthHandlerError, referral::ReferralHandlerError, HandlerError},
-    models::ModelError,
+    handlers::{
+        auth::AuthHandlerError, feature_flag::FeatureFlagHandlerError, raid_team::RaidTeamHandlerError,
+        referral::ReferralHandlerError, HandlerError,
+    },
+    middlewares::idempotency::IdempotencyError,
+    models::{validation::ValidationErrors, ModelError},
     services::{
-        exchange_rate_service::ExchangeRateError, graphql_client::GraphqlError, risk_checker_service::RiskCheckerError,
-        wallet_config_service::WalletConfigsError,
+        exchange_rate_service::ExchangeRateError, graphql_client::GraphqlError,
+        referral_code_service::ReferralCodeError, risk_checker_service::RiskCheckerError, secrets::SecretsError,
+        settings_service::SettingsError, wallet_config_service::WalletConfigsError,
     },
+    utils::retry::RetryError,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -43,18 +49,134 @@ pub enum AppError {
     RiskChecker(#[from] RiskCheckerError),
     #[error("Exchange rate error: {0}")]
     ExchangeRate(#[from] ExchangeRateError),
+    #[error("Referral code error: {0}")]
+    ReferralCode(#[from] ReferralCodeError),
+    #[error("Settings error: {0}")]
+    Settings(#[from] SettingsError),
+    #[error("{0}")]
+    Validation(#[from] ValidationErrors),
+    #[error("Secrets error: {0}")]
+    Secrets(#[from] SecretsError),
+}
+
+impl From<RetryError<SdkError>> for AppError {
+    fn from(err: RetryError<SdkError>) -> Self {
+        match err {
+            RetryError::Failed(e) => AppError::Rusx(e),
+            RetryError::CircuitOpen(_) => AppError::Handler(HandlerError::Unavailable(err.to_string())),
+        }
+    }
 }
 
 pub type AppResult<T> = Result<T, AppError>;
 
+/// Machine readable code sent with every error response, clients should match on it rather than on the
+/// message. Codes are part of the API, renaming one is a breaking change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// Body or query string could not be used.
+    InvalidRequest,
+    /// One or more fields failed validation, listed under `errors`.
+    ValidationFailed,
+    /// Missing or invalid credentials.
+    Unauthorized,
+    /// The address is banned.
+    AddressBanned,
+    /// Authenticated but not allowed to do this.
+    Forbidden,
+    ReferralNotFound,
+    InvalidReferral,
+    DuplicateReferral,
+    /// There is no active raid to act on.
+    NoActiveRaid,
+    /// The feature is switched off.
+    FeatureDisabled,
+    NotFound,
+    AddressNotFound,
+    /// The value conflicts with an existing record.
+    Conflict,
+    /// The raid's payouts already went out, so it can't be finished or reactivated again.
+    PayoutsAlreadyPaid,
+    InvalidVanityCode,
+    ReservedVanityCode,
+    VanityCodeTaken,
+    VanityCodeAlreadySet,
+    SettingNotOverridable,
+    InvalidSettingValue,
+    InvalidEthAddress,
+    EnsNotFound,
+    /// Too many requests, from the client or from us to an upstream service.
+    RateLimited,
+    /// An upstream service could not be reached or answered with an error.
+    UpstreamUnavailable,
+    /// Error passed through from the X API.
+    XApiError,
+    /// The body is larger than the endpoint accepts.
+    PayloadTooLarge,
+    /// The `Idempotency-Key` was already used for a different request.
+    IdempotencyKeyReused,
+    /// A request with the same `Idempotency-Key` is still being handled.
+    RequestInProgress,
+    InternalError,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidRequest => "INVALID_REQUEST",
+            ErrorCode::ValidationFailed => "VALIDATION_FAILED",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::AddressBanned => "ADDRESS_BANNED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::ReferralNotFound => "REFERRAL_NOT_FOUND",
+            ErrorCode::InvalidReferral => "INVALID_REFERRAL",
+            ErrorCode::DuplicateReferral => "DUPLICATE_REFERRAL",
+            ErrorCode::NoActiveRaid => "NO_ACTIVE_RAID",
+            ErrorCode::FeatureDisabled => "FEATURE_DISABLED",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::AddressNotFound => "ADDRESS_NOT_FOUND",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::PayoutsAlreadyPaid => "PAYOUTS_ALREADY_PAID",
+            ErrorCode::InvalidVanityCode => "INVALID_VANITY_CODE",
+            ErrorCode::ReservedVanityCode => "RESERVED_VANITY_CODE",
+            ErrorCode::VanityCodeTaken => "VANITY_CODE_TAKEN",
+            ErrorCode::VanityCodeAlreadySet => "VANITY_CODE_ALREADY_SET",
+            ErrorCode::SettingNotOverridable => "SETTING_NOT_OVERRIDABLE",
+            ErrorCode::InvalidSettingValue => "INVALID_SETTING_VALUE",
+            ErrorCode::InvalidEthAddress => "INVALID_ETH_ADDRESS",
+            ErrorCode::EnsNotFound => "ENS_NOT_FOUND",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+            ErrorCode::UpstreamUnavailable => "UPSTREAM_UNAVAILABLE",
+            ErrorCode::XApiError => "X_API_ERROR",
+            ErrorCode::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            ErrorCode::IdempotencyKeyReused => "IDEMPOTENCY_KEY_REUSED",
+            ErrorCode::RequestInProgress => "REQUEST_IN_PROGRESS",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+        }
+    }
+}
+
+/// Status, code and message of an error response.
+type Problem = (StatusCode, ErrorCode, String);
+
+const INTERNAL_ERROR_MESSAGE: &str = "An internal server error occurred";
+
+fn internal_error() -> Problem {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorCode::InternalError,
+        INTERNAL_ERROR_MESSAGE.to_string(),
+    )
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
+        let (status, code, message) = match self {
             // --- Wallet Feature Flags ---
             AppError::WalletConfigs(err) => map_wallet_configs_error(err),
 
             // --- Model ---
-            AppError::Model(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+            AppError::Model(err) => (StatusCode::BAD_REQUEST, ErrorCode::InvalidRequest, err.to_string()),
 
             // --- Rusx ---
             AppError::Rusx(err) => map_rusx_error(err),
@@ -71,81 +193,144 @@ impl IntoResponse for AppError {
             // --- Exchange Rate ---
             AppError::ExchangeRate(err) => map_exchange_rate_error(err),
 
+            // --- Referral Code ---
+            AppError::ReferralCode(err) => map_referral_code_error(err),
+
+            // --- Settings ---
+            AppError::Settings(err) => map_settings_error(err),
+
+            // --- Validation ---
+            AppError::Validation(err) => return validation_error_response(err),
+
             // --- Everything else ---
             e @ (AppError::Join(_)
             | AppError::Graphql(_)
             | AppError::Config(_)
             | AppError::Http(_)
+            | AppError::Secrets(_)
             | AppError::Server(_)) => {
                 tracing::error!("Internal server error: {:?}", e.to_string());
 
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "An internal server error occurred".to_string(),
-                )
+                internal_error()
             }
         };
 
-        error_response(status, message)
+        error_response(status, code, message)
     }
 }
 
-fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
-    let message = message.into();
-
+/// RFC 7807 body with `code` and the former `error` field as extension members. `type` stays `about:blank`,
+/// the code is what identifies the problem.
+fn problem_body(status: StatusCode, code: ErrorCode, message: String) -> serde_json::Value {
     let message = if message.is_empty() {
         "An error occurred".to_string()
     } else {
         message
     };
 
-    (
-        status,
-        Json(json!({
-            "error": message
-        })),
-    )
-        .into_response()
+    json!({
+        "type": "about:blank",
+        "title": status.canonical_reason().unwrap_or("Error"),
+        "status": status.as_u16(),
+        "detail": message,
+        "code": code.as_str(),
+        "error": message,
+    })
+}
+
+fn problem_response(status: StatusCode, body: serde_json::Value) -> Response {
+    let mut response = (status, Json(body)).into_response();
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/problem+json"),
+    );
+    response
+}
+
+fn error_response(status: StatusCode, code: ErrorCode, message: impl Into<String>) -> Response {
+    problem_response(status, problem_body(status, code, message.into()))
+}
+
+/// 422 listing every field that failed validation.
+fn validation_error_response(err: ValidationErrors) -> Response {
+    let status = StatusCode::UNPROCESSABLE_ENTITY;
+    let mut body = problem_body(status, ErrorCode::ValidationFailed, err.to_string());
+    body["errors"] = json!(err.errors);
+
+    problem_response(status, body)
 }
 
-fn map_rusx_error(err: SdkError) -> (StatusCode, String) {
+fn map_rusx_error(err: SdkError) -> Problem {
     match err {
         SdkError::Api { status, data } => {
             let message = data.title;
 
             (
                 StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                ErrorCode::XApiError,
                 message,
             )
         }
 
-        _ => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "An internal server error occurred".to_string(),
-        ),
+        _ => internal_error(),
     }
 }
 
-fn map_handler_error(err: HandlerError) -> (StatusCode, String) {
+fn map_handler_error(err: HandlerError) -> Problem {
     match err {
-        HandlerError::InvalidBody(err) | HandlerError::QueryParams(err) => (StatusCode::BAD_REQUEST, err),
+        HandlerError::InvalidBody(err) | HandlerError::QueryParams(err) => {
+            (StatusCode::BAD_REQUEST, ErrorCode::InvalidRequest, err)
+        }
 
         HandlerError::Auth(err) => match err {
-            AuthHandlerError::Unauthorized(err) => (StatusCode::UNAUTHORIZED, err),
+            AuthHandlerError::Unauthorized(err) => (StatusCode::UNAUTHORIZED, ErrorCode::Unauthorized, err),
+            AuthHandlerError::Banned(_) => (StatusCode::FORBIDDEN, ErrorCode::AddressBanned, err.to_string()),
+            AuthHandlerError::Forbidden(err) => (StatusCode::FORBIDDEN, ErrorCode::Forbidden, err),
         },
 
         HandlerError::Referral(err) => match err {
-            ReferralHandlerError::ReferralNotFound(err) => (StatusCode::NOT_FOUND, err),
-            ReferralHandlerError::InvalidReferral(err) => (StatusCode::BAD_REQUEST, err),
-            ReferralHandlerError::DuplicateReferral(err) => (StatusCode::CONFLICT, err),
+            ReferralHandlerError::ReferralNotFound(err) => (StatusCode::NOT_FOUND, ErrorCode::ReferralNotFound, err),
+            ReferralHandlerError::InvalidReferral(err) => (StatusCode::BAD_REQUEST, ErrorCode::InvalidReferral, err),
+            ReferralHandlerError::DuplicateReferral(err) => (StatusCode::CONFLICT, ErrorCode::DuplicateReferral, err),
+        },
+
+        HandlerError::RaidTeam(err) => match err {
+            RaidTeamHandlerError::RaidNotActive(err) => (StatusCode::CONFLICT, ErrorCode::NoActiveRaid, err),
         },
+
+        HandlerError::FeatureFlag(err) => match err {
+            FeatureFlagHandlerError::Disabled(_) => {
+                (StatusCode::FORBIDDEN, ErrorCode::FeatureDisabled, err.to_string())
+            }
+        },
+
+        HandlerError::Idempotency(err) => match err {
+            IdempotencyError::InvalidKey => (StatusCode::BAD_REQUEST, ErrorCode::InvalidRequest, err.to_string()),
+            IdempotencyError::BodyTooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                ErrorCode::PayloadTooLarge,
+                err.to_string(),
+            ),
+            IdempotencyError::KeyReused => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorCode::IdempotencyKeyReused,
+                err.to_string(),
+            ),
+            IdempotencyError::InProgress => (StatusCode::CONFLICT, ErrorCode::RequestInProgress, err.to_string()),
+            IdempotencyError::Released => (StatusCode::CONFLICT, ErrorCode::Conflict, err.to_string()),
+        },
+
+        HandlerError::RateLimited(err) => (StatusCode::TOO_MANY_REQUESTS, ErrorCode::RateLimited, err),
+        HandlerError::Unavailable(err) => (StatusCode::SERVICE_UNAVAILABLE, ErrorCode::UpstreamUnavailable, err),
     }
 }
 
-fn map_db_error(err: DbError) -> (StatusCode, String) {
+fn map_db_error(err: DbError) -> Problem {
     match err {
-        DbError::UniqueViolation(err) => (StatusCode::CONFLICT, err),
-        DbError::RecordNotFound(err) | DbError::AddressNotFound(err) => (StatusCode::NOT_FOUND, err),
+        DbError::UniqueViolation(err) => (StatusCode::CONFLICT, ErrorCode::Conflict, err),
+        DbError::RecordNotFound(err) => (StatusCode::NOT_FOUND, ErrorCode::NotFound, err),
+        DbError::AddressNotFound(err) => (StatusCode::NOT_FOUND, ErrorCode::AddressNotFound, err),
+        err @ DbError::PayoutsAlreadyPaid(_) => (StatusCode::CONFLICT, ErrorCode::PayoutsAlreadyPaid, err.to_string()),
 
         DbError::Database(err) => {
             error!("Database error: {}", err);
@@ -154,70 +339,178 @@ fn map_db_error(err: DbError) -> (StatusCode, String) {
             if msg.contains("duplicate key value violates unique constraint") {
                 (
                     StatusCode::CONFLICT,
+                    ErrorCode::Conflict,
                     "The given value is conflicting with existing record".to_string(),
                 )
             } else {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "An internal server error occurred".to_string(),
-                )
+                internal_error()
             }
         }
 
-        DbError::Migration(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "An internal server error occurred".to_string(),
-        ),
+        DbError::Migration(_) => internal_error(),
     }
 }
 
-fn map_wallet_configs_error(err: WalletConfigsError) -> (StatusCode, String) {
-    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn map_wallet_configs_error(err: WalletConfigsError) -> Problem {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorCode::InternalError,
+        err.to_string(),
+    )
 }
 
-fn map_risk_checker_error(err: RiskCheckerError) -> (StatusCode, String) {
+fn map_risk_checker_error(err: RiskCheckerError) -> Problem {
     match err {
-        RiskCheckerError::InvalidInput => (StatusCode::BAD_REQUEST, err.to_string()),
+        RiskCheckerError::InvalidInput => (StatusCode::BAD_REQUEST, ErrorCode::InvalidEthAddress, err.to_string()),
         RiskCheckerError::EnsNotFound(name) => (
             StatusCode::NOT_FOUND,
+            ErrorCode::EnsNotFound,
             format!(
                 "The ENS name \"{}\" could not be resolved to an Ethereum address. Please verify the .eth name is correct.",
                 name
             ),
         ),
-        RiskCheckerError::AddressNotFound => (StatusCode::NOT_FOUND, err.to_string()),
-        RiskCheckerError::RateLimit => (StatusCode::TOO_MANY_REQUESTS, err.to_string()),
-        RiskCheckerError::NetworkError => (StatusCode::SERVICE_UNAVAILABLE, err.to_string()),
+        RiskCheckerError::AddressNotFound => (StatusCode::NOT_FOUND, ErrorCode::AddressNotFound, err.to_string()),
+        RiskCheckerError::RateLimit => (StatusCode::TOO_MANY_REQUESTS, ErrorCode::RateLimited, err.to_string()),
+        RiskCheckerError::NetworkError => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::UpstreamUnavailable,
+            err.to_string(),
+        ),
         RiskCheckerError::Other(msg) => {
             tracing::error!("Risk checker error: {}", msg);
-            (StatusCode::INTERNAL_SERVER_ERROR, "An internal server error occurred".to_string())
+            internal_error()
         }
     }
 }
 
-fn map_exchange_rate_error(err: ExchangeRateError) -> (StatusCode, String) {
+fn map_exchange_rate_error(err: ExchangeRateError) -> Problem {
     match err {
         ExchangeRateError::Api(detail) => {
             tracing::error!("Exchange rate API error: {}", detail);
-            (StatusCode::BAD_GATEWAY, "Failed to fetch exchange rates".to_string())
+            (
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::UpstreamUnavailable,
+                "Failed to fetch exchange rates".to_string(),
+            )
         }
         ExchangeRateError::Http(e) => {
             tracing::error!("Exchange rate HTTP error: {}", e.without_url());
-            (StatusCode::BAD_GATEWAY, "Failed to fetch exchange rates".to_string())
+            (
+                StatusCode::BAD_GATEWAY,
+                ErrorCode::UpstreamUnavailable,
+                "Failed to fetch exchange rates".to_string(),
+            )
         }
         ExchangeRateError::Json(e) => {
             tracing::error!("Exchange rate JSON parse error: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorCode::InternalError,
                 "Failed to parse exchange rate response".to_string(),
             )
         }
         ExchangeRateError::Cache(detail) => {
             tracing::error!("Exchange rate cache error: {}", detail);
+            internal_error()
+        }
+        ExchangeRateError::Unavailable(detail) => {
+            tracing::error!("Exchange rate API unavailable: {}", detail);
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "An internal server error occurred".to_string(),
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorCode::UpstreamUnavailable,
+                "Failed to fetch exchange rates".to_string(),
             )
         }
     }
 }
+
+fn map_referral_code_error(err: ReferralCodeError) -> Problem {
+    match err {
+        ReferralCodeError::InvalidVanityCode(_) => {
+            (StatusCode::BAD_REQUEST, ErrorCode::InvalidVanityCode, err.to_string())
+        }
+        ReferralCodeError::ReservedVanityCode(_) => {
+            (StatusCode::BAD_REQUEST, ErrorCode::ReservedVanityCode, err.to_string())
+        }
+        ReferralCodeError::VanityCodeTaken(_) => (StatusCode::CONFLICT, ErrorCode::VanityCodeTaken, err.to_string()),
+        ReferralCodeError::VanityCodeAlreadySet => {
+            (StatusCode::CONFLICT, ErrorCode::VanityCodeAlreadySet, err.to_string())
+        }
+        ReferralCodeError::Database(err) => map_db_error(err),
+        ReferralCodeError::Checkphrase(_) | ReferralCodeError::Exhausted(_) => {
+            tracing::error!("Referral code error: {}", err);
+            internal_error()
+        }
+    }
+}
+
+fn map_settings_error(err: SettingsError) -> Problem {
+    match err {
+        SettingsError::NotOverridable(_) => (
+            StatusCode::BAD_REQUEST,
+            ErrorCode::SettingNotOverridable,
+            err.to_string(),
+        ),
+        SettingsError::InvalidValue(_, _) => (StatusCode::BAD_REQUEST, ErrorCode::InvalidSettingValue, err.to_string()),
+        SettingsError::Database(err) => map_db_error(err),
+        SettingsError::Lock => {
+            tracing::error!("Settings error: {}", err);
+            internal_error()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn problem(err: AppError) -> (StatusCode, String, serde_json::Value) {
+        let response = err.into_response();
+        let status = response.status();
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, content_type, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_problem_json_with_codes() {
+        let (status, content_type, body) = problem(AppError::Handler(HandlerError::RaidTeam(
+            RaidTeamHandlerError::RaidNotActive("No active raid".to_string()),
+        )))
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(content_type, "application/problem+json");
+        assert_eq!(body["type"], "about:blank");
+        assert_eq!(body["title"], "Conflict");
+        assert_eq!(body["status"], 409);
+        assert_eq!(body["code"], "NO_ACTIVE_RAID");
+        assert_eq!(body["detail"], "No active raid");
+        assert_eq!(body["error"], "No active raid");
+
+        let (status, _, body) = problem(AppError::Database(DbError::AddressNotFound("missing".to_string()))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "ADDRESS_NOT_FOUND");
+
+        let (status, _, body) = problem(AppError::Database(DbError::PayoutsAlreadyPaid(7))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "PAYOUTS_ALREADY_PAID");
+        assert_eq!(body["detail"], "Raid Quest 7 already has paid payouts");
+
+        let (status, _, body) = problem(AppError::Server("boom".to_string())).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["code"], "INTERNAL_ERROR");
+        assert_eq!(body["detail"], INTERNAL_ERROR_MESSAGE);
+
+        let (status, content_type, body) = problem(AppError::Validation(ValidationErrors::single(
+            "name",
+            "must not be empty",
+        )))
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(content_type, "application/problem+json");
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["errors"][0]["field"], "name");
+    }
+}
@@ -0,0 +1,251 @@
+//! Named fixture sets embedded in the binary, used to reset staging and demo environments to a known state.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::models::address::QuanAddress;
+
+/// Fixture sets that can be seeded, by name.
+pub const FIXTURE_SETS: &[(&str, &str)] = &[
+    ("demo", include_str!("../fixtures/demo.json")),
+    ("staging", include_str!("../fixtures/staging.json")),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum FixtureError {
+    #[error("Unknown fixture set '{0}'")]
+    UnknownSet(String),
+    #[error("Invalid fixture set '{0}': {1}")]
+    Invalid(String, String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub type FixtureResult<T> = Result<T, FixtureError>;
+
+#[derive(Debug, Deserialize)]
+pub struct FixtureSet {
+    pub addresses: Vec<AddressFixture>,
+    pub raids: Vec<RaidFixture>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddressFixture {
+    pub quan_address: String,
+    pub referral_code: String,
+    pub opted_in: bool,
+    pub eth_address: Option<String>,
+    pub x_username: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RaidFixture {
+    pub name: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+/// Rows inserted, updated or removed by a seeding run. Rows that already matched the fixtures are not counted.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SeedReport {
+    pub addresses: u64,
+    pub opt_ins: u64,
+    pub eth_associations: u64,
+    pub x_associations: u64,
+    pub raids: u64,
+}
+
+impl FixtureSet {
+    pub fn load(name: &str) -> FixtureResult<Self> {
+        let (_, raw) = FIXTURE_SETS
+            .iter()
+            .find(|(set, _)| *set == name)
+            .ok_or_else(|| FixtureError::UnknownSet(name.to_string()))?;
+
+        let set: FixtureSet =
+            serde_json::from_str(raw).map_err(|e| FixtureError::Invalid(name.to_string(), e.to_string()))?;
+
+        for address in &set.addresses {
+            QuanAddress::from(&address.quan_address)
+                .map_err(|e| FixtureError::Invalid(name.to_string(), format!("{}: {}", address.quan_address, e)))?;
+        }
+
+        Ok(set)
+    }
+
+    /// Writes the fixtures in a single transaction, resetting any fixture rows that drifted since the last run.
+    /// Deleted fixture addresses are restored, and opt-ins and associations the fixtures don't list are removed.
+    /// Raids are matched by name and get their dates and default scoring rules back.
+    pub async fn seed(&self, pool: &PgPool) -> FixtureResult<SeedReport> {
+        let mut tx = pool.begin().await?;
+        let mut report = SeedReport::default();
+
+        for address in &self.addresses {
+            report.addresses += sqlx::query(
+                r#"
+                INSERT INTO addresses (quan_address, referral_code) VALUES ($1, $2)
+                ON CONFLICT (quan_address) DO UPDATE
+                SET referral_code = EXCLUDED.referral_code, deleted_at = NULL, deleted_by = NULL
+                WHERE addresses.referral_code IS DISTINCT FROM EXCLUDED.referral_code
+                    OR addresses.deleted_at IS NOT NULL
+                "#,
+            )
+            .bind(&address.quan_address)
+            .bind(address.referral_code.to_lowercase())
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            let opt_in = if address.opted_in {
+                sqlx::query("INSERT INTO opt_ins (quan_address) VALUES ($1) ON CONFLICT (quan_address) DO NOTHING")
+            } else {
+                sqlx::query("DELETE FROM opt_ins WHERE quan_address = $1")
+            };
+            report.opt_ins += opt_in
+                .bind(&address.quan_address)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+
+            report.eth_associations += match &address.eth_address {
+                Some(eth_address) => sqlx::query(
+                    r#"
+                    INSERT INTO eth_associations (quan_address, eth_address) VALUES ($1, $2)
+                    ON CONFLICT (quan_address) DO UPDATE SET eth_address = EXCLUDED.eth_address
+                    WHERE eth_associations.eth_address <> EXCLUDED.eth_address
+                    "#,
+                )
+                .bind(&address.quan_address)
+                .bind(eth_address),
+                None => sqlx::query("DELETE FROM eth_associations WHERE quan_address = $1").bind(&address.quan_address),
+            }
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            report.x_associations += match &address.x_username {
+                Some(username) => sqlx::query(
+                    r#"
+                    INSERT INTO x_associations (quan_address, username) VALUES ($1, $2)
+                    ON CONFLICT (quan_address) DO UPDATE SET username = EXCLUDED.username
+                    WHERE x_associations.username <> EXCLUDED.username
+                    "#,
+                )
+                .bind(&address.quan_address)
+                .bind(username),
+                None => sqlx::query("DELETE FROM x_associations WHERE quan_address = $1").bind(&address.quan_address),
+            }
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        for raid in &self.raids {
+            // Names aren't unique in `raid_quests`, so there's no conflict target to upsert on.
+            report.raids += sqlx::query(
+                r#"
+                UPDATE raid_quests
+                SET start_date = $2, end_date = $3, impression_weight = 1, like_weight = 0, reply_weight = 0,
+                    retweet_weight = 0, max_submission_score = NULL, min_follower_count = 0
+                WHERE name = $1
+                    AND (start_date, end_date, impression_weight, like_weight, reply_weight, retweet_weight,
+                        max_submission_score, min_follower_count)
+                        IS DISTINCT FROM ($2, $3, 1, 0, 0, 0, NULL, 0)
+                "#,
+            )
+            .bind(&raid.name)
+            .bind(raid.start_date)
+            .bind(raid.end_date)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            report.raids += sqlx::query(
+                r#"
+                INSERT INTO raid_quests (name, start_date, end_date)
+                SELECT $1, $2, $3
+                WHERE NOT EXISTS (SELECT 1 FROM raid_quests WHERE name = $1)
+                "#,
+            )
+            .bind(&raid.name)
+            .bind(raid.start_date)
+            .bind(raid.end_date)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        tx.commit().await?;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_app_state::create_test_app_state;
+
+    #[test]
+    fn test_all_fixture_sets_parse() {
+        for (name, _) in FIXTURE_SETS {
+            FixtureSet::load(name).unwrap();
+        }
+
+        assert!(matches!(FixtureSet::load("missing"), Err(FixtureError::UnknownSet(_))));
+    }
+
+    #[tokio::test]
+    async fn test_seed_is_idempotent() {
+        let state = create_test_app_state().await;
+
+        let set = FixtureSet::load("staging").unwrap();
+
+        let first = set.seed(&state.db.pool).await.unwrap();
+        assert_eq!(first.addresses, 5);
+        assert_eq!(first.opt_ins, 3);
+        assert_eq!(first.raids, 2);
+
+        let second = set.seed(&state.db.pool).await.unwrap();
+        assert_eq!(second, SeedReport::default());
+
+        let staging = |n: u32| format!("qzstaging{:042}", n);
+        sqlx::query("UPDATE addresses SET deleted_at = NOW() WHERE quan_address = $1")
+            .bind(staging(1))
+            .execute(&state.db.pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO opt_ins (quan_address) VALUES ($1)")
+            .bind(staging(4))
+            .execute(&state.db.pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE eth_associations SET eth_address = '0xdrifted' WHERE quan_address = $1")
+            .bind(staging(2))
+            .execute(&state.db.pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE raid_quests SET end_date = NOW(), like_weight = 5 WHERE name = 'Staging Raid (active)'")
+            .execute(&state.db.pool)
+            .await
+            .unwrap();
+
+        let reset = set.seed(&state.db.pool).await.unwrap();
+        assert_eq!(
+            reset,
+            SeedReport {
+                addresses: 1,
+                opt_ins: 1,
+                eth_associations: 1,
+                x_associations: 0,
+                raids: 1,
+            }
+        );
+        assert_eq!(set.seed(&state.db.pool).await.unwrap(), SeedReport::default());
+
+        let active = state.db.raid_quests.find_all_active().await.unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].name, "Staging Raid (active)");
+    }
+}
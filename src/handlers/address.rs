@@ -1,28 +1,56 @@
 use axum::{
-    extract::{Query, State},
+    body::Body,
+    extract::{self, Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, NoContent, Response},
     Extension, Json,
 };
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tracing::Instrument;
 
 use crate::{
+    db_persistence::DbError,
     handlers::{
-        calculate_total_pages, validate_pagination_query, ListQueryParams, PaginatedResponse, PaginationMetadata,
+        auth::AuthHandlerError, calculate_total_pages, validation::ValidatedQuery, HandlerError, ListQueryParams,
+        PaginatedResponse, PaginationMetadata, SuccessResponse,
     },
     http_server::AppState,
+    middlewares::feature_flag::{FeatureFlagKey, RequireFeature},
     models::{
-        address::{AddressFilter, AddressSortColumn, AddressWithOptInAndAssociations},
+        activity::{ActivityEvent, ActivityQueryParams},
+        address::{
+            Address, AddressDataFormat, AddressExportQuery, AddressFilter, AddressImportSummary, AddressSortColumn,
+            AddressWithOptInAndAssociations, VanityReferralCodeInput,
+        },
         admin::Admin,
     },
+    services::{
+        activity_feed::ActivityFeedService,
+        address_import::{export_line, AddressImporter, CSV_EXPORT_HEADER},
+        referral_code_service::{ReferralCodeError, ReferralCodeService},
+    },
     AppError,
 };
 
+/// Addresses read per query while exporting.
+const EXPORT_PAGE_SIZE: i64 = 1000;
+/// Pages read ahead of a slow client.
+const EXPORT_BUFFERED_PAGES: usize = 4;
+
+/// Guards the activity timeline, so it can be turned off without a deploy.
+pub struct ActivityFeedFeature;
+
+impl FeatureFlagKey for ActivityFeedFeature {
+    const KEY: &'static str = "activity_feed";
+}
+
 pub async fn handle_get_addresses(
     State(state): State<AppState>,
     Extension(_): Extension<Admin>,
-    Query(params): Query<ListQueryParams<AddressSortColumn>>,
+    ValidatedQuery(params): ValidatedQuery<ListQueryParams<AddressSortColumn>>,
     Query(filters): Query<AddressFilter>,
 ) -> Result<Json<PaginatedResponse<AddressWithOptInAndAssociations>>, AppError> {
-    validate_pagination_query(params.page, params.page_size)?;
-
     let total_items = state.db.addresses.count_filtered(&params, &filters).await? as u32;
     let total_pages = calculate_total_pages(params.page_size, total_items);
 
@@ -45,16 +73,189 @@ pub async fn handle_get_addresses(
     Ok(Json(response))
 }
 
+/// Admin grant of a vanity referral code. Unlike the user endpoint this may use reserved
+/// prefixes and may replace a previously set vanity code.
+pub async fn handle_set_referral_code(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    extract::Path(quan_address): extract::Path<String>,
+    extract::Json(input): Json<VanityReferralCodeInput>,
+) -> Result<Json<SuccessResponse<Address>>, AppError> {
+    let referral_code = ReferralCodeService::validate_vanity_code(
+        &input.referral_code,
+        &state.settings.current().referral_codes.reserved_prefixes,
+        true,
+    )?;
+
+    if let Some(owner) = state.db.addresses.find_by_referral_code(&referral_code).await? {
+        if owner.quan_address.0 != quan_address {
+            return Err(ReferralCodeError::VanityCodeTaken(referral_code).into());
+        }
+    }
+
+    let updated = state
+        .db
+        .addresses
+        .set_vanity_referral_code(&quan_address, &referral_code, true)
+        .await?
+        .ok_or_else(|| DbError::AddressNotFound(quan_address.clone()))?;
+
+    tracing::info!(
+        "Admin {} set referral code of {} to '{}'",
+        admin.username,
+        quan_address,
+        referral_code
+    );
+
+    Ok(SuccessResponse::new(updated))
+}
+
+/// GET /addresses/:quan_address/activity
+/// Timeline of the caller's own transfers, referrals, opt-in and raid submissions, newest first. The
+/// `activity_feed` flag is enabled for everyone by default, admins can narrow or disable it
+pub async fn handle_get_address_activity(
+    State(state): State<AppState>,
+    _: RequireFeature<ActivityFeedFeature>,
+    Extension(user): Extension<Address>,
+    extract::Path(quan_address): extract::Path<String>,
+    ValidatedQuery(params): ValidatedQuery<ActivityQueryParams>,
+) -> Result<Json<PaginatedResponse<ActivityEvent>>, AppError> {
+    if user.quan_address.0 != quan_address {
+        return Err(AppError::Handler(HandlerError::Auth(AuthHandlerError::Forbidden(
+            "only your own activity can be viewed".to_string(),
+        ))));
+    }
+
+    let response = ActivityFeedService::new(state.db.addresses.clone(), state.db.activity.clone())
+        .timeline(&quan_address, &params)
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// PUT /addresses/:quan_address/ban
+/// Hides the address from auth, leaderboards and rewards
+pub async fn handle_ban_address(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    extract::Path(quan_address): extract::Path<String>,
+) -> Result<NoContent, AppError> {
+    state.db.addresses.soft_delete(&quan_address, &admin.username).await?;
+    state.db.raid_teams.notify_member_changed(&quan_address).await?;
+    tracing::warn!("Address {} banned by {}", quan_address, admin.username);
+
+    Ok(NoContent)
+}
+
+/// DELETE /addresses/:quan_address/ban
+pub async fn handle_unban_address(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    extract::Path(quan_address): extract::Path<String>,
+) -> Result<NoContent, AppError> {
+    state.db.addresses.restore(&quan_address).await?;
+    state.db.raid_teams.notify_member_changed(&quan_address).await?;
+    tracing::info!("Address {} unbanned by {}", quan_address, admin.username);
+
+    Ok(NoContent)
+}
+
+/// POST /admin/addresses/import
+/// Imports a CSV or NDJSON body of addresses, picked by Content-Type. The body is streamed and stored in chunks,
+/// failed rows are reported by line and don't stop the import
+pub async fn handle_import_addresses(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Json<SuccessResponse<AddressImportSummary>>, AppError> {
+    let format = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(AddressDataFormat::from_content_type)
+        .ok_or_else(|| {
+            HandlerError::InvalidBody("Content-Type must be text/csv or application/x-ndjson".to_string())
+        })?;
+
+    let mut importer = AddressImporter::new(&state.db.addresses, format);
+    let mut stream = body.into_data_stream();
+    while let Some(bytes) = stream.next().await {
+        let bytes = bytes.map_err(|e| HandlerError::InvalidBody(format!("Failed to read body: {}", e)))?;
+        importer.push_bytes(&bytes).await?;
+    }
+    let summary = importer.finish().await?;
+
+    tracing::info!(
+        "Admin {} imported {} addresses, {} already existed, {} rows failed",
+        admin.username,
+        summary.imported,
+        summary.skipped_existing,
+        summary.failed
+    );
+
+    Ok(SuccessResponse::new(summary))
+}
+
+/// GET /admin/addresses/export
+/// Streams every address that isn't banned, in the format the import accepts
+pub async fn handle_export_addresses(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+    Query(query): Query<AddressExportQuery>,
+) -> Response {
+    let format = query.format;
+    let addresses = state.db.addresses.clone();
+    let (sender, receiver) = mpsc::channel::<Result<String, DbError>>(EXPORT_BUFFERED_PAGES);
+
+    tokio::spawn(
+        async move {
+            if format == AddressDataFormat::Csv && sender.send(Ok(CSV_EXPORT_HEADER.to_string())).await.is_err() {
+                return;
+            }
+
+            let mut after: Option<String> = None;
+            loop {
+                let page = match addresses.find_page_after(after.as_deref(), EXPORT_PAGE_SIZE).await {
+                    Ok(page) => page,
+                    Err(e) => {
+                        tracing::error!("Address export failed: {}", e);
+                        let _ = sender.send(Err(e)).await;
+                        return;
+                    }
+                };
+                let Some(last) = page.last() else {
+                    return;
+                };
+                after = Some(last.quan_address.0.clone());
+
+                let lines: String = page.iter().map(|address| export_line(format, address)).collect();
+                // The client went away
+                if sender.send(Ok(lines)).await.is_err() || (page.len() as i64) < EXPORT_PAGE_SIZE {
+                    return;
+                }
+            }
+        }
+        .in_current_span(),
+    );
+
+    (
+        [(header::CONTENT_TYPE, format.content_type())],
+        Body::from_stream(ReceiverStream::new(receiver)),
+    )
+        .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        models::admin::Admin,
+        models::{
+            admin::{Admin, AdminRole},
+            feature_flag::FeatureFlagInput,
+        },
         utils::{
             test_app_state::create_test_app_state,
-            test_db::{
-                create_persisted_address, create_persisted_eth_association, create_persisted_opt_in, reset_database,
-            },
+            test_db::{create_persisted_address, create_persisted_eth_association, create_persisted_opt_in},
         },
     };
     use axum::{
@@ -69,7 +270,6 @@ mod tests {
     #[tokio::test]
     async fn test_handle_get_addresses_success() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         let addr1 = create_persisted_address(&state.db.addresses, "A1").await;
         let addr2 = create_persisted_address(&state.db.addresses, "A2").await;
@@ -88,6 +288,8 @@ mod tests {
             id: Uuid::new_v4(),
             username: "new-user".to_string(),
             password: "what-ever".to_string(),
+            role: AdminRole::Superadmin,
+            disabled_at: None,
             updated_at: chrono::Utc::now(),
             created_at: chrono::Utc::now(),
         };
@@ -140,4 +342,120 @@ mod tests {
         assert_eq!(res_addr3["is_opted_in"], false);
         assert!(res_addr3["eth_address"].is_null());
     }
+
+    #[tokio::test]
+    async fn test_handle_set_referral_code_allows_reserved_prefix() {
+        let state = create_test_app_state().await;
+
+        let address = create_persisted_address(&state.db.addresses, "A1").await;
+
+        let router = Router::new()
+            .route("/:quan_address", axum::routing::put(handle_set_referral_code))
+            .layer(Extension(crate::utils::test_db::create_mock_admin()))
+            .with_state(state.clone());
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri(format!("/{}", address.quan_address.0))
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"referral_code":"Quantus-Partner"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let updated = state
+            .db
+            .addresses
+            .find_by_id(&address.quan_address.0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.referral_code, "quantus-partner");
+    }
+
+    #[tokio::test]
+    async fn test_activity_feed_flag_turns_the_timeline_off() {
+        let state = create_test_app_state().await;
+
+        let user = create_persisted_address(&state.db.addresses, "activity").await;
+        let router = Router::new()
+            .route("/:quan_address/activity", get(handle_get_address_activity))
+            .layer(Extension(user.clone()))
+            .with_state(state.clone());
+        let call = |router: Router| {
+            router.oneshot(
+                Request::builder()
+                    .uri(format!("/{}/activity?page=1&page_size=10", user.quan_address.0))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        // The flag is created enabled by the migrations
+        assert_eq!(call(router.clone()).await.unwrap().status(), StatusCode::OK);
+
+        state
+            .db
+            .feature_flags
+            .upsert(
+                ActivityFeedFeature::KEY,
+                &FeatureFlagInput {
+                    description: String::new(),
+                    enabled: false,
+                    rollout_percentage: 100,
+                    allowlist: vec![],
+                },
+                "admin",
+            )
+            .await
+            .unwrap();
+
+        let response = call(router).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "FEATURE_DISABLED");
+    }
+
+    #[tokio::test]
+    async fn test_export_streams_addresses_that_are_not_banned() {
+        let state = create_test_app_state().await;
+
+        let kept = create_persisted_address(&state.db.addresses, "kept").await;
+        let banned = create_persisted_address(&state.db.addresses, "banned").await;
+        state
+            .db
+            .addresses
+            .soft_delete(&banned.quan_address.0, "admin")
+            .await
+            .unwrap();
+
+        let router = Router::new()
+            .route("/export", get(handle_export_addresses))
+            .layer(Extension(crate::utils::test_db::create_mock_admin()))
+            .with_state(state);
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/export?format=csv")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/csv");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(
+            String::from_utf8(body.to_vec()).unwrap(),
+            format!("{}{},{}\n", CSV_EXPORT_HEADER, kept.quan_address.0, kept.referral_code)
+        );
+    }
 }
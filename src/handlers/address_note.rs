@@ -0,0 +1,188 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+
+use crate::{
+    db_persistence::DbError,
+    handlers::SuccessResponse,
+    http_server::AppState,
+    models::{
+        address_note::{AddressDetail, AddressNote, AddressNoteInput, AddressNoteRevision},
+        admin::Admin,
+    },
+    AppError,
+};
+
+async fn ensure_address_exists(state: &AppState, quan_address: &str) -> Result<(), AppError> {
+    state
+        .db
+        .addresses
+        .find_by_id(quan_address)
+        .await?
+        .ok_or_else(|| AppError::Database(DbError::AddressNotFound(quan_address.to_string())))?;
+
+    Ok(())
+}
+
+/// GET /addresses/:quan_address
+/// Admin detail view of an address, including its notes
+pub async fn handle_get_address_detail(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+    Path(quan_address): Path<String>,
+) -> Result<Json<SuccessResponse<AddressDetail>>, AppError> {
+    let address = state
+        .db
+        .addresses
+        .find_with_optin_and_associations_by_id(&quan_address)
+        .await?
+        .ok_or_else(|| DbError::AddressNotFound(quan_address.clone()))?;
+
+    let notes = state.db.address_notes.find_all_by_address(&quan_address).await?;
+
+    Ok(SuccessResponse::new(AddressDetail { address, notes }))
+}
+
+/// POST /addresses/:quan_address/notes
+pub async fn handle_create_address_note(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(quan_address): Path<String>,
+    Json(payload): Json<AddressNoteInput>,
+) -> Result<(StatusCode, Json<SuccessResponse<AddressNote>>), AppError> {
+    let input = payload.validated()?;
+    ensure_address_exists(&state, &quan_address).await?;
+
+    let note = state
+        .db
+        .address_notes
+        .create(&quan_address, &input, &admin.username)
+        .await?;
+
+    Ok((StatusCode::CREATED, SuccessResponse::new(note)))
+}
+
+/// PUT /addresses/:quan_address/notes/:note_id
+/// Edits a note, the previous version is kept in its history
+pub async fn handle_update_address_note(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path((quan_address, note_id)): Path<(String, i32)>,
+    Json(payload): Json<AddressNoteInput>,
+) -> Result<Json<SuccessResponse<AddressNote>>, AppError> {
+    let input = payload.validated()?;
+
+    let note = state
+        .db
+        .address_notes
+        .update(&quan_address, note_id, &input, &admin.username)
+        .await?;
+
+    Ok(SuccessResponse::new(note))
+}
+
+/// GET /addresses/:quan_address/notes/:note_id/history
+pub async fn handle_get_address_note_history(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+    Path((quan_address, note_id)): Path<(String, i32)>,
+) -> Result<Json<SuccessResponse<Vec<AddressNoteRevision>>>, AppError> {
+    let revisions = state.db.address_notes.find_revisions(&quan_address, note_id).await?;
+
+    Ok(SuccessResponse::new(revisions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        test_app_state::create_test_app_state,
+        test_db::{create_mock_admin, create_persisted_address, create_persisted_opt_in},
+    };
+    use axum::{
+        body::Body,
+        http::Request,
+        routing::{get, post},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_address_detail_includes_notes() {
+        let state = create_test_app_state().await;
+
+        let address = create_persisted_address(&state.db.addresses, "A1").await;
+        create_persisted_opt_in(&state.db.pool, &address.quan_address.0).await;
+
+        let router = Router::new()
+            .route("/:quan_address", get(handle_get_address_detail))
+            .route("/:quan_address/notes", post(handle_create_address_note))
+            .layer(Extension(create_mock_admin()))
+            .with_state(state);
+
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/{}/notes", address.quan_address.0))
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        r#"{"body":"  Asked about delayed reward  ","ticket_ref":"SUP-7"}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/{}", address.quan_address.0))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+        let data = &body_json["data"];
+
+        assert_eq!(data["address"]["quan_address"], address.quan_address.0);
+        assert_eq!(data["is_opted_in"], true);
+
+        let notes = data["notes"].as_array().unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0]["body"], "Asked about delayed reward");
+        assert_eq!(notes[0]["ticket_ref"], "SUP-7");
+        assert_eq!(notes[0]["author"], "admin_tester");
+    }
+
+    #[tokio::test]
+    async fn test_create_note_for_unknown_address() {
+        let state = create_test_app_state().await;
+
+        let router = Router::new()
+            .route("/:quan_address/notes", post(handle_create_address_note))
+            .layer(Extension(create_mock_admin()))
+            .with_state(state);
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/qz_unknown/notes")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"body":"Hello"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
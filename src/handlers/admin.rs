@@ -0,0 +1,224 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
+    Argon2,
+};
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    db_persistence::DbError,
+    handlers::{auth::require_admin_role, validation::ValidatedJson, HandlerError, SuccessResponse},
+    http_server::AppState,
+    models::admin::{Admin, AdminRole, AdminView, CreateAdminInput, UpdateAdminRoleInput},
+    AppError,
+};
+
+fn admin_not_found(id: &Uuid) -> AppError {
+    AppError::Database(DbError::RecordNotFound(format!("Admin {} not found", id)))
+}
+
+/// Admins can't lock themselves out by demoting or disabling their own account.
+fn ensure_not_self(admin: &Admin, id: &Uuid) -> Result<(), AppError> {
+    if admin.id == *id {
+        return Err(AppError::Handler(HandlerError::InvalidBody(
+            "You can't change your own account".to_string(),
+        )));
+    }
+
+    Ok(())
+}
+
+/// GET /admins
+pub async fn handle_get_admins(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+) -> Result<Json<SuccessResponse<Vec<AdminView>>>, AppError> {
+    require_admin_role(&admin, AdminRole::Superadmin)?;
+
+    let admins = state.db.admin.find_all().await?;
+
+    Ok(SuccessResponse::new(admins.into_iter().map(AdminView::from).collect()))
+}
+
+/// POST /admins
+pub async fn handle_create_admin(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    ValidatedJson(input): ValidatedJson<CreateAdminInput>,
+) -> Result<Json<SuccessResponse<AdminView>>, AppError> {
+    require_admin_role(&admin, AdminRole::Superadmin)?;
+
+    let username = input.username.trim();
+    let salt = SaltString::generate(&mut OsRng);
+    let password_hash = Argon2::default()
+        .hash_password(input.password.as_bytes(), &salt)
+        .map_err(|e| AppError::Server(format!("Failed hashing password: {}", e)))?
+        .to_string();
+
+    let created = state.db.admin.create(username, &password_hash, input.role).await?;
+    tracing::info!(
+        "Admin {} created admin {} with role {}",
+        admin.username,
+        created.username,
+        created.role.as_str()
+    );
+
+    Ok(SuccessResponse::new(created.into()))
+}
+
+/// PUT /admins/:id/role
+pub async fn handle_update_admin_role(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<UpdateAdminRoleInput>,
+) -> Result<Json<SuccessResponse<AdminView>>, AppError> {
+    require_admin_role(&admin, AdminRole::Superadmin)?;
+    ensure_not_self(&admin, &id)?;
+
+    let updated = state
+        .db
+        .admin
+        .update_role(&id, input.role)
+        .await?
+        .ok_or_else(|| admin_not_found(&id))?;
+    tracing::info!(
+        "Admin {} changed role of {} to {}",
+        admin.username,
+        updated.username,
+        updated.role.as_str()
+    );
+
+    Ok(SuccessResponse::new(updated.into()))
+}
+
+/// POST /admins/:id/disable
+/// Blocks the admin's logins and rejects the tokens they already have
+pub async fn handle_disable_admin(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<AdminView>>, AppError> {
+    require_admin_role(&admin, AdminRole::Superadmin)?;
+    ensure_not_self(&admin, &id)?;
+
+    let updated = state
+        .db
+        .admin
+        .set_disabled(&id, true)
+        .await?
+        .ok_or_else(|| admin_not_found(&id))?;
+    tracing::warn!("Admin {} disabled admin {}", admin.username, updated.username);
+
+    Ok(SuccessResponse::new(updated.into()))
+}
+
+/// POST /admins/:id/enable
+pub async fn handle_enable_admin(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<AdminView>>, AppError> {
+    require_admin_role(&admin, AdminRole::Superadmin)?;
+
+    let updated = state
+        .db
+        .admin
+        .set_disabled(&id, false)
+        .await?
+        .ok_or_else(|| admin_not_found(&id))?;
+    tracing::info!("Admin {} enabled admin {}", admin.username, updated.username);
+
+    Ok(SuccessResponse::new(updated.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::create_mock_admin};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        routing::{post, put},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    fn json_request(method: &str, uri: String, body: serde_json::Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_superadmin_manages_admins() {
+        let state = create_test_app_state().await;
+
+        let router = |admin: Admin| {
+            Router::new()
+                .route("/admins", post(handle_create_admin))
+                .route("/admins/:id/role", put(handle_update_admin_role))
+                .route("/admins/:id/disable", post(handle_disable_admin))
+                .layer(Extension(admin))
+                .with_state(state.clone())
+        };
+        let superadmin = create_mock_admin();
+
+        let response = router(superadmin.clone())
+            .oneshot(json_request(
+                "POST",
+                "/admins".to_string(),
+                serde_json::json!({ "username": "operator_1", "password": "long enough password", "role": "operator" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["data"]["role"], "operator");
+        assert!(body["data"].get("password").is_none());
+        let id: Uuid = body["data"]["id"].as_str().unwrap().parse().unwrap();
+
+        let response = router(superadmin.clone())
+            .oneshot(json_request(
+                "PUT",
+                format!("/admins/{}/role", id),
+                serde_json::json!({ "role": "viewer" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Only superadmins manage admins
+        let viewer = state.db.admin.find_by_id(&id).await.unwrap().unwrap();
+        assert_eq!(viewer.role, AdminRole::Viewer);
+        let response = router(viewer)
+            .oneshot(json_request(
+                "POST",
+                "/admins".to_string(),
+                serde_json::json!({ "username": "sneaky", "password": "long enough password", "role": "superadmin" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = router(superadmin)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/admins/{}/disable", id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.db.admin.find_by_id(&id).await.unwrap().unwrap().is_disabled());
+    }
+}
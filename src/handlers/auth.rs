@@ -1,6 +1,11 @@
 use argon2::{Argon2, PasswordHash, PasswordVerifier};
-use axum::{extract::State, http::StatusCode, response::Json, Extension};
-use chrono::Utc;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{Json, NoContent},
+    Extension,
+};
+use chrono::{Duration, Utc};
 use jsonwebtoken::{encode, EncodingKey, Header};
 use uuid::Uuid;
 
@@ -10,32 +15,53 @@ use crate::{
     http_server::{AppState, Challenge},
     models::{
         address::{Address, AddressInput},
-        admin::{Admin, AdminAuthCheckResponse, AdminClaims, AdminLoginPayload, AdminLoginResponse},
-        auth::{RequestChallengeBody, RequestChallengeResponse, TokenClaims, VerifyLoginBody, VerifyLoginResponse},
+        admin::{Admin, AdminAuthCheckResponse, AdminClaims, AdminLoginPayload, AdminLoginResponse, AdminRole},
+        auth::{
+            LogoutQuery, RefreshSessionBody, RequestChallengeBody, RequestChallengeResponse, TokenClaims,
+            VerifyLoginBody, VerifyLoginResponse,
+        },
     },
-    services::signature_service::SignatureService,
-    utils::{generate_referral_code::generate_referral_code, jwt::get_default_jwt_config},
+    services::{referral_code_service::ReferralCodeService, signature_service::SignatureService},
+    utils::jwt::{generate_refresh_token, get_default_jwt_config, hash_refresh_token},
     AppError,
 };
 use tracing::{debug, warn};
 
+/// Ended sessions are kept this long before they are deleted.
+const SESSION_RETENTION_HOURS: i64 = 24;
+
 #[derive(Debug, thiserror::Error)]
 pub enum AuthHandlerError {
     #[error("Not authorized: {0}")]
     Unauthorized(String),
+    #[error("Address {0} is banned")]
+    Banned(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+}
+
+/// Per handler guard for actions that need more than the operator access `jwt_admin_auth` grants.
+pub fn require_admin_role(admin: &Admin, role: AdminRole) -> Result<(), AppError> {
+    if admin.has_role(role) {
+        return Ok(());
+    }
+
+    Err(AppError::Handler(HandlerError::Auth(AuthHandlerError::Forbidden(
+        format!("the {} role is required", role.as_str()),
+    ))))
 }
 
 pub async fn request_challenge(
     State(state): State<AppState>,
     Json(_body): Json<RequestChallengeBody>,
-) -> Result<Json<RequestChallengeResponse>, StatusCode> {
+) -> Result<Json<RequestChallengeResponse>, AppError> {
     let temp_session_id = Uuid::new_v4().to_string();
     let challenge = Uuid::new_v4().to_string();
     let entry = Challenge {
         challenge: challenge.clone(),
         created_at: Utc::now(),
     };
-    state.challenges.write().await.insert(temp_session_id.clone(), entry);
+    state.challenges.insert(&temp_session_id, entry).await?;
     Ok(Json(RequestChallengeResponse {
         temp_session_id,
         challenge,
@@ -53,9 +79,16 @@ pub async fn verify_login(
         address = %body.address,
         signature_len = sig_len,
         public_key_len = pk_len,
+        scheme = ?body.scheme,
         "verify_login: received payload"
     );
-    let Some(chal) = state.challenges.read().await.get(&body.temp_session_id).cloned() else {
+    if !body.scheme.derives_quan_address() {
+        return Err(AppError::Handler(HandlerError::InvalidBody(format!(
+            "{:?} signatures can't be used to log in",
+            body.scheme
+        ))));
+    }
+    let Some(chal) = state.challenges.get(&body.temp_session_id).await? else {
         return Err(AppError::Handler(HandlerError::Auth(AuthHandlerError::Unauthorized(
             format!("no challenge with key {} found", &body.temp_session_id),
         ))));
@@ -66,7 +99,7 @@ pub async fn verify_login(
     );
     debug!(message = %message, message_len = message.len(), message_hex = %hex::encode(message.as_bytes()), "verify_login: constructed message");
 
-    let addr_res = SignatureService::verify_address(&body.public_key, &body.address);
+    let addr_res = SignatureService::verify_address(body.scheme, &body.public_key, &body.address);
     if let Err(e) = &addr_res {
         warn!(error = %e, "verify_login: verify_address error");
     }
@@ -80,7 +113,7 @@ pub async fn verify_login(
             "address verification failed".to_string(),
         ))));
     }
-    let sig_res = SignatureService::verify_message(message.as_bytes(), &body.signature, &body.public_key);
+    let sig_res = SignatureService::verify_message(body.scheme, message.as_bytes(), &body.signature, &body.public_key);
     if let Err(e) = &sig_res {
         warn!(error = %e, "verify_login: verify_message error");
     }
@@ -96,11 +129,18 @@ pub async fn verify_login(
         ))));
     }
 
+    if state.db.addresses.is_banned(&body.address).await? {
+        warn!(address = %body.address, "verify_login: banned address");
+        return Err(AppError::Handler(HandlerError::Auth(AuthHandlerError::Banned(
+            body.address,
+        ))));
+    }
+
     if state.db.addresses.find_by_id(&body.address).await?.is_none() {
         tracing::info!("Address is not saved yet, proceed to saving...");
 
         tracing::debug!("Generating address referral code...");
-        let referral_code = generate_referral_code(body.address.clone()).await?;
+        let referral_code = ReferralCodeService::generate_unique(&state.db.addresses, &body.address).await?;
 
         tracing::debug!("Creating address struct...");
         let address = Address::new(AddressInput {
@@ -112,22 +152,109 @@ pub async fn verify_login(
         state.db.addresses.create(&address).await?;
     }
 
-    let (iat, exp) = get_default_jwt_config(&state);
+    let cutoff = Utc::now() - Duration::hours(SESSION_RETENTION_HOURS);
+    if let Err(e) = state.db.sessions.delete_ended_before(cutoff).await {
+        warn!(error = %e, "verify_login: failed to clean up ended sessions");
+    }
+
+    let refresh_token = generate_refresh_token();
+    let expires_at = Utc::now() + state.settings.current().get_refresh_token_expiration();
+    let session = state
+        .db
+        .sessions
+        .create(&body.address, &hash_refresh_token(&refresh_token), expires_at)
+        .await?;
+    let access_token = issue_access_token(&state, body.address, &session.id).await?;
+
+    state.challenges.remove(&body.temp_session_id).await?;
+    Ok(Json(VerifyLoginResponse {
+        access_token,
+        refresh_token,
+    }))
+}
+
+async fn issue_access_token(state: &AppState, quan_address: String, session_id: &Uuid) -> Result<String, AppError> {
+    let secret = state.secrets.current(&state.config.jwt.secret).await?;
+    let (iat, exp) = get_default_jwt_config(state);
     let claims: TokenClaims = TokenClaims {
-        sub: body.address,
+        sub: quan_address,
         iat,
         exp,
+        sid: Some(session_id.to_string()),
     };
 
-    let access_token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(state.config.jwt.secret.as_ref()),
-    )
-    .unwrap();
+    Ok(encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_ref())).unwrap())
+}
+
+/// POST /auth/refresh
+/// Trades a refresh token for a new access token and a new refresh token
+pub async fn refresh_session(
+    State(state): State<AppState>,
+    Json(body): Json<RefreshSessionBody>,
+) -> Result<Json<VerifyLoginResponse>, AppError> {
+    let refresh_token = generate_refresh_token();
+    let expires_at = Utc::now() + state.settings.current().get_refresh_token_expiration();
+
+    let Some(session) = state
+        .db
+        .sessions
+        .rotate(
+            &hash_refresh_token(&body.refresh_token),
+            &hash_refresh_token(&refresh_token),
+            expires_at,
+        )
+        .await?
+    else {
+        return Err(AppError::Handler(HandlerError::Auth(AuthHandlerError::Unauthorized(
+            "invalid or expired refresh token".to_string(),
+        ))));
+    };
+
+    if state.db.addresses.is_banned(&session.quan_address).await? {
+        warn!(address = %session.quan_address, "refresh_session: banned address");
+        state.db.sessions.revoke(&session.id).await?;
+        return Err(AppError::Handler(HandlerError::Auth(AuthHandlerError::Banned(
+            session.quan_address,
+        ))));
+    }
+
+    let access_token = issue_access_token(&state, session.quan_address, &session.id).await?;
+    Ok(Json(VerifyLoginResponse {
+        access_token,
+        refresh_token,
+    }))
+}
+
+/// POST /auth/logout
+/// Ends the session of the token, or every session of the address with `?all=true`
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(address): Extension<Address>,
+    Extension(claims): Extension<TokenClaims>,
+    Query(query): Query<LogoutQuery>,
+) -> Result<NoContent, AppError> {
+    if query.all {
+        let revoked = state
+            .db
+            .sessions
+            .revoke_all_for_address(&address.quan_address.0)
+            .await?;
+        tracing::info!("Ended {} sessions of {}", revoked, address.quan_address.0);
+        return Ok(NoContent);
+    }
+
+    match claims.sid.as_deref().map(Uuid::parse_str) {
+        Some(Ok(session_id)) => {
+            state.db.sessions.revoke(&session_id).await?;
+        }
+        _ => {
+            return Err(AppError::Handler(HandlerError::InvalidBody(
+                "Token isn't bound to a session".to_string(),
+            )))
+        }
+    }
 
-    state.challenges.write().await.remove(&body.temp_session_id);
-    Ok(Json(VerifyLoginResponse { access_token }))
+    Ok(NoContent)
 }
 
 pub async fn auth_me(Extension(address): Extension<Address>) -> Result<Json<SuccessResponse<Address>>, StatusCode> {
@@ -161,6 +288,12 @@ pub async fn handle_admin_login(
             ))
         })?;
 
+    if admin.is_disabled() {
+        return Err(AppError::Handler(HandlerError::Auth(AuthHandlerError::Unauthorized(
+            "Admin account is disabled".to_string(),
+        ))));
+    }
+
     let (iat, exp) = get_default_jwt_config(&state);
     let claims: AdminClaims = AdminClaims {
         sub: admin.id.to_string(),
@@ -170,12 +303,8 @@ pub async fn handle_admin_login(
 
     tracing::info!("Generating admin token...");
 
-    let access_token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(state.config.jwt.admin_secret.as_ref()),
-    )
-    .unwrap();
+    let secret = state.secrets.current(&state.config.jwt.admin_secret).await?;
+    let access_token = encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_ref())).unwrap();
 
     Ok(Json(AdminLoginResponse { access_token }))
 }
@@ -186,12 +315,19 @@ pub async fn auth_admin(
     Ok(SuccessResponse::new(AdminAuthCheckResponse {
         id: admin.id,
         username: admin.username,
+        role: admin.role,
     }))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{routes::auth::auth_routes, utils::test_app_state::create_test_app_state};
+    use crate::{
+        routes::auth::auth_routes,
+        utils::{
+            generate_referral_code::generate_referral_code, test_app_state::create_test_app_state,
+            test_db::AddressBuilder,
+        },
+    };
     use axum::{body::Body, http};
     use qp_rusty_crystals_dilithium::SensitiveBytes32;
     use sp_core::crypto::{self, Ss58AddressFormat, Ss58Codec};
@@ -257,7 +393,62 @@ mod tests {
         let bytes = axum::body::to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
         let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
         let access_token = v["access_token"].as_str().unwrap();
+        let refresh_token = v["refresh_token"].as_str().unwrap();
+
+        let resp = app
+            .clone()
+            .oneshot(
+                http::Request::builder()
+                    .method("GET")
+                    .uri("/auth/me")
+                    .header(http::header::AUTHORIZATION, format!("Bearer {}", access_token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let refresh = |refresh_token: String| {
+            app.clone().oneshot(
+                http::Request::builder()
+                    .method("POST")
+                    .uri("/auth/refresh")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "refresh_token": refresh_token }).to_string(),
+                    ))
+                    .unwrap(),
+            )
+        };
+        let resp = refresh(refresh_token.to_string()).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+        let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let refreshed_access_token = v["access_token"].as_str().unwrap().to_string();
+        let rotated_refresh_token = v["refresh_token"].as_str().unwrap().to_string();
+        // Refresh tokens are single use
+        let resp = refresh(refresh_token.to_string()).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::UNAUTHORIZED);
 
+        let resp = app
+            .clone()
+            .oneshot(
+                http::Request::builder()
+                    .method("POST")
+                    .uri("/auth/logout")
+                    .header(
+                        http::header::AUTHORIZATION,
+                        format!("Bearer {}", refreshed_access_token),
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+
+        // Logging out ends the session for every token issued for it
         let resp = app
             .clone()
             .oneshot(
@@ -270,6 +461,73 @@ mod tests {
             )
             .await
             .unwrap();
+        assert_eq!(resp.status(), http::StatusCode::UNAUTHORIZED);
+        let resp = refresh(rotated_refresh_token).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn login_with_code_held_by_banned_address() {
+        crypto::set_default_ss58_version(Ss58AddressFormat::custom(189));
+        let state = create_test_app_state().await;
+        let app = auth_routes(state.clone()).with_state(state.clone());
+
+        let kp = qp_rusty_crystals_dilithium::ml_dsa_87::Keypair::generate(SensitiveBytes32::from(&mut [5u8; 32]));
+        let addr = quantus_cli::qp_dilithium_crypto::types::DilithiumPublic::try_from(kp.public.to_bytes().as_slice())
+            .unwrap()
+            .into_account()
+            .to_ss58check();
+
+        // A banned address already holds the code the new address would get
+        let code = generate_referral_code(addr.clone()).await.unwrap().to_lowercase();
+        let banned = AddressBuilder::new("banned_holder")
+            .referral_code(&code)
+            .create(&state.db.addresses)
+            .await;
+        state
+            .db
+            .addresses
+            .soft_delete(&banned.quan_address.0, "admin")
+            .await
+            .unwrap();
+
+        let resp = app
+            .clone()
+            .oneshot(
+                http::Request::builder()
+                    .method("POST")
+                    .uri("/auth/request-challenge")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+        let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let challenge = v["challenge"].as_str().unwrap();
+        let msg = format!("taskmaster:login:1|challenge={}|address={}", challenge, addr);
+
+        let verify_payload = serde_json::json!({
+            "temp_session_id": v["temp_session_id"],
+            "address": addr,
+            "public_key": hex::encode(kp.public.to_bytes()),
+            "signature": hex::encode(kp.sign(msg.as_bytes(), None, Some([7u8; 32])).unwrap()),
+        });
+        let resp = app
+            .oneshot(
+                http::Request::builder()
+                    .method("POST")
+                    .uri("/auth/verify")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(serde_json::to_vec(&verify_payload).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
         assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let created = state.db.addresses.find_by_id(&addr).await.unwrap().unwrap();
+        assert_eq!(created.referral_code, format!("{}-2", code));
     }
 }
@@ -0,0 +1,80 @@
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use tokio::sync::{broadcast::error::RecvError, mpsc};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+
+use crate::http_server::AppState;
+
+/// Sent instead of the events a slow client missed, it should refetch what it displays.
+const RESYNC_EVENT: &str = "resync";
+/// Events waiting for a slow client before it starts missing them.
+const CLIENT_BUFFER: usize = 16;
+
+/// GET /events
+/// Server-Sent Events stream of raid quest and leaderboard changes. The stream ends on shutdown so open
+/// dashboards don't hold up the graceful shutdown
+pub async fn handle_live_events(State(state): State<AppState>) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let mut events = state.db.events.subscribe();
+    let shutdown = state.shutdown.clone();
+    let (sender, receiver) = mpsc::channel(CLIENT_BUFFER);
+
+    tokio::spawn(async move {
+        loop {
+            let event = tokio::select! {
+                _ = shutdown.cancelled() => return,
+                // The client went away
+                _ = sender.closed() => return,
+                event = events.recv() => event,
+            };
+            let event = match event {
+                Ok(event) => Event::default().event(event.name()).json_data(&event),
+                Err(RecvError::Lagged(missed)) => Ok(Event::default().event(RESYNC_EVENT).data(missed.to_string())),
+                Err(RecvError::Closed) => return,
+            };
+            tokio::select! {
+                _ = shutdown.cancelled() => return,
+                sent = sender.send(event) => {
+                    if sent.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+
+    Sse::new(ReceiverStream::new(receiver)).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_app_state::create_test_app_state;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_live_events_end_on_shutdown() {
+        let state = create_test_app_state().await;
+        let shutdown = state.shutdown.clone();
+        let router = Router::new()
+            .route("/events", get(handle_live_events))
+            .with_state(state);
+
+        let response = router
+            .oneshot(Request::builder().uri("/events").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        shutdown.cancel();
+
+        let body = tokio::time::timeout(
+            Duration::from_secs(5),
+            axum::body::to_bytes(response.into_body(), usize::MAX),
+        )
+        .await
+        .expect("stream should end on shutdown");
+        assert!(body.is_ok());
+    }
+}
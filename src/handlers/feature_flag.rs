@@ -0,0 +1,142 @@
+use axum::{
+    extract::{Path, State},
+    response::NoContent,
+    Extension, Json,
+};
+
+use crate::{
+    handlers::SuccessResponse,
+    http_server::AppState,
+    models::{
+        address::Address,
+        admin::Admin,
+        feature_flag::{FeatureFlag, FeatureFlagInput},
+    },
+    AppError,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FeatureFlagHandlerError {
+    /// Raised by the `RequireFeature` guard.
+    #[error("Feature '{0}' is not enabled")]
+    Disabled(String),
+}
+
+/// GET /feature-flags
+pub async fn handle_get_feature_flags(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+) -> Result<Json<SuccessResponse<Vec<FeatureFlag>>>, AppError> {
+    let flags = state.db.feature_flags.find_all().await?;
+
+    Ok(SuccessResponse::new(flags))
+}
+
+/// PUT /feature-flags/:key
+/// Creates or replaces a flag
+pub async fn handle_put_feature_flag(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(key): Path<String>,
+    Json(input): Json<FeatureFlagInput>,
+) -> Result<Json<SuccessResponse<FeatureFlag>>, AppError> {
+    input.validate(&key)?;
+
+    let flag = state.db.feature_flags.upsert(&key, &input, &admin.username).await?;
+    tracing::info!(
+        "Feature flag '{}' set by {} (enabled: {}, rollout: {}%)",
+        key,
+        admin.username,
+        flag.enabled,
+        flag.rollout_percentage
+    );
+
+    Ok(SuccessResponse::new(flag))
+}
+
+/// DELETE /feature-flags/:key
+pub async fn handle_delete_feature_flag(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(key): Path<String>,
+) -> Result<NoContent, AppError> {
+    state.db.feature_flags.delete(&key).await?;
+    tracing::info!("Feature flag '{}' deleted by {}", key, admin.username);
+
+    Ok(NoContent)
+}
+
+/// GET /feature-flags/me
+/// Keys of the flags enabled for the authenticated address, so clients can hide unavailable features
+pub async fn handle_get_my_feature_flags(
+    State(state): State<AppState>,
+    Extension(user): Extension<Address>,
+) -> Result<Json<SuccessResponse<Vec<String>>>, AppError> {
+    let enabled = state
+        .db
+        .feature_flags
+        .find_all()
+        .await?
+        .into_iter()
+        .filter(|flag| flag.is_enabled_for(&user.quan_address.0))
+        .map(|flag| flag.key)
+        .collect();
+
+    Ok(SuccessResponse::new(enabled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::create_mock_admin};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        routing::put,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_put_feature_flag_validates_input() {
+        let state = create_test_app_state().await;
+
+        let router = Router::new()
+            .route("/feature-flags/:key", put(handle_put_feature_flag))
+            .layer(Extension(create_mock_admin()))
+            .with_state(state.clone());
+
+        let put_flag = |router: Router, key: &'static str, body: &'static str| async move {
+            router
+                .oneshot(
+                    Request::builder()
+                        .method("PUT")
+                        .uri(format!("/feature-flags/{}", key))
+                        .header("content-type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+                .status()
+        };
+
+        let status = put_flag(router.clone(), "claims", r#"{"enabled":true,"rollout_percentage":150}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let status = put_flag(
+            router.clone(),
+            "Bad%20Key",
+            r#"{"enabled":true,"rollout_percentage":10}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let status = put_flag(router, "claims", r#"{"enabled":true,"rollout_percentage":10}"#).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let flag = state.db.feature_flags.find_by_key("claims").await.unwrap().unwrap();
+        assert_eq!(flag.rollout_percentage, 10);
+        assert_eq!(flag.updated_by, "admin_tester");
+    }
+}
@@ -0,0 +1,143 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+
+use crate::{
+    handlers::{validation::ValidatedJson, SuccessResponse},
+    http_server::AppState,
+    models::{
+        admin::Admin,
+        fraud_rule::{FraudFlag, FraudFlagQuery, FraudRule, FraudRuleEvaluation, FraudRuleInput, FraudRuleVersion},
+    },
+    services::fraud_rules::FraudRuleService,
+    AppError,
+};
+
+/// GET /fraud-rules
+/// All rules with their hit statistics
+pub async fn handle_get_fraud_rules(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+) -> Result<Json<SuccessResponse<Vec<FraudRule>>>, AppError> {
+    let rules = state.db.fraud_rules.find_all().await?;
+
+    Ok(SuccessResponse::new(rules))
+}
+
+/// POST /fraud-rules
+pub async fn handle_create_fraud_rule(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    ValidatedJson(input): ValidatedJson<FraudRuleInput>,
+) -> Result<(StatusCode, Json<SuccessResponse<FraudRule>>), AppError> {
+    let rule = state.db.fraud_rules.create(&input, &admin.username).await?;
+    tracing::info!("Fraud rule '{}' created by {}", rule.name, admin.username);
+
+    Ok((StatusCode::CREATED, SuccessResponse::new(rule)))
+}
+
+/// PUT /fraud-rules/:id
+/// Replaces a rule, creating a new version
+pub async fn handle_update_fraud_rule(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(id): Path<i32>,
+    ValidatedJson(input): ValidatedJson<FraudRuleInput>,
+) -> Result<Json<SuccessResponse<FraudRule>>, AppError> {
+    let rule = state.db.fraud_rules.update(id, &input, &admin.username).await?;
+    tracing::info!(
+        "Fraud rule '{}' updated to version {} by {}",
+        rule.name,
+        rule.version,
+        admin.username
+    );
+
+    Ok(SuccessResponse::new(rule))
+}
+
+/// GET /fraud-rules/:id/versions
+pub async fn handle_get_fraud_rule_versions(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+    Path(id): Path<i32>,
+) -> Result<Json<SuccessResponse<Vec<FraudRuleVersion>>>, AppError> {
+    let versions = state.db.fraud_rules.find_versions(id).await?;
+
+    Ok(SuccessResponse::new(versions))
+}
+
+/// POST /fraud-rules/evaluate
+/// Runs every enabled rule now and reports the new hits per rule
+pub async fn handle_evaluate_fraud_rules(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+) -> Result<Json<SuccessResponse<Vec<FraudRuleEvaluation>>>, AppError> {
+    let evaluations = FraudRuleService::new(state.db.fraud_rules.clone())
+        .evaluate_enabled()
+        .await?;
+    tracing::info!("Fraud rules evaluated by {}", admin.username);
+
+    Ok(SuccessResponse::new(evaluations))
+}
+
+/// GET /fraud-flags?rule_id=&quan_address=
+pub async fn handle_get_fraud_flags(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+    Query(query): Query<FraudFlagQuery>,
+) -> Result<Json<SuccessResponse<Vec<FraudFlag>>>, AppError> {
+    let flags = state.db.fraud_rules.find_flags(&query).await?;
+
+    Ok(SuccessResponse::new(flags))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::create_mock_admin};
+    use axum::{body::Body, http::Request, routing::post, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_create_fraud_rule_validates_input() {
+        let state = create_test_app_state().await;
+
+        let router = Router::new()
+            .route("/fraud-rules", post(handle_create_fraud_rule))
+            .layer(Extension(create_mock_admin()))
+            .with_state(state.clone());
+
+        let create_rule = |router: Router, body: &'static str| async move {
+            router
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/fraud-rules")
+                        .header("content-type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+                .status()
+        };
+
+        let status = create_rule(
+            router.clone(),
+            r#"{"name":"velocity","definition":{"type":"submission_velocity","max_submissions":0,"window_minutes":10},"action":"flag"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let body = r#"{"name":"velocity","definition":{"type":"submission_velocity","max_submissions":5,"window_minutes":10},"action":"quarantine"}"#;
+        assert_eq!(create_rule(router.clone(), body).await, StatusCode::CREATED);
+        assert_eq!(create_rule(router, body).await, StatusCode::CONFLICT);
+
+        let rules = state.db.fraud_rules.find_all().await.unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].version, 1);
+        assert_eq!(rules[0].updated_by, "admin_tester");
+    }
+}
@@ -0,0 +1,53 @@
+use axum::{extract::State, Extension, Json};
+
+use crate::{
+    handlers::SuccessResponse,
+    http_server::AppState,
+    models::{
+        admin::Admin,
+        maintenance::{PauseInput, PauseState},
+    },
+    AppError,
+};
+
+/// GET /admin/pause
+pub async fn handle_get_pause_state(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+) -> Result<Json<SuccessResponse<PauseState>>, AppError> {
+    let pause = state.db.maintenance.find_pause_state().await?;
+
+    Ok(SuccessResponse::new(pause))
+}
+
+/// POST /admin/pause
+/// Suspends background jobs, e.g. during chain upgrades. The API keeps running
+pub async fn handle_pause(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Json(input): Json<PauseInput>,
+) -> Result<Json<SuccessResponse<PauseState>>, AppError> {
+    let pause = state
+        .db
+        .maintenance
+        .set_paused(true, input.reason.as_deref(), &admin.username)
+        .await?;
+    tracing::warn!(
+        "Background jobs paused by {} ({})",
+        admin.username,
+        pause.reason.as_deref().unwrap_or("no reason given")
+    );
+
+    Ok(SuccessResponse::new(pause))
+}
+
+/// POST /admin/resume
+pub async fn handle_resume(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+) -> Result<Json<SuccessResponse<PauseState>>, AppError> {
+    let pause = state.db.maintenance.set_paused(false, None, &admin.username).await?;
+    tracing::info!("Background jobs resumed by {}", admin.username);
+
+    Ok(SuccessResponse::new(pause))
+}
@@ -3,19 +3,35 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 use crate::{
-    handlers::{auth::AuthHandlerError, referral::ReferralHandlerError},
-    AppError,
+    handlers::{
+        auth::AuthHandlerError, feature_flag::FeatureFlagHandlerError, raid_team::RaidTeamHandlerError,
+        referral::ReferralHandlerError,
+    },
+    middlewares::idempotency::IdempotencyError,
+    models::validation::{Validate, ValidationErrors},
 };
 
 pub mod address;
+pub mod address_note;
+pub mod admin;
 pub mod auth;
 pub mod config;
+pub mod events;
 pub mod exchange_rate;
+pub mod feature_flag;
+pub mod fraud_rule;
+pub mod maintenance;
+pub mod opt_in_stat;
+pub mod program;
 pub mod raid_quest;
+pub mod raid_team;
 pub mod referral;
 pub mod relevant_tweet;
 pub mod risk_checker;
+pub mod setting;
+pub mod transfer;
 pub mod tweet_author;
+pub mod validation;
 
 #[derive(Debug, thiserror::Error)]
 pub enum HandlerError {
@@ -23,7 +39,18 @@ pub enum HandlerError {
     Referral(#[from] ReferralHandlerError),
     #[error("Auth handler error")]
     Auth(#[from] AuthHandlerError),
+    #[error("Raid team handler error")]
+    RaidTeam(#[from] RaidTeamHandlerError),
+    #[error("Feature flag handler error")]
+    FeatureFlag(#[from] FeatureFlagHandlerError),
+    #[error("Idempotency error")]
+    Idempotency(#[from] IdempotencyError),
 
+    #[error("{0}")]
+    RateLimited(String),
+    /// An upstream service is failing and calls to it are on hold.
+    #[error("{0}")]
+    Unavailable(String),
     #[error("{0}")]
     QueryParams(String),
     #[error("Invalid body: {0}")]
@@ -40,12 +67,6 @@ impl<T> SuccessResponse<T> {
     }
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub status: &'static str,
-    pub message: String,
-}
-
 #[derive(Debug, Serialize)]
 pub struct PaginationMetadata {
     pub page: u32,
@@ -103,20 +124,13 @@ fn default_sort_direction() -> SortDirection {
     SortDirection::Desc
 }
 
-pub fn validate_pagination_query(page: u32, page_size: u32) -> Result<(), AppError> {
-    if page < 1 {
-        return Err(AppError::Handler(HandlerError::QueryParams(
-            "Page query params must not be less than 1".to_string(),
-        )));
-    }
-
-    if page_size < 1 {
-        return Err(AppError::Handler(HandlerError::QueryParams(
-            "Page size query params must not be less than 1".to_string(),
-        )));
+impl<T> Validate for ListQueryParams<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.check(self.page >= 1, "page", "must not be less than 1");
+        errors.check(self.page_size >= 1, "page_size", "must not be less than 1");
+        errors.into_result()
     }
-
-    Ok(())
 }
 
 pub(crate) fn calculate_total_pages(page_size: u32, total_items: u32) -> u32 {
@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+
+use crate::{
+    handlers::{HandlerError, SuccessResponse},
+    http_server::AppState,
+    models::{
+        admin::Admin,
+        opt_in_stat::{OptInStats, OptInStatsQuery},
+    },
+    AppError,
+};
+
+/// Referral sources beyond this are left out of the breakdown, the long tail isn't useful in reviews.
+const MAX_REFERRAL_SOURCES: u32 = 100;
+
+/// GET /opt-ins/stats?from=YYYY-MM-DD&to=YYYY-MM-DD
+/// Opt-in counts by day and by referral source, read from the pre-aggregated stats table
+pub async fn handle_get_opt_in_stats(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+    Query(query): Query<OptInStatsQuery>,
+) -> Result<Json<SuccessResponse<OptInStats>>, AppError> {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(AppError::Handler(HandlerError::QueryParams(
+                "'from' must not be after 'to'".to_string(),
+            )));
+        }
+    }
+
+    let by_day = state.db.opt_in_stats.find_by_day(query.from, query.to).await?;
+    let by_referral_source = state
+        .db
+        .opt_in_stats
+        .find_by_referral_source(query.from, query.to, MAX_REFERRAL_SOURCES)
+        .await?;
+
+    Ok(SuccessResponse::new(OptInStats {
+        from: query.from,
+        to: query.to,
+        total: by_day.iter().map(|day| day.opt_ins).sum(),
+        by_day,
+        by_referral_source,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        test_app_state::create_test_app_state,
+        test_db::{create_mock_admin, create_persisted_address, create_persisted_opt_in},
+    };
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_handle_get_opt_in_stats() {
+        let state = create_test_app_state().await;
+
+        let address = create_persisted_address(&state.db.addresses, "stats").await;
+        create_persisted_opt_in(&state.db.pool, &address.quan_address.0).await;
+
+        let router = Router::new()
+            .route("/opt-ins/stats", get(handle_get_opt_in_stats))
+            .layer(Extension(create_mock_admin()))
+            .with_state(state);
+
+        let response = router
+            .clone()
+            .oneshot(Request::builder().uri("/opt-ins/stats").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert_eq!(body_json["data"]["total"], 1);
+        assert_eq!(body_json["data"]["by_referral_source"][0]["referral_source"], "direct");
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/opt-ins/stats?from=2025-02-01&to=2025-01-01")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+    }
+}
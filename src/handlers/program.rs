@@ -0,0 +1,113 @@
+use axum::{extract::State, Json};
+
+use crate::{
+    handlers::{
+        raid_team::{DEFAULT_LEADERBOARD_LIMIT, MAX_LEADERBOARD_LIMIT},
+        SuccessResponse,
+    },
+    http_server::AppState,
+    models::{
+        program::{ActiveRaidScoring, ProgramRules, RaidRules, ReferralCodeRules, SessionRules},
+        raid_quest::RaidScoringRules,
+        raid_team::{TeamScoreNormalization, TEAM_NAME_MAX_LEN},
+    },
+    services::referral_code_service::{VANITY_CODE_MAX_LEN, VANITY_CODE_MIN_LEN},
+    AppError,
+};
+
+/// GET /program/rules
+/// Built from the effective settings and the active raids on every call so it reflects runtime overrides and
+/// scoring changes
+pub async fn handle_get_program_rules(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<ProgramRules>>, AppError> {
+    let config = state.settings.current();
+    let active_raids = state
+        .db
+        .raid_quests
+        .find_all_active()
+        .await?
+        .into_iter()
+        .map(|raid| ActiveRaidScoring {
+            raid_id: raid.id,
+            name: raid.name,
+            scoring: raid.scoring,
+        })
+        .collect();
+
+    Ok(SuccessResponse::new(ProgramRules {
+        referral_codes: ReferralCodeRules {
+            vanity_min_length: VANITY_CODE_MIN_LEN,
+            vanity_max_length: VANITY_CODE_MAX_LEN,
+            reserved_prefixes: config.referral_codes.reserved_prefixes.clone(),
+        },
+        raids: RaidRules {
+            active_raids,
+            default_scoring: RaidScoringRules::default(),
+            team_score_normalizations: TeamScoreNormalization::ALL.to_vec(),
+            default_team_score_normalization: TeamScoreNormalization::default(),
+            team_name_max_length: TEAM_NAME_MAX_LEN,
+            team_leaderboard_default_limit: DEFAULT_LEADERBOARD_LIMIT,
+            team_leaderboard_max_limit: MAX_LEADERBOARD_LIMIT,
+        },
+        session: SessionRules {
+            token_lifetime_hours: config.jwt.exp_in_hours,
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::RaidBuilder};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+        Router,
+    };
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_rules_reflect_setting_overrides() {
+        let state = create_test_app_state().await;
+
+        state
+            .settings
+            .set("referral_codes.reserved_prefixes", json!(["partner"]), "admin")
+            .await
+            .unwrap();
+        let raid_id = RaidBuilder::new("Weighted")
+            .scoring(RaidScoringRules {
+                like_weight: 2.0,
+                ..Default::default()
+            })
+            .create(&state.db)
+            .await;
+
+        let router = Router::new()
+            .route("/program/rules", get(handle_get_program_rules))
+            .with_state(state);
+
+        let response = router
+            .oneshot(Request::builder().uri("/program/rules").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+        let data = &body_json["data"];
+
+        assert_eq!(data["referral_codes"]["reserved_prefixes"], json!(["partner"]));
+        assert_eq!(data["raids"]["default_team_score_normalization"], "none");
+        assert_eq!(
+            data["raids"]["team_score_normalizations"],
+            json!(["none", "per_member", "sqrt_members"])
+        );
+        assert_eq!(data["raids"]["active_raids"][0]["raid_id"], raid_id);
+        assert_eq!(data["raids"]["active_raids"][0]["scoring"]["like_weight"], 2.0);
+        assert_eq!(data["raids"]["default_scoring"]["impression_weight"], 1.0);
+    }
+}
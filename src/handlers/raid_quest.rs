@@ -5,14 +5,19 @@ use axum::{
 };
 
 use crate::{
+    db_persistence::DbError,
     handlers::{
-        calculate_total_pages, validate_pagination_query, ListQueryParams, PaginatedResponse, PaginationMetadata,
+        calculate_total_pages,
+        validation::{ValidatedJson, ValidatedQuery},
+        ListQueryParams, PaginatedResponse, PaginationMetadata,
     },
     http_server::AppState,
     models::{
         admin::Admin,
-        raid_quest::{CreateRaidQuest, RaidQuest, RaidQuestFilter, RaidQuestSortColumn},
+        raid_payout::{MarkRaidPayoutsPaidInput, RaidPayout, RaidPayoutShare},
+        raid_quest::{CreateRaidQuest, RaidQuest, RaidQuestFilter, RaidQuestSortColumn, RaidScoringRules},
     },
+    services::{event_bus::RaidQuestChange, raid_payout::RaidPayoutService},
     AppError,
 };
 
@@ -21,7 +26,7 @@ use super::SuccessResponse;
 pub async fn handle_create_raid(
     State(state): State<AppState>,
     Extension(_admin): Extension<Admin>,
-    Json(payload): Json<CreateRaidQuest>,
+    ValidatedJson(payload): ValidatedJson<CreateRaidQuest>,
 ) -> Result<Json<SuccessResponse<i32>>, AppError> {
     tracing::info!("Admin creating new raid: {}", payload.name);
 
@@ -37,11 +42,78 @@ pub async fn handle_finish_raid(
 ) -> Result<NoContent, AppError> {
     tracing::info!("Admin finishing raid id: {}", id);
 
-    state.db.raid_quests.finish(id).await?;
+    let mut uow = state.db.begin().await?;
+    payout_service(&state).finish(uow.conn(), id).await?;
+    uow.commit().await?;
+    state.db.raid_quests.publish(id, RaidQuestChange::Finished);
 
     Ok(NoContent)
 }
 
+fn payout_service(state: &AppState) -> RaidPayoutService {
+    RaidPayoutService::new(
+        state.db.raid_payouts.clone(),
+        state.settings.current().raid_payout.clone(),
+    )
+}
+
+async fn ensure_raid_exists(state: &AppState, id: i32) -> Result<(), AppError> {
+    state
+        .db
+        .raid_quests
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::Database(DbError::RecordNotFound(format!("Raid Quest {} not found", id))))?;
+
+    Ok(())
+}
+
+/// GET /raid-quests/:raid_id/payouts/preview
+/// Payouts the raid would get if it was finished now
+pub async fn handle_preview_raid_payouts(
+    State(state): State<AppState>,
+    Extension(_admin): Extension<Admin>,
+    Path(id): Path<i32>,
+) -> Result<Json<SuccessResponse<Vec<RaidPayoutShare>>>, AppError> {
+    ensure_raid_exists(&state, id).await?;
+
+    let shares = payout_service(&state).preview(id).await?;
+
+    Ok(SuccessResponse::new(shares))
+}
+
+/// GET /raid-quests/:raid_id/payouts
+/// Payouts recorded when the raid was finished
+pub async fn handle_get_raid_payouts(
+    State(state): State<AppState>,
+    Extension(_admin): Extension<Admin>,
+    Path(id): Path<i32>,
+) -> Result<Json<SuccessResponse<Vec<RaidPayout>>>, AppError> {
+    ensure_raid_exists(&state, id).await?;
+
+    let payouts = state.db.raid_payouts.find_by_raid(id).await?;
+
+    Ok(SuccessResponse::new(payouts))
+}
+
+/// POST /raid-quests/:raid_id/payouts/paid
+/// Marks payouts of the raid as paid, returns how many were updated
+pub async fn handle_mark_raid_payouts_paid(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(id): Path<i32>,
+    Json(input): Json<MarkRaidPayoutsPaidInput>,
+) -> Result<Json<SuccessResponse<u64>>, AppError> {
+    let updated = state
+        .db
+        .raid_payouts
+        .mark_paid(id, &input.payout_ids, &admin.username)
+        .await?;
+    tracing::info!("{} payouts of raid {} marked paid by {}", updated, id, admin.username);
+
+    Ok(SuccessResponse::new(updated))
+}
+
 pub async fn handle_revert_to_active_raid(
     State(state): State<AppState>,
     Extension(_admin): Extension<Admin>,
@@ -49,7 +121,10 @@ pub async fn handle_revert_to_active_raid(
 ) -> Result<NoContent, AppError> {
     tracing::info!("Admin reverting to active raid id: {}", id);
 
-    state.db.raid_quests.make_active(id).await?;
+    let mut uow = state.db.begin().await?;
+    payout_service(&state).reactivate(uow.conn(), id).await?;
+    uow.commit().await?;
+    state.db.raid_quests.publish(id, RaidQuestChange::Reactivated);
 
     Ok(NoContent)
 }
@@ -66,13 +141,34 @@ pub async fn handle_delete_raid(
     Ok(NoContent)
 }
 
+/// PUT /raid-quests/:raid_id/scoring
+/// Replaces the scoring rules of a raid, leaderboards use them right away
+pub async fn handle_update_raid_scoring(
+    State(state): State<AppState>,
+    Extension(_admin): Extension<Admin>,
+    Path(id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<RaidScoringRules>,
+) -> Result<NoContent, AppError> {
+    tracing::info!("Admin updating scoring rules of raid id: {}", id);
+
+    state.db.raid_quests.update_scoring(id, &payload).await?;
+
+    Ok(NoContent)
+}
+
+pub async fn handle_get_active_raid_quests(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<RaidQuest>>>, AppError> {
+    let raid_quests = state.db.raid_quests.find_all_active().await?;
+
+    Ok(SuccessResponse::new(raid_quests))
+}
+
 pub async fn handle_get_raid_quests(
     State(state): State<AppState>,
-    Query(params): Query<ListQueryParams<RaidQuestSortColumn>>,
+    ValidatedQuery(params): ValidatedQuery<ListQueryParams<RaidQuestSortColumn>>,
     Query(filters): Query<RaidQuestFilter>,
 ) -> Result<Json<PaginatedResponse<RaidQuest>>, AppError> {
-    validate_pagination_query(params.page, params.page_size)?;
-
     let total_items = state.db.raid_quests.count_filtered(&params, &filters).await? as u32;
     let total_pages = calculate_total_pages(params.page_size, total_items);
 
@@ -105,19 +201,16 @@ mod tests {
 
     use crate::{
         handlers::raid_quest::{
-            handle_create_raid, handle_finish_raid, handle_get_raid_quests, handle_revert_to_active_raid,
+            handle_create_raid, handle_finish_raid, handle_get_active_raid_quests, handle_get_raid_quests,
+            handle_revert_to_active_raid,
         },
         models::raid_quest::CreateRaidQuest,
-        utils::{
-            test_app_state::create_test_app_state,
-            test_db::{create_mock_admin, reset_database},
-        },
+        utils::{test_app_state::create_test_app_state, test_db::create_mock_admin},
     };
 
     #[tokio::test]
     async fn test_admin_create_raid() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         let router = Router::new()
             .route("/raids", post(handle_create_raid))
@@ -126,6 +219,7 @@ mod tests {
 
         let payload = CreateRaidQuest {
             name: "Unit Test Raid".to_string(),
+            scoring: Default::default(),
         };
 
         let response = router
@@ -151,10 +245,10 @@ mod tests {
     #[tokio::test]
     async fn test_admin_finish_raid() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         let create_payload = CreateRaidQuest {
             name: "Active Raid".to_string(),
+            scoring: Default::default(),
         };
         let raid_id = state.db.raid_quests.create(&create_payload).await.unwrap();
 
@@ -183,10 +277,10 @@ mod tests {
     #[tokio::test]
     async fn test_admin_revert_to_active() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         let create_payload = CreateRaidQuest {
             name: "Finished Raid".to_string(),
+            scoring: Default::default(),
         };
         let raid_id = state.db.raid_quests.create(&create_payload).await.unwrap();
         state.db.raid_quests.finish(raid_id).await.unwrap();
@@ -217,13 +311,13 @@ mod tests {
     #[tokio::test]
     async fn test_get_raid_quests_pagination() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         let raid_id = state
             .db
             .raid_quests
             .create(&CreateRaidQuest {
                 name: "Raid 1".to_string(),
+                scoring: Default::default(),
             })
             .await
             .unwrap();
@@ -234,6 +328,7 @@ mod tests {
             .raid_quests
             .create(&CreateRaidQuest {
                 name: "Raid 2".to_string(),
+                scoring: Default::default(),
             })
             .await
             .unwrap();
@@ -262,4 +357,46 @@ mod tests {
         assert_eq!(data.len(), 2);
         assert_eq!(body["meta"]["total_items"], 2);
     }
+
+    #[tokio::test]
+    async fn test_get_active_raid_quests_returns_concurrent_raids() {
+        let state = create_test_app_state().await;
+
+        for name in ["Raid US", "Raid EU"] {
+            state
+                .db
+                .raid_quests
+                .create(&CreateRaidQuest {
+                    name: name.to_string(),
+                    scoring: Default::default(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let router = Router::new()
+            .route("/raids/active", get(handle_get_active_raid_quests))
+            .with_state(state);
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/raids/active")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body_bytes).unwrap();
+
+        let data = body["data"].as_array().unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0]["name"], "Raid US");
+        assert_eq!(data[1]["name"], "Raid EU");
+    }
 }
@@ -0,0 +1,282 @@
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, NoContent, Response},
+    Extension, Json,
+};
+
+use crate::{
+    db_persistence::DbError,
+    handlers::{HandlerError, SuccessResponse},
+    http_server::AppState,
+    models::{
+        address::Address,
+        raid_quest::RaidQuest,
+        raid_team::{CreateRaidTeam, RaidTeam, TeamLeaderboardQuery},
+    },
+    AppError,
+};
+
+pub const DEFAULT_LEADERBOARD_LIMIT: u32 = 50;
+pub const MAX_LEADERBOARD_LIMIT: u32 = 500;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RaidTeamHandlerError {
+    #[error("{0}")]
+    RaidNotActive(String),
+}
+
+async fn find_raid(state: &AppState, raid_id: i32) -> Result<RaidQuest, AppError> {
+    state
+        .db
+        .raid_quests
+        .find_by_id(raid_id)
+        .await?
+        .ok_or_else(|| AppError::Database(DbError::RecordNotFound(format!("Raid Quest {} not found", raid_id))))
+}
+
+async fn find_active_raid(state: &AppState, raid_id: i32) -> Result<RaidQuest, AppError> {
+    let raid = find_raid(state, raid_id).await?;
+
+    if !raid.is_active() {
+        return Err(AppError::Handler(HandlerError::RaidTeam(
+            RaidTeamHandlerError::RaidNotActive(format!("Raid Quest {} is not active", raid_id)),
+        )));
+    }
+
+    Ok(raid)
+}
+
+/// POST /raid-quests/:raid_id/teams
+/// Creates a team in an active raid, the creator becomes its first member
+pub async fn handle_create_raid_team(
+    State(state): State<AppState>,
+    Extension(user): Extension<Address>,
+    Path(raid_id): Path<i32>,
+    Json(payload): Json<CreateRaidTeam>,
+) -> Result<(StatusCode, Json<SuccessResponse<RaidTeam>>), AppError> {
+    let name = payload.validated_name()?;
+    find_active_raid(&state, raid_id).await?;
+
+    let team = state.db.raid_teams.create(raid_id, &name, &user.quan_address.0).await?;
+
+    Ok((StatusCode::CREATED, SuccessResponse::new(team)))
+}
+
+/// GET /raid-quests/:raid_id/teams
+pub async fn handle_get_raid_teams(
+    State(state): State<AppState>,
+    Path(raid_id): Path<i32>,
+) -> Result<Json<SuccessResponse<Vec<RaidTeam>>>, AppError> {
+    find_raid(&state, raid_id).await?;
+
+    let teams = state.db.raid_teams.find_all_by_raid(raid_id).await?;
+
+    Ok(SuccessResponse::new(teams))
+}
+
+/// POST /raid-quests/:raid_id/teams/:team_id/members
+pub async fn handle_join_raid_team(
+    State(state): State<AppState>,
+    Extension(user): Extension<Address>,
+    Path((raid_id, team_id)): Path<(i32, i32)>,
+) -> Result<NoContent, AppError> {
+    find_active_raid(&state, raid_id).await?;
+
+    state.db.raid_teams.join(raid_id, team_id, &user.quan_address.0).await?;
+
+    Ok(NoContent)
+}
+
+/// DELETE /raid-quests/:raid_id/teams/membership
+pub async fn handle_leave_raid_team(
+    State(state): State<AppState>,
+    Extension(user): Extension<Address>,
+    Path(raid_id): Path<i32>,
+) -> Result<NoContent, AppError> {
+    find_active_raid(&state, raid_id).await?;
+
+    state.db.raid_teams.leave(raid_id, &user.quan_address.0).await?;
+
+    Ok(NoContent)
+}
+
+/// GET /raid-quests/:raid_id/teams/leaderboard
+/// Ranks teams by member scores, optionally normalized by team size. Served from the leaderboard cache, with an
+/// `ETag` so polling clients get 304 while the rankings don't change
+pub async fn handle_get_team_leaderboard(
+    State(state): State<AppState>,
+    Path(raid_id): Path<i32>,
+    Query(query): Query<TeamLeaderboardQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT);
+    if !(1..=MAX_LEADERBOARD_LIMIT).contains(&limit) {
+        return Err(AppError::Handler(HandlerError::QueryParams(format!(
+            "Limit must be between 1 and {}",
+            MAX_LEADERBOARD_LIMIT
+        ))));
+    }
+
+    let key = (raid_id, query.normalization, limit);
+    let ttl = Duration::from_secs(state.settings.current().leaderboard_cache.ttl_seconds);
+
+    let leaderboard = match state.leaderboard_cache.get(&key, ttl, Instant::now()) {
+        Some(cached) => cached,
+        None => {
+            find_raid(&state, raid_id).await?;
+
+            let entries = state
+                .db
+                .raid_teams
+                .find_leaderboard(raid_id, query.normalization, limit)
+                .await?;
+
+            state.leaderboard_cache.insert(key, entries, Instant::now())
+        }
+    };
+
+    let etag = [(header::ETAG, leaderboard.etag.clone())];
+    if etag_matches(&headers, &leaderboard.etag) {
+        return Ok((StatusCode::NOT_MODIFIED, etag).into_response());
+    }
+
+    Ok((etag, SuccessResponse::new(leaderboard.entries)).into_response())
+}
+
+/// Whether the request's `If-None-Match` lists `etag`, weak validators compare by their opaque tag.
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::Request,
+        http::{self, StatusCode},
+        routing::{get, post},
+        Extension, Router,
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        models::raid_quest::CreateRaidQuest,
+        utils::{test_app_state::create_test_app_state, test_db::create_persisted_address},
+    };
+
+    #[tokio::test]
+    async fn test_create_team_and_rank_it() {
+        let state = create_test_app_state().await;
+
+        let raid_id = state
+            .db
+            .raid_quests
+            .create(&CreateRaidQuest {
+                name: "Raid".to_string(),
+                scoring: Default::default(),
+            })
+            .await
+            .unwrap();
+        let user = create_persisted_address(&state.db.addresses, "team_creator").await;
+
+        let router = Router::new()
+            .route("/raids/:raid_id/teams", post(handle_create_raid_team))
+            .route("/raids/:raid_id/teams/leaderboard", get(handle_get_team_leaderboard))
+            .layer(Extension(user))
+            .with_state(state);
+
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/raids/{}/teams", raid_id))
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(r#"{"name": "  EU Raiders "}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let leaderboard_uri = format!("/raids/{}/teams/leaderboard?normalization=per_member", raid_id);
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(&leaderboard_uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[http::header::ETAG].clone();
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert_eq!(body["data"][0]["name"], "EU Raiders");
+        assert_eq!(body["data"][0]["member_count"], 1);
+        assert_eq!(body["data"][0]["rank"], 1);
+
+        // Polling with the validator doesn't resend unchanged rankings
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(&leaderboard_uri)
+                    .header(http::header::IF_NONE_MATCH, etag)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[tokio::test]
+    async fn test_cannot_create_team_in_finished_raid() {
+        let state = create_test_app_state().await;
+
+        let raid_id = state
+            .db
+            .raid_quests
+            .create(&CreateRaidQuest {
+                name: "Raid".to_string(),
+                scoring: Default::default(),
+            })
+            .await
+            .unwrap();
+        state.db.raid_quests.finish(raid_id).await.unwrap();
+        let user = create_persisted_address(&state.db.addresses, "late_creator").await;
+
+        let result = handle_create_raid_team(
+            State(state),
+            Extension(user),
+            Path(raid_id),
+            Json(CreateRaidTeam {
+                name: "Too Late".to_string(),
+            }),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(AppError::Handler(HandlerError::RaidTeam(
+                RaidTeamHandlerError::RaidNotActive(_)
+            )))
+        ));
+    }
+}
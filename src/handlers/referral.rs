@@ -7,9 +7,13 @@ use crate::{
     handlers::HandlerError,
     http_server::AppState,
     models::{
-        address::Address,
+        address::{Address, VanityReferralCodeInput},
+        admin::Admin,
+        referral_reward::{MarkRewardsPaidInput, ReferralPayout, ReferralReward},
         referrals::{Referral, ReferralData, ReferralInput},
     },
+    repositories::{address::AddressRepository, referral::ReferralRepository},
+    services::referral_code_service::{ReferralCodeError, ReferralCodeService},
     AppError,
 };
 
@@ -32,7 +36,7 @@ pub async fn handle_add_referral(
 ) -> Result<Json<SuccessResponse<String>>, AppError> {
     tracing::debug!("Lookup referral code owner...");
     let submitted_code = referral_input.referral_code.to_lowercase();
-    let referrer = state.db.addresses.find_by_referral_code(&submitted_code).await?;
+    let referrer = state.db.addresses.find_referrer_by_code(&submitted_code).await?;
 
     let referee_address = &user.quan_address.0;
     if let Some(referrer) = referrer {
@@ -59,12 +63,10 @@ pub async fn handle_add_referral(
         let referral = Referral::new(referral_data)?;
 
         tracing::debug!("Saving referral to DB...");
-        state.db.referrals.create(&referral).await?;
-        state
-            .db
-            .addresses
-            .increment_referrals_count(&referrer.quan_address.0)
-            .await?;
+        let mut uow = state.db.begin().await?;
+        ReferralRepository::create_with(uow.conn(), &referral).await?;
+        AddressRepository::increment_referrals_count_with(uow.conn(), &referrer.quan_address.0).await?;
+        uow.commit().await?;
 
         Ok(SuccessResponse::new(referrer.referral_code))
     } else {
@@ -90,6 +92,73 @@ pub async fn handle_get_referral_by_referee(
     }
 }
 
+pub async fn handle_set_vanity_referral_code(
+    State(state): State<AppState>,
+    Extension(user): Extension<Address>,
+    extract::Json(input): Json<VanityReferralCodeInput>,
+) -> Result<Json<SuccessResponse<String>>, AppError> {
+    let referral_code = ReferralCodeService::validate_vanity_code(
+        &input.referral_code,
+        &state.settings.current().referral_codes.reserved_prefixes,
+        false,
+    )?;
+
+    if let Some(owner) = state.db.addresses.find_by_referral_code(&referral_code).await? {
+        if owner.quan_address.0 != user.quan_address.0 {
+            return Err(ReferralCodeError::VanityCodeTaken(referral_code).into());
+        }
+    }
+
+    tracing::debug!("Saving vanity referral code...");
+    let updated = state
+        .db
+        .addresses
+        .set_vanity_referral_code(&user.quan_address.0, &referral_code, false)
+        .await?
+        .ok_or(ReferralCodeError::VanityCodeAlreadySet)?;
+
+    Ok(SuccessResponse::new(updated.referral_code))
+}
+
+/// GET /referrals/rewards
+/// Rewards earned by the authenticated address
+pub async fn handle_get_my_referral_rewards(
+    State(state): State<AppState>,
+    Extension(user): Extension<Address>,
+) -> Result<Json<SuccessResponse<Vec<ReferralReward>>>, AppError> {
+    let rewards = state.db.referral_rewards.find_by_referrer(&user.quan_address.0).await?;
+
+    Ok(SuccessResponse::new(rewards))
+}
+
+/// GET /referrals/rewards/payouts
+/// Unpaid rewards per referrer, for the payout run
+pub async fn handle_get_referral_payouts(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+) -> Result<Json<SuccessResponse<Vec<ReferralPayout>>>, AppError> {
+    let payouts = state.db.referral_rewards.find_unpaid_payouts().await?;
+
+    Ok(SuccessResponse::new(payouts))
+}
+
+/// POST /referrals/rewards/payouts/paid
+/// Marks exported rewards as paid, returns how many were updated
+pub async fn handle_mark_referral_rewards_paid(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Json(input): Json<MarkRewardsPaidInput>,
+) -> Result<Json<SuccessResponse<u64>>, AppError> {
+    let updated = state
+        .db
+        .referral_rewards
+        .mark_paid(&input.reward_ids, &admin.username)
+        .await?;
+    tracing::info!("{} referral rewards marked paid by {}", updated, admin.username);
+
+    Ok(SuccessResponse::new(updated))
+}
+
 #[cfg(test)]
 mod tests {
     use axum::extract::Path;
@@ -97,13 +166,12 @@ mod tests {
     use super::*;
     use crate::models::address::AddressInput;
     use crate::utils::test_app_state::create_test_app_state;
-    use crate::utils::test_db::{create_persisted_address, reset_database};
+    use crate::utils::test_db::create_persisted_address;
 
     #[tokio::test]
     async fn test_add_referral_success() {
         // Arrange
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         // Referrals require existing addresses, so we create them first.
         let referrer = create_persisted_address(&state.db.addresses, "referrer_01").await;
@@ -163,7 +231,6 @@ mod tests {
     async fn test_get_referral_by_referee() {
         // Arrange
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         // Referrals require existing addresses, so we create them first.
         let referrer = create_persisted_address(&state.db.addresses, "referrer_01").await;
@@ -191,7 +258,6 @@ mod tests {
     async fn test_add_referral_invalid_referee_input() {
         // Arrange
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         // Referrals require existing addresses, so we create them first.
         let referrer = create_persisted_address(&state.db.addresses, "referrer_01").await;
@@ -227,7 +293,6 @@ mod tests {
     async fn test_add_referral_duplicate() {
         // Arrange
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         let referrer = create_persisted_address(&state.db.addresses, "referrer_01").await;
         let referee = create_persisted_address(&state.db.addresses, "referee_01").await;
@@ -253,4 +318,66 @@ mod tests {
             AppError::Handler(HandlerError::Referral(ReferralHandlerError::DuplicateReferral(_)))
         ));
     }
+
+    #[tokio::test]
+    async fn test_set_vanity_referral_code_once() {
+        let state = create_test_app_state().await;
+
+        let user = create_persisted_address(&state.db.addresses, "vanity_01").await;
+        let input = VanityReferralCodeInput {
+            referral_code: "Moon-Shot".to_string(),
+        };
+
+        let result = handle_set_vanity_referral_code(State(state.clone()), Extension(user.clone()), Json(input))
+            .await
+            .unwrap();
+        assert_eq!(result.data, "moon-shot");
+
+        let second = VanityReferralCodeInput {
+            referral_code: "moon-shot-2".to_string(),
+        };
+        let error = handle_set_vanity_referral_code(State(state.clone()), Extension(user), Json(second))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            AppError::ReferralCode(ReferralCodeError::VanityCodeAlreadySet)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_set_vanity_referral_code_rejects_reserved_and_taken() {
+        let state = create_test_app_state().await;
+
+        let owner = create_persisted_address(&state.db.addresses, "vanity_02").await;
+        let user = create_persisted_address(&state.db.addresses, "vanity_03").await;
+
+        let reserved = VanityReferralCodeInput {
+            referral_code: "quantus-official".to_string(),
+        };
+        let error = handle_set_vanity_referral_code(State(state.clone()), Extension(user.clone()), Json(reserved))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            AppError::ReferralCode(ReferralCodeError::ReservedVanityCode(_))
+        ));
+
+        state
+            .db
+            .addresses
+            .set_vanity_referral_code(&owner.quan_address.0, "taken-code", false)
+            .await
+            .unwrap();
+        let taken = VanityReferralCodeInput {
+            referral_code: "Taken-Code".to_string(),
+        };
+        let error = handle_set_vanity_referral_code(State(state.clone()), Extension(user), Json(taken))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            AppError::ReferralCode(ReferralCodeError::VanityCodeTaken(_))
+        ));
+    }
 }
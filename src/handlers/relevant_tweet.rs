@@ -6,7 +6,7 @@ use axum::{
 use crate::{
     db_persistence::DbError,
     handlers::{
-        calculate_total_pages, validate_pagination_query, ListQueryParams, PaginatedResponse, PaginationMetadata,
+        calculate_total_pages, validation::ValidatedQuery, ListQueryParams, PaginatedResponse, PaginationMetadata,
         SuccessResponse,
     },
     http_server::AppState,
@@ -18,11 +18,9 @@ use crate::{
 /// Lists tweets with pagination, filtering, and joined author details.
 pub async fn handle_get_relevant_tweets(
     State(state): State<AppState>,
-    Query(params): Query<ListQueryParams<TweetSortColumn>>,
+    ValidatedQuery(params): ValidatedQuery<ListQueryParams<TweetSortColumn>>,
     Query(filters): Query<TweetFilter>,
 ) -> Result<Json<PaginatedResponse<TweetWithAuthor>>, AppError> {
-    validate_pagination_query(params.page, params.page_size)?;
-
     let total_items = state.db.relevant_tweets.count_filtered(&params, &filters).await? as u32;
     let total_pages = calculate_total_pages(params.page_size, total_items);
 
@@ -71,7 +69,7 @@ mod tests {
     use crate::{
         handlers::relevant_tweet::{handle_get_relevant_tweet_by_id, handle_get_relevant_tweets},
         models::{relevant_tweet::NewTweetPayload, tweet_author::NewAuthorPayload},
-        utils::{test_app_state::create_test_app_state, test_db::reset_database},
+        utils::test_app_state::create_test_app_state,
     };
 
     // --- Helper to seed data ---
@@ -158,7 +156,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_relevant_tweets_pagination() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_tweets(&state).await;
 
         let router = Router::new()
@@ -198,7 +195,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_relevant_tweets_filtering_by_author() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_tweets(&state).await;
 
         let router = Router::new()
@@ -234,7 +230,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_relevant_tweets_filtering_by_min_likes() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_tweets(&state).await;
 
         let router = Router::new()
@@ -266,7 +261,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_relevant_tweets_search_fts() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_tweets(&state).await;
 
         let router = Router::new()
@@ -298,7 +292,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_relevant_tweets_sorting() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_tweets(&state).await;
 
         let router = Router::new()
@@ -332,7 +325,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_relevant_tweet_by_id_success() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_tweets(&state).await;
 
         let router = Router::new()
@@ -362,7 +354,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_relevant_tweet_by_id_not_found() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         let router = Router::new()
             .route("/relevant-tweets/:id", get(handle_get_relevant_tweet_by_id))
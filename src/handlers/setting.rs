@@ -0,0 +1,159 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+
+use crate::{
+    handlers::{
+        auth::require_admin_role, calculate_total_pages, validation::ValidatedQuery, ListQueryParams,
+        PaginatedResponse, PaginationMetadata, SuccessResponse,
+    },
+    http_server::AppState,
+    models::{
+        admin::{Admin, AdminRole},
+        setting::{SettingAudit, SettingAuditFilter, SettingAuditSortColumn, SettingView, UpdateSettingInput},
+    },
+    AppError,
+};
+
+/// GET /settings
+/// Lists every overridable setting with its file value, override and effective value
+pub async fn handle_get_settings(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+) -> Result<Json<SuccessResponse<Vec<SettingView>>>, AppError> {
+    let settings = state.settings.list()?;
+
+    Ok(SuccessResponse::new(settings))
+}
+
+/// PUT /settings/:key
+pub async fn handle_update_setting(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(key): Path<String>,
+    Json(payload): Json<UpdateSettingInput>,
+) -> Result<Json<SuccessResponse<Vec<SettingView>>>, AppError> {
+    require_admin_role(&admin, AdminRole::Superadmin)?;
+    state.settings.set(&key, payload.value, &admin.username).await?;
+
+    Ok(SuccessResponse::new(state.settings.list()?))
+}
+
+/// DELETE /settings/:key
+/// Removes the override so the file value applies again
+pub async fn handle_delete_setting(
+    State(state): State<AppState>,
+    Extension(admin): Extension<Admin>,
+    Path(key): Path<String>,
+) -> Result<Json<SuccessResponse<Vec<SettingView>>>, AppError> {
+    require_admin_role(&admin, AdminRole::Superadmin)?;
+    state.settings.clear(&key, &admin.username).await?;
+
+    Ok(SuccessResponse::new(state.settings.list()?))
+}
+
+/// GET /settings/audit
+pub async fn handle_get_settings_audit(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+    ValidatedQuery(params): ValidatedQuery<ListQueryParams<SettingAuditSortColumn>>,
+    Query(filters): Query<SettingAuditFilter>,
+) -> Result<Json<PaginatedResponse<SettingAudit>>, AppError> {
+    let total_items = state.db.settings.count_audit_filtered(&params, &filters).await? as u32;
+    let total_pages = calculate_total_pages(params.page_size, total_items);
+
+    let entries = state.db.settings.find_audit(&params, &filters).await?;
+
+    let response = PaginatedResponse::<SettingAudit> {
+        data: entries,
+        meta: PaginationMetadata {
+            page: params.page,
+            page_size: params.page_size,
+            total_items,
+            total_pages,
+        },
+    };
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::create_mock_admin};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        routing::{get, put},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_update_setting_applies_and_is_audited() {
+        let state = create_test_app_state().await;
+
+        let router = Router::new()
+            .route("/settings/audit", get(handle_get_settings_audit))
+            .route("/settings/:key", put(handle_update_setting))
+            .layer(Extension(create_mock_admin()))
+            .with_state(state.clone());
+
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri("/settings/jwt.exp_in_hours")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"value": 1}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(state.settings.current().jwt.exp_in_hours, 1);
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/settings/audit?key=jwt.exp_in_hours")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert_eq!(body_json["meta"]["total_items"], 1);
+        assert_eq!(body_json["data"][0]["changed_by"], "admin_tester");
+        assert_eq!(body_json["data"][0]["new_value"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_non_whitelisted_setting_is_rejected() {
+        let state = create_test_app_state().await;
+
+        let router = Router::new()
+            .route("/settings/:key", put(handle_update_setting))
+            .layer(Extension(create_mock_admin()))
+            .with_state(state);
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri("/settings/data.database_url")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"value": "postgres://elsewhere"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
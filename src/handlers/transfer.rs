@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+
+use crate::{
+    db_persistence::DbError,
+    handlers::SuccessResponse,
+    http_server::AppState,
+    models::{
+        admin::Admin,
+        processed_transfer::{ProcessedTransfer, TransferGap},
+    },
+    AppError,
+};
+
+/// GET /transfers/:transfer_id
+/// Answers whether an indexer transfer was ingested by the transfer sync
+pub async fn handle_get_processed_transfer(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+    Path(transfer_id): Path<String>,
+) -> Result<Json<SuccessResponse<ProcessedTransfer>>, AppError> {
+    let transfer = state
+        .db
+        .processed_transfers
+        .find_by_id(&transfer_id)
+        .await?
+        .ok_or_else(|| DbError::RecordNotFound(format!("Transfer '{}' has not been processed", transfer_id)))?;
+
+    Ok(SuccessResponse::new(transfer))
+}
+
+/// GET /transfers/gaps
+/// Transfer ranges the sync skipped, empty when every transfer up to the checkpoint was ingested
+pub async fn handle_get_transfer_gaps(
+    State(state): State<AppState>,
+    Extension(_): Extension<Admin>,
+) -> Result<Json<SuccessResponse<Vec<TransferGap>>>, AppError> {
+    let gaps = state.db.processed_transfers.find_gaps().await?;
+
+    Ok(SuccessResponse::new(gaps))
+}
@@ -4,7 +4,10 @@ use axum::{
     response::NoContent,
     Extension, Json,
 };
-use rusx::resources::{user::UserParams, UserField};
+use rusx::{
+    error::SdkError,
+    resources::{user::UserParams, UserField},
+};
 
 use crate::{
     db_persistence::DbError,
@@ -59,11 +62,15 @@ pub async fn handle_create_tweet_author(
         UserField::Username,
     ]);
 
+    let users = state.twitter_gateway.users();
     let author_response = state
-        .twitter_gateway
-        .users()
-        .get_by_username(&payload.username, Some(params.clone()))
-        .await?;
+        .twitter_retrier
+        .run(is_transient_x_error, || {
+            users.get_by_username(&payload.username, Some(params.clone()))
+        })
+        .await;
+    state.twitter_calls.record(&author_response);
+    let author_response = author_response?;
     let Some(author) = author_response.data else {
         return Err(AppError::Handler(HandlerError::InvalidBody(format!(
             "Tweet Author {} not found",
@@ -77,6 +84,11 @@ pub async fn handle_create_tweet_author(
     Ok((StatusCode::CREATED, SuccessResponse::new(create_response)))
 }
 
+/// X rate limits last for minutes, only its server errors are worth retrying.
+fn is_transient_x_error(err: &SdkError) -> bool {
+    matches!(err, SdkError::Api { status, .. } if *status >= 500)
+}
+
 /// PUT /tweet-authors/:id/ignore
 pub async fn handle_ignore_tweet_author(
     State(state): State<AppState>,
@@ -144,10 +156,7 @@ mod tests {
             handle_ignore_tweet_author, handle_watch_tweet_author,
         },
         models::tweet_author::NewAuthorPayload,
-        utils::{
-            test_app_state::create_test_app_state,
-            test_db::{create_mock_admin, reset_database},
-        },
+        utils::{test_app_state::create_test_app_state, test_db::create_mock_admin},
     };
 
     // --- Helper to seed authors easily ---
@@ -202,7 +211,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_tweet_authors_success_pagination() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_authors(&state).await;
 
         let router = Router::new()
@@ -244,7 +252,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_tweet_authors_filtering() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_authors(&state).await;
 
         let router = Router::new()
@@ -274,10 +281,41 @@ mod tests {
         assert_eq!(data[0]["username"], "beta_tester");
     }
 
+    #[tokio::test]
+    async fn test_get_tweet_authors_partial_handle_search() {
+        let state = create_test_app_state().await;
+        seed_authors(&state).await;
+
+        let router = Router::new()
+            .route("/tweet-authors", get(handle_get_tweet_authors))
+            .layer(Extension(create_mock_admin()))
+            .with_state(state);
+
+        // Request: Search for the start of a handle
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/tweet-authors?search=%40beta_te")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body_json: Value = serde_json::from_slice(&body_bytes).unwrap();
+
+        let data = body_json["data"].as_array().unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0]["username"], "beta_tester");
+    }
+
     #[tokio::test]
     async fn test_get_tweet_authors_sorting() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_authors(&state).await;
 
         let router = Router::new()
@@ -312,7 +350,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_tweet_author_by_id_success() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_authors(&state).await;
 
         let router = Router::new()
@@ -342,7 +379,6 @@ mod tests {
     #[tokio::test]
     async fn test_get_tweet_author_by_id_not_found() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         let router = Router::new()
             .route("/tweet-authors/:id", get(handle_get_tweet_author_by_id))
@@ -365,7 +401,6 @@ mod tests {
     #[tokio::test]
     async fn test_create_tweet_author_success() {
         let mut state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         // --- Setup Twitter Mock ---
         let mut mock_gateway = MockTwitterGateway::new();
@@ -429,7 +464,6 @@ mod tests {
     #[tokio::test]
     async fn test_ignore_and_watch_tweet_author() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
         seed_authors(&state).await;
 
         let router = Router::new()
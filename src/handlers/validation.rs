@@ -0,0 +1,115 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Query, Request},
+    http::request::Parts,
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    models::validation::{Validate, ValidationErrors},
+    AppError,
+};
+
+/// `Json` that also runs [`Validate`]. Bodies that don't parse are reported the same way, under `body`.
+#[derive(Debug)]
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| ValidationErrors::single("body", rejection.body_text()))?;
+        value.validate()?;
+
+        Ok(Self(value))
+    }
+}
+
+/// `Query` that also runs [`Validate`]. Query strings that don't parse are reported under `query`.
+#[derive(Debug)]
+pub struct ValidatedQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| ValidationErrors::single("query", rejection.body_text()))?;
+        value.validate()?;
+
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::raid_quest::CreateRaidQuest;
+    use axum::{
+        body::Body,
+        http::{header, StatusCode},
+        routing::post,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    async fn create(ValidatedJson(payload): ValidatedJson<CreateRaidQuest>) -> String {
+        payload.name
+    }
+
+    async fn send(body: &'static str) -> (StatusCode, serde_json::Value) {
+        let response = Router::new()
+            .route("/raids", post(create))
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/raids")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_body_lists_fields() {
+        let (status, _) = send(r#"{"name": "Raid"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = send(r#"{"name": " ", "scoring": {"like_weight": -1, "max_submission_score": 0}}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let fields: Vec<&str> = body["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            fields,
+            vec!["name", "scoring.like_weight", "scoring.max_submission_score"]
+        );
+
+        let (status, body) = send("{not json").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["errors"][0]["field"], "body");
+    }
+}
@@ -1,8 +1,9 @@
-use axum::http::Method;
+use axum::extract::State;
+use axum::http::{header, Method, StatusCode};
 use axum::{middleware, response::Json, routing::get, Router};
 use rusx::TwitterGateway;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
+use std::{net::SocketAddr, sync::Arc};
 use tower::ServiceBuilder;
 use tower_http::{
     cors::{AllowHeaders, CorsLayer},
@@ -13,12 +14,28 @@ use crate::services::exchange_rate_service::ExchangeRateService;
 use crate::{
     db_persistence::DbPersistence,
     metrics::{metrics_handler, track_metrics, Metrics},
+    middlewares::{
+        idempotency::sweep_expired_records,
+        rate_limit::RateLimiter,
+        request_id::{request_id, REQUEST_ID_HEADER},
+        request_logging::log_requests,
+    },
     routes::api_routes,
-    services::{risk_checker_service::RiskCheckerService, wallet_config_service::WalletConfigService},
+    services::{
+        challenge_store::ChallengeStore,
+        health_registry::{DegradationPolicy, HealthRegistry, HealthReport, LastCallOutcome, ServiceStatus},
+        leaderboard_cache::{invalidate_on_events, refresh_in_background, LeaderboardCache},
+        risk_checker_service::RiskCheckerService,
+        runbook::{RunbookEntry, RUNBOOK},
+        secrets::SecretStore,
+        settings_service::SettingsService,
+        wallet_config_service::WalletConfigService,
+    },
+    utils::{config_reload::reload_on_sighup, retry::Retrier},
     Config,
 };
 use chrono::{DateTime, Utc};
-use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone)]
 pub struct AppState {
@@ -28,8 +45,24 @@ pub struct AppState {
     pub risk_checker_service: Arc<RiskCheckerService>,
     pub exchange_rate_service: Arc<ExchangeRateService>,
     pub config: Arc<Config>,
-    pub challenges: Arc<RwLock<HashMap<String, Challenge>>>,
+    /// Effective configuration for settings that admins can override at runtime.
+    pub settings: Arc<SettingsService>,
+    /// Login challenges, see [`ChallengeStore`] for where they are kept.
+    pub challenges: Arc<ChallengeStore>,
     pub twitter_gateway: Arc<dyn TwitterGateway>,
+    /// How the last X API call went, reported by the health registry instead of probing X.
+    pub twitter_calls: Arc<LastCallOutcome>,
+    /// Retries and circuit breaker shared by the X API calls.
+    pub twitter_retrier: Arc<Retrier>,
+    /// Health probes of outbound integrations.
+    pub health: Arc<HealthRegistry>,
+    /// Token buckets of the rate limited routes.
+    pub rate_limiter: Arc<RateLimiter>,
+    pub leaderboard_cache: Arc<LeaderboardCache>,
+    /// JWT secrets, resolved from wherever the config points and refreshed on rotation.
+    pub secrets: Arc<SecretStore>,
+    /// Cancelled on shutdown. Long-lived responses such as event streams end on it.
+    pub shutdown: CancellationToken,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,12 +79,26 @@ pub struct HealthResponse {
     pub timestamp: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    pub ready: bool,
+    /// Critical integrations that are down.
+    pub failing: Vec<&'static str>,
+}
+
 /// Create the HTTP server router
 pub fn create_router(state: AppState) -> Router {
     Router::new()
         .route("/health", get(health_check))
+        .route("/health/integrations", get(integrations_health_check))
+        .route("/health/runbook", get(runbook_handler))
+        .route("/healthz", get(liveness_check))
+        .route("/readyz", get(readiness_check))
         .route("/metrics", get(metrics_handler))
-        .nest("/api", api_routes(state.clone()))
+        .nest(
+            "/api",
+            api_routes(state.clone()).layer(middleware::from_fn_with_state(state.clone(), log_requests)),
+        )
         .layer(middleware::from_fn(track_metrics))
         .layer(
             ServiceBuilder::new().layer(TraceLayer::new_for_http()).layer(
@@ -59,9 +106,11 @@ pub fn create_router(state: AppState) -> Router {
                     .allow_origin(state.config.get_cors_allowed_origins())
                     .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
                     .allow_headers(AllowHeaders::mirror_request())
+                    .expose_headers([header::ETAG, REQUEST_ID_HEADER])
                     .allow_credentials(true),
             ),
         )
+        .layer(middleware::from_fn(request_id))
         .with_state(state)
 }
 
@@ -75,31 +124,183 @@ async fn health_check() -> Json<HealthResponse> {
     })
 }
 
+/// Health of outbound integrations. Responds with 503 when a critical integration is down.
+async fn integrations_health_check(State(state): State<AppState>) -> (StatusCode, Json<HealthReport>) {
+    let report = state.health.report().await;
+
+    let status = match report.status {
+        ServiceStatus::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+        ServiceStatus::Healthy | ServiceStatus::Degraded => StatusCode::OK,
+    };
+
+    (status, Json(report))
+}
+
+/// Liveness probe. Only tells that the process is serving requests, dependencies are not checked so an
+/// outage of the database doesn't get every pod restarted.
+async fn liveness_check() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Readiness probe. Not ready while a critical integration is down. Only the critical probes run, so frequent polling
+/// doesn't reach the other integrations.
+async fn readiness_check(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let report = state.health.critical_report().await;
+    let failing: Vec<&'static str> = report
+        .integrations
+        .iter()
+        .filter(|i| !i.healthy && i.policy == DegradationPolicy::Critical)
+        .map(|i| i.name)
+        .collect();
+
+    let status = if failing.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(ReadinessResponse {
+            ready: failing.is_empty(),
+            failing,
+        }),
+    )
+}
+
+/// Known failure codes and their remediation hints.
+async fn runbook_handler() -> Json<&'static [RunbookEntry]> {
+    Json(RUNBOOK)
+}
+
 /// Start the HTTP server
 pub async fn start_server(
     db: Arc<DbPersistence>,
     twitter_gateway: Arc<dyn TwitterGateway>,
     bind_address: &str,
+    config_path: &str,
     config: Arc<Config>,
+    shutdown: CancellationToken,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let settings = Arc::new(SettingsService::load(config.clone(), db.settings.clone()).await?);
+    tokio::spawn(reload_on_sighup(
+        config_path.to_string(),
+        settings.clone(),
+        shutdown.clone(),
+    ));
+    let leaderboard_cache = Arc::new(LeaderboardCache::new());
+    tokio::spawn(invalidate_on_events(
+        leaderboard_cache.clone(),
+        db.events.clone(),
+        shutdown.clone(),
+    ));
+    tokio::spawn(refresh_in_background(
+        leaderboard_cache.clone(),
+        db.raid_teams.clone(),
+        settings.clone(),
+        shutdown.clone(),
+    ));
+    tokio::spawn(sweep_expired_records(db.idempotency_records.clone(), shutdown.clone()));
+    let challenges = ChallengeStore::new(Arc::new(db.auth_challenges.clone()), settings.clone());
+    let secrets = SecretStore::new(&config.secrets);
+    // Fail at startup rather than on the first login when a secret can't be resolved
+    secrets.current(&config.jwt.secret).await?;
+    secrets.current(&config.jwt.admin_secret).await?;
+    let risk_checker_service = Arc::new(RiskCheckerService::new(
+        &config.risk_checker,
+        config.retry.etherscan.clone(),
+    ));
+    let exchange_rate_service = Arc::new(ExchangeRateService::new(
+        &config.exchange_rate.api_key,
+        config.retry.exchange_rate.clone(),
+    ));
+    let twitter_calls = Arc::new(LastCallOutcome::new());
+    let twitter_retrier = Arc::new(Retrier::new("X API", config.retry.x_api.clone()));
+    let health = HealthRegistry::with_default_probes(
+        db.pool.clone(),
+        config.candidates.graphql_url.clone(),
+        twitter_calls.clone(),
+        exchange_rate_service.clone(),
+        risk_checker_service.clone(),
+    );
     let state = AppState {
         db,
         metrics: Arc::new(Metrics::new()),
         wallet_config_service: Arc::new(WalletConfigService::new(
             config.remote_configs.wallet_configs_file.clone(),
         )?),
-        risk_checker_service: Arc::new(RiskCheckerService::new(&config.risk_checker)),
-        exchange_rate_service: Arc::new(ExchangeRateService::new(&config.exchange_rate.api_key)),
+        risk_checker_service,
+        exchange_rate_service,
+        health: Arc::new(health),
+        settings,
         config,
         twitter_gateway,
-        challenges: Arc::new(RwLock::new(HashMap::new())),
+        twitter_calls,
+        twitter_retrier,
+        challenges: Arc::new(challenges),
+        rate_limiter: Arc::new(RateLimiter::new()),
+        leaderboard_cache,
+        secrets: Arc::new(secrets),
+        shutdown: shutdown.clone(),
     };
     let app = create_router(state);
 
     tracing::info!("Starting HTTP server on {}", bind_address);
 
     let listener = tokio::net::TcpListener::bind(bind_address).await?;
-    axum::serve(listener, app).await?;
+    // Stops accepting connections once cancelled and waits for in-flight requests to finish.
+    // Connection info gives the rate limiter the client IP of unauthenticated requests.
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown.cancelled_owned())
+        .await?;
+
+    tracing::info!("HTTP server stopped");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_app_state::create_test_app_state;
+
+    #[tokio::test]
+    async fn test_liveness_and_readiness() {
+        use axum::{body::Body, http::Request};
+        use tower::ServiceExt;
+
+        let router = create_router(create_test_app_state().await);
+
+        for (uri, expected) in [("/healthz", StatusCode::OK), ("/readyz", StatusCode::OK)] {
+            let response = router
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), expected, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_stops_when_cancelled() {
+        let state = create_test_app_state().await;
+        let shutdown = CancellationToken::new();
+        shutdown.cancel();
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            start_server(
+                state.db,
+                state.twitter_gateway,
+                "127.0.0.1:0",
+                "config/test.toml",
+                state.config,
+                shutdown,
+            ),
+        )
+        .await
+        .expect("Server did not shut down");
+
+        assert!(result.is_ok());
+    }
+}
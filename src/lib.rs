@@ -8,10 +8,13 @@ pub mod args;
 pub mod config;
 pub mod db_persistence;
 pub mod errors;
+pub mod fixtures;
 pub mod handlers;
 pub mod http_server;
 pub mod metrics;
 pub mod middlewares;
+#[cfg(any(test, feature = "dev-tools"))]
+pub mod mock_indexer;
 pub mod models;
 pub mod repositories;
 pub mod routes;
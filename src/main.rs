@@ -2,15 +2,19 @@ use crate::{
     args::Args,
     db_persistence::DbPersistence,
     errors::{AppError, AppResult},
-    services::graphql_client::GraphqlClient,
+    services::{
+        fraud_rules::FraudRuleService, graphql_client::GraphqlClient, referral_rewards::ReferralRewardsService,
+        runbook, sybil_detector::SybilDetector,
+    },
 };
 
 use clap::Parser;
 use rusx::RusxGateway;
 use sp_core::crypto::{self, Ss58AddressFormat};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+use tokio_util::sync::CancellationToken;
 
-use tracing::{error, info};
+use tracing::{error, info, warn, Instrument};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod args;
@@ -27,7 +31,10 @@ mod routes;
 mod services;
 mod utils;
 
-use config::Config;
+use config::{Config, LogFormat, LoggingConfig};
+
+/// How long in-flight requests get to finish after a shutdown signal before we give up on them.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(30);
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
@@ -38,7 +45,7 @@ async fn main() -> AppResult<()> {
 
     crypto::set_default_ss58_version(Ss58AddressFormat::custom(189));
     // Initialize logging
-    init_logging(&config.logging.level)?;
+    init_logging(&config.logging)?;
 
     info!("🚀 Starting TaskMaster v{}", env!("CARGO_PKG_VERSION"));
     info!("Configuration loaded from: {}", args.config);
@@ -46,18 +53,68 @@ async fn main() -> AppResult<()> {
     // Initialize database persistence
     let db_url = config.get_database_url();
     info!("Database URL: {}", db_url);
-    let db = Arc::new(DbPersistence::new(db_url).await?);
+    let read_replica_url = config.data.read_replica_url.as_deref();
+    if read_replica_url.is_some() {
+        info!("Read replica configured for list, leaderboard and stats queries");
+    }
+    let db = Arc::new(DbPersistence::with_read_replica(db_url, read_replica_url).await?);
 
     // Initialize graphql client
-    let graphql_client = GraphqlClient::new((*db).clone(), config.candidates.graphql_url.clone());
+    let graphql_client = GraphqlClient::new(
+        (*db).clone(),
+        config.candidates.graphql_url.clone(),
+        config.candidates.sync_page_size,
+        config.retry.graphql.clone(),
+    )
+    .with_concurrency_limit(config.candidates.max_concurrent_requests);
 
     if args.sync_transfers {
         info!("Running in sync-transfers mode");
-        let (transfer_count, address_count) = graphql_client.sync_transfers_and_addresses().await?;
+
+        let pause = db.maintenance.find_pause_state().await?;
+        if pause.paused {
+            warn!(
+                "Background jobs are paused by {} ({}), skipping sync",
+                pause.updated_by.as_deref().unwrap_or("an admin"),
+                pause.reason.as_deref().unwrap_or("no reason given")
+            );
+            return Ok(());
+        }
+
+        // Logs of a sync run share an ID the same way request logs do
+        let sync_span = tracing::info_span!("sync", request_id = %uuid::Uuid::new_v4());
+        let result = graphql_client
+            .sync_transfers_and_addresses()
+            .instrument(sync_span)
+            .await;
+        if let Some(entry) = result
+            .as_ref()
+            .err()
+            .and_then(|e| e.runbook_code())
+            .and_then(runbook::lookup)
+        {
+            error!("Sync failed [{}]: {}", entry.code, entry.remediation);
+        }
+        let (transfer_count, address_count) = result?;
         info!(
             "Sync completed successfully: {} transfers processed, {} addresses stored",
             transfer_count, address_count
         );
+
+        // Rewards skip high risk referees, so scores are refreshed first.
+        SybilDetector::new(db.sybil_scores.clone(), config.sybil_detector.clone())
+            .compute()
+            .await?;
+        let evaluations = FraudRuleService::new(db.fraud_rules.clone()).evaluate_enabled().await?;
+        info!(
+            "Fraud rules evaluated: {} new flags from {} rules",
+            evaluations.iter().map(|e| e.new_flags).sum::<u64>(),
+            evaluations.len()
+        );
+
+        let rewards = ReferralRewardsService::new(db.referral_rewards.clone(), config.referral_rewards.clone());
+        let granted = rewards.compute().await?;
+        info!("Referral rewards computed: {} granted", granted);
         return Ok(());
     }
 
@@ -68,29 +125,60 @@ async fn main() -> AppResult<()> {
     let twitter_gateway = Arc::new(RusxGateway::new(config.x_oauth.clone(), None)?);
     let server_db = db.clone();
     let server_addr_clone = server_address.clone();
+    let server_config_path = args.config.clone();
     let server_config = Arc::new(config.clone());
     let server_twitter_gateway = twitter_gateway.clone();
-    let server_task = tokio::spawn(async move {
-        http_server::start_server(server_db, server_twitter_gateway, &server_addr_clone, server_config)
-            .await
-            .map_err(|e| AppError::Server(e.to_string()))
+    let shutdown = CancellationToken::new();
+    let server_shutdown = shutdown.clone();
+    let mut server_task = tokio::spawn(async move {
+        http_server::start_server(
+            server_db,
+            server_twitter_gateway,
+            &server_addr_clone,
+            &server_config_path,
+            server_config,
+            server_shutdown,
+        )
+        .await
+        .map_err(|e| AppError::Server(e.to_string()))
     });
 
+    tokio::spawn(utils::shutdown::cancel_on_signal(shutdown.clone()));
+
     info!("🎯 TaskMaster is now running!");
     info!("HTTP API available at: http://{}", server_address);
 
-    // Wait for any task to complete (they should run forever unless there's an error)
+    // Run until a shutdown signal arrives or the server fails on its own.
     tokio::select! {
-        result = server_task => {
+        result = &mut server_task => {
             error!("HTTP server exited: {:?}", result);
+            shutdown.cancel();
+            db.pool.close().await;
             result??;
+            return Ok(());
+        }
+        _ = shutdown.cancelled() => {
+            info!("Draining in-flight requests...");
         }
     }
 
+    match tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, server_task).await {
+        Ok(result) => result??,
+        Err(_) => warn!(
+            "HTTP server did not drain within {}s, forcing shutdown",
+            SHUTDOWN_GRACE_PERIOD.as_secs()
+        ),
+    }
+
+    // Wait for pending queries to finish and release connections before exiting.
+    db.pool.close().await;
+    info!("TaskMaster stopped");
+
     Ok(())
 }
 
-fn init_logging(level: &str) -> AppResult<()> {
+fn init_logging(config: &LoggingConfig) -> AppResult<()> {
+    let level = &config.level;
     let log_level = match level.to_lowercase().as_str() {
         "error" => tracing::Level::ERROR,
         "warn" => tracing::Level::WARN,
@@ -103,13 +191,24 @@ fn init_logging(level: &str) -> AppResult<()> {
         }
     };
 
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| format!("task_master={}", log_level).into()),
-        )
-        .with(tracing_subscriber::fmt::layer().with_target(false))
-        .init();
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| format!("task_master={}", log_level).into());
+    let registry = tracing_subscriber::registry().with(filter);
+
+    match config.format {
+        LogFormat::Text => registry
+            .with(tracing_subscriber::fmt::layer().with_target(false))
+            .init(),
+        // Each line lists its enclosing spans, which carries the `request_id` of the request that logged it
+        LogFormat::Json => registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_target(false)
+                    .with_span_list(true),
+            )
+            .init(),
+    }
 
     Ok(())
 }
@@ -49,6 +49,14 @@ lazy_static! {
         &["method", "endpoint", "status"]
     )
     .unwrap();
+    pub static ref CHALLENGE_STORE_LOOKUPS: IntCounterVec = IntCounterVec::new(
+        Opts::new(
+            "challenge_store_lookups_total",
+            "Login challenge lookups by store and result"
+        ),
+        &["store", "result"]
+    )
+    .unwrap();
 }
 
 impl Default for Metrics {
@@ -80,6 +88,7 @@ impl Metrics {
         registry.register(Box::new(HTTP_REQUEST_SIZE_BYTES.clone())).unwrap();
         registry.register(Box::new(HTTP_RESPONSE_SIZE_BYTES.clone())).unwrap();
         registry.register(Box::new(HTTP_ERRORS_TOTAL.clone())).unwrap();
+        registry.register(Box::new(CHALLENGE_STORE_LOOKUPS.clone())).unwrap();
 
         Self {
             registry: Arc::new(registry),
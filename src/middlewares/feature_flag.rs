@@ -0,0 +1,120 @@
+use std::marker::PhantomData;
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+
+use crate::{
+    handlers::{auth::AuthHandlerError, feature_flag::FeatureFlagHandlerError, HandlerError},
+    http_server::AppState,
+    models::address::Address,
+    AppError,
+};
+
+/// Names the flag guarding a handler, see [`RequireFeature`].
+pub trait FeatureFlagKey {
+    const KEY: &'static str;
+}
+
+/// Extractor that rejects the request unless the flag `F` is enabled for the authenticated address.
+/// Must run behind `jwt_auth`. Unknown flags count as disabled.
+///
+/// ```ignore
+/// struct Claims;
+/// impl FeatureFlagKey for Claims {
+///     const KEY: &'static str = "claims";
+/// }
+///
+/// async fn handle_claim(_: RequireFeature<Claims>, ...) { ... }
+/// ```
+pub struct RequireFeature<F: FeatureFlagKey>(PhantomData<F>);
+
+#[async_trait]
+impl<F: FeatureFlagKey> FromRequestParts<AppState> for RequireFeature<F> {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = parts.extensions.get::<Address>().ok_or_else(|| {
+            AppError::Handler(HandlerError::Auth(AuthHandlerError::Unauthorized(
+                "Authentication required".to_string(),
+            )))
+        })?;
+
+        let enabled = state
+            .db
+            .feature_flags
+            .find_by_key(F::KEY)
+            .await?
+            .is_some_and(|flag| flag.is_enabled_for(&user.quan_address.0));
+
+        if !enabled {
+            return Err(AppError::Handler(HandlerError::FeatureFlag(
+                FeatureFlagHandlerError::Disabled(F::KEY.to_string()),
+            )));
+        }
+
+        Ok(Self(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::feature_flag::FeatureFlagInput,
+        utils::{test_app_state::create_test_app_state, test_db::create_persisted_address},
+    };
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+        Extension, Router,
+    };
+    use tower::ServiceExt;
+
+    struct Beta;
+    impl FeatureFlagKey for Beta {
+        const KEY: &'static str = "beta";
+    }
+
+    async fn beta_handler(_: RequireFeature<Beta>) -> &'static str {
+        "ok"
+    }
+
+    #[tokio::test]
+    async fn test_require_feature() {
+        let state = create_test_app_state().await;
+
+        let user = create_persisted_address(&state.db.addresses, "beta").await;
+        let router = Router::new()
+            .route("/", get(beta_handler))
+            .layer(Extension(user.clone()))
+            .with_state(state.clone());
+
+        let call = |router: Router| async move {
+            router
+                .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+                .await
+                .unwrap()
+                .status()
+        };
+
+        assert_eq!(call(router.clone()).await, StatusCode::FORBIDDEN);
+
+        state
+            .db
+            .feature_flags
+            .upsert(
+                "beta",
+                &FeatureFlagInput {
+                    description: String::new(),
+                    enabled: true,
+                    rollout_percentage: 0,
+                    allowlist: vec![user.quan_address.0.clone()],
+                },
+                "admin",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(call(router).await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,331 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    handlers::HandlerError, http_server::AppState, models::address::Address,
+    repositories::idempotency_record::IdempotencyRecordRepository, AppError,
+};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Set on responses that are replays of an earlier request.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+const IDEMPOTENCY_KEY_MAX_LEN: usize = 255;
+/// Keys can be reused after this long, once [`sweep_expired_records`] dropped their record.
+const IDEMPOTENCY_RETENTION_HOURS: i64 = 24;
+/// How often records past their retention are dropped.
+const SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+/// Request bodies of idempotent endpoints are small JSON documents.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IdempotencyError {
+    #[error("Idempotency-Key must be 1-{max} visible ASCII characters", max = IDEMPOTENCY_KEY_MAX_LEN)]
+    InvalidKey,
+    #[error("Request body is too large")]
+    BodyTooLarge,
+    #[error("Idempotency-Key was already used for a different request")]
+    KeyReused,
+    #[error("A request with this Idempotency-Key is still being processed")]
+    InProgress,
+    #[error("Request with this Idempotency-Key failed, retry it")]
+    Released,
+}
+
+fn fail(err: IdempotencyError) -> Response {
+    AppError::Handler(HandlerError::Idempotency(err)).into_response()
+}
+
+fn request_hash(method: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update([0]);
+    hasher.update(path.as_bytes());
+    hasher.update([0]);
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+/// Makes retries of a mutating request safe. When the request has an `Idempotency-Key` header, the first
+/// response for that key is stored and returned again for later requests with the same key, without running
+/// the handler. Keys are scoped to the authenticated address, so the layer must sit inside `jwt_auth`.
+///
+/// Reusing a key for a different request is rejected with 422, and a retry that arrives while the first
+/// request is still being handled gets 409. Server errors are not stored so the request can be retried, and a
+/// request that never finished, because the client disconnected or the handler panicked, can be retried once
+/// its `idempotency.pending_lease_secs` lease ran out. Replays keep the original status and Content-Type.
+pub async fn idempotency(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(req).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= IDEMPOTENCY_KEY_MAX_LEN => key.to_string(),
+        _ => return fail(IdempotencyError::InvalidKey),
+    };
+    let Some(owner) = req
+        .extensions()
+        .get::<Address>()
+        .map(|user| user.quan_address.0.clone())
+    else {
+        tracing::error!("Idempotency layer used without authentication on {}", req.uri().path());
+        return next.run(req).await;
+    };
+
+    let (parts, body) = req.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => return fail(IdempotencyError::BodyTooLarge),
+    };
+    let hash = request_hash(parts.method.as_str(), parts.uri.path(), &body);
+    let records = &state.db.idempotency_records;
+
+    let abandoned_before = Utc::now() - Duration::seconds(state.config.idempotency.pending_lease_secs as i64);
+    match records.try_start(&owner, &key, &hash, abandoned_before).await {
+        Ok(true) => {}
+        Ok(false) => return replay(&state, &owner, &key, &hash).await,
+        Err(e) => {
+            tracing::error!("Failed to store idempotency key: {}", e);
+            return AppError::Database(e).into_response();
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    if response.status().is_server_error() {
+        if let Err(e) = records.delete(&owner, &key).await {
+            tracing::warn!("Failed to release idempotency key: {}", e);
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for idempotency key: {}", e);
+            return AppError::Server(e.to_string()).into_response();
+        }
+    };
+
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    if let Err(e) = records
+        .complete(&owner, &key, parts.status.as_u16(), &body, content_type)
+        .await
+    {
+        tracing::warn!("Failed to store idempotent response: {}", e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+async fn replay(state: &AppState, owner: &str, key: &str, hash: &str) -> Response {
+    let record = match state.db.idempotency_records.find(owner, key).await {
+        Ok(Some(record)) => record,
+        // Released by a failed first request in the meantime
+        Ok(None) => return fail(IdempotencyError::Released),
+        Err(e) => {
+            tracing::error!("Failed to load idempotency record: {}", e);
+            return AppError::Database(e).into_response();
+        }
+    };
+
+    if record.request_hash != hash {
+        return fail(IdempotencyError::KeyReused);
+    }
+
+    let (Some(status_code), Some(body)) = (record.status_code, record.response_body) else {
+        return fail(IdempotencyError::InProgress);
+    };
+
+    let status = StatusCode::from_u16(status_code as u16).unwrap_or(StatusCode::OK);
+    // Records stored before the Content-Type was kept all hold JSON
+    let content_type = record
+        .content_type
+        .and_then(|value| HeaderValue::from_str(&value).ok())
+        .unwrap_or(HeaderValue::from_static("application/json"));
+
+    let mut response = (status, body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, content_type);
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+/// Drops records past their retention every [`SWEEP_INTERVAL`] until `token` is cancelled, so keyed requests
+/// don't wait on the cleanup.
+pub async fn sweep_expired_records(records: IdempotencyRecordRepository, token: CancellationToken) {
+    loop {
+        let cutoff = Utc::now() - Duration::hours(IDEMPOTENCY_RETENTION_HOURS);
+        if let Err(e) = records.delete_created_before(cutoff).await {
+            tracing::warn!("Failed to clean up expired idempotency records: {}", e);
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(SWEEP_INTERVAL) => {}
+            _ = token.cancelled() => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::create_persisted_address};
+    use axum::{handler::Handler, middleware, routing::post, Extension, Json, Router};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tower::ServiceExt;
+
+    async fn counting_handler(State(calls): State<Arc<AtomicUsize>>) -> (StatusCode, Json<serde_json::Value>) {
+        let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        (StatusCode::CREATED, Json(serde_json::json!({ "call": call })))
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_key_replays_first_response() {
+        let state = create_test_app_state().await;
+        let user = create_persisted_address(&state.db.addresses, "idem").await;
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let router = Router::new()
+            .route(
+                "/mutate",
+                post(counting_handler.layer(middleware::from_fn_with_state(state.clone(), idempotency))),
+            )
+            .layer(Extension(user))
+            .with_state(calls.clone());
+
+        let send = |router: Router, key: &'static str, body: &'static str| async move {
+            router
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/mutate")
+                        .header(IDEMPOTENCY_KEY_HEADER, key)
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+        };
+
+        let first = send(router.clone(), "key-1", "{}").await;
+        assert_eq!(first.status(), StatusCode::CREATED);
+
+        let retry = send(router.clone(), "key-1", "{}").await;
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert!(retry.headers().contains_key(IDEMPOTENT_REPLAYED_HEADER));
+        let body = to_bytes(retry.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], br#"{"call":1}"#);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let reused = send(router.clone(), "key-1", r#"{"other":true}"#).await;
+        assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(reused.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(body["code"], "IDEMPOTENCY_KEY_REUSED");
+
+        let other_key = send(router, "key-2", "{}").await;
+        assert_eq!(other_key.status(), StatusCode::CREATED);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_replay_keeps_the_content_type() {
+        let state = create_test_app_state().await;
+        let user = create_persisted_address(&state.db.addresses, "idem_problem").await;
+
+        async fn rejecting_handler() -> Result<StatusCode, AppError> {
+            Err(AppError::Handler(HandlerError::InvalidBody("rejected".to_string())))
+        }
+
+        let router = Router::new()
+            .route(
+                "/mutate",
+                post(rejecting_handler.layer(middleware::from_fn_with_state(state.clone(), idempotency))),
+            )
+            .layer(Extension(user))
+            .with_state(state);
+        let send = || {
+            router.clone().oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/mutate")
+                    .header(IDEMPOTENCY_KEY_HEADER, "key-1")
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+        };
+
+        let first = send().await.unwrap();
+        assert_eq!(first.status(), StatusCode::BAD_REQUEST);
+        let content_type = first.headers()[header::CONTENT_TYPE].clone();
+        assert_eq!(content_type, "application/problem+json");
+
+        let retry = send().await.unwrap();
+        assert!(retry.headers().contains_key(IDEMPOTENT_REPLAYED_HEADER));
+        assert_eq!(retry.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(retry.headers()[header::CONTENT_TYPE], content_type);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_pending_key_can_be_claimed_again() {
+        let state = create_test_app_state().await;
+        let user = create_persisted_address(&state.db.addresses, "idem_abandoned").await;
+        let owner = user.quan_address.0.clone();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let router = Router::new()
+            .route(
+                "/mutate",
+                post(counting_handler.layer(middleware::from_fn_with_state(state.clone(), idempotency))),
+            )
+            .layer(Extension(user))
+            .with_state(calls.clone());
+        let send = |key: &'static str| {
+            router.clone().oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/mutate")
+                    .header(IDEMPOTENCY_KEY_HEADER, key)
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+        };
+
+        // Pending records left behind by requests that never finished
+        let hash = request_hash("POST", "/mutate", b"{}");
+        let records = &state.db.idempotency_records;
+        assert!(records.try_start(&owner, "in-flight", &hash, Utc::now()).await.unwrap());
+        assert!(records.try_start(&owner, "abandoned", &hash, Utc::now()).await.unwrap());
+        sqlx::query(
+            "UPDATE idempotency_records SET created_at = NOW() - INTERVAL '1 hour' WHERE idempotency_key = 'abandoned'",
+        )
+        .execute(&state.db.pool)
+        .await
+        .unwrap();
+
+        assert_eq!(send("in-flight").await.unwrap().status(), StatusCode::CONFLICT);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        assert_eq!(send("abandoned").await.unwrap().status(), StatusCode::CREATED);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        // Completed now, so it replays
+        let retry = send("abandoned").await.unwrap();
+        assert!(retry.headers().contains_key(IDEMPOTENT_REPLAYED_HEADER));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
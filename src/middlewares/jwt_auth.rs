@@ -1,108 +1,111 @@
 use axum::{
     extract::{Request, State},
-    http::StatusCode,
+    http::Method,
     middleware::Next,
     response::IntoResponse,
-    Json,
 };
 
 use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::de::DeserializeOwned;
 use uuid::Uuid;
 
 use crate::{
-    handlers::ErrorResponse,
+    handlers::{auth::AuthHandlerError, HandlerError},
     http_server::AppState,
-    models::{admin::AdminClaims, auth::TokenClaims},
+    models::{
+        admin::{AdminClaims, AdminRole},
+        auth::TokenClaims,
+    },
     utils::jwt::extract_jwt_token_from_request,
+    AppError,
 };
 
+fn unauthorized(message: impl Into<String>) -> AppError {
+    AppError::Handler(HandlerError::Auth(AuthHandlerError::Unauthorized(message.into())))
+}
+
 pub async fn jwt_auth(
     State(state): State<AppState>,
     mut req: Request,
     next: Next,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<impl IntoResponse, AppError> {
     let token = extract_jwt_token_from_request(&req)?;
 
-    let claims = decode::<TokenClaims>(
-        &token,
-        &DecodingKey::from_secret(state.config.jwt.secret.as_ref()),
-        &Validation::default(),
-    )
-    .map_err(|_| {
-        let json_error = ErrorResponse {
-            status: "fail",
-            message: "Invalid token".to_string(),
-        };
-        (StatusCode::UNAUTHORIZED, Json(json_error))
-    })?
-    .claims;
+    let claims = decode_claims::<TokenClaims>(&state, &state.config.jwt.secret, &token).await?;
 
-    let user_id = &claims.sub;
+    if let Some(sid) = &claims.sid {
+        let session_id = Uuid::parse_str(sid).map_err(|_| unauthorized("Invalid token"))?;
 
-    let user = state.db.addresses.find_by_id(user_id).await.map_err(|e| {
-        let json_error = ErrorResponse {
-            status: "fail",
-            message: format!("Error fetching user from database: {}", e),
-        };
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(json_error))
-    })?;
+        if !state.db.sessions.is_active(&session_id).await? {
+            return Err(unauthorized("The session of this token has ended"));
+        }
+    }
 
-    let user = user.ok_or_else(|| {
-        let json_error = ErrorResponse {
-            status: "fail",
-            message: "The user belonging to this token not exists".to_string(),
-        };
-        (StatusCode::UNAUTHORIZED, Json(json_error))
-    })?;
+    let user_id = &claims.sub;
+
+    let user = state
+        .db
+        .addresses
+        .find_by_id(user_id)
+        .await?
+        .ok_or_else(|| unauthorized("The user belonging to this token not exists"))?;
 
     req.extensions_mut().insert(user);
+    req.extensions_mut().insert(claims);
     Ok(next.run(req).await)
 }
 
+/// Claims of a token signed with one of the accepted values of the secret, so tokens signed just before a
+/// rotation stay valid.
+async fn decode_claims<T: DeserializeOwned>(state: &AppState, secret: &str, token: &str) -> Result<T, AppError> {
+    let secrets = state.secrets.accepted(secret).await?;
+
+    secrets
+        .iter()
+        .find_map(|secret| {
+            decode::<T>(
+                token,
+                &DecodingKey::from_secret(secret.as_ref()),
+                &Validation::default(),
+            )
+            .ok()
+        })
+        .map(|data| data.claims)
+        .ok_or_else(|| unauthorized("Invalid token"))
+}
+
+fn is_read_only(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
 pub async fn jwt_admin_auth(
     State(state): State<AppState>,
     mut req: Request,
     next: Next,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<impl IntoResponse, AppError> {
     let token = extract_jwt_token_from_request(&req)?;
 
-    let claims = decode::<AdminClaims>(
-        &token,
-        &DecodingKey::from_secret(state.config.jwt.admin_secret.as_ref()),
-        &Validation::default(),
-    )
-    .map_err(|_| {
-        let json_error = ErrorResponse {
-            status: "fail",
-            message: "Invalid token".to_string(),
-        };
-        (StatusCode::UNAUTHORIZED, Json(json_error))
-    })?
-    .claims;
-
-    let admin_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-        let json_error = ErrorResponse {
-            status: "fail",
-            message: "Invalid token".to_string(),
-        };
-        (StatusCode::UNAUTHORIZED, Json(json_error))
-    })?;
+    let claims = decode_claims::<AdminClaims>(&state, &state.config.jwt.admin_secret, &token).await?;
 
-    let admin = state.db.admin.find_by_id(&admin_id).await.map_err(|e| {
-        let json_error = ErrorResponse {
-            status: "fail",
-            message: format!("Error fetching admin from database: {}", e),
-        };
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(json_error))
-    })?;
+    let admin_id = Uuid::parse_str(&claims.sub).map_err(|_| unauthorized("Invalid token"))?;
 
-    let admin = admin.ok_or_else(|| {
-        let json_error = ErrorResponse {
-            status: "fail",
-            message: "The admin belonging to this token not exists".to_string(),
-        };
-        (StatusCode::UNAUTHORIZED, Json(json_error))
-    })?;
+    let admin = state
+        .db
+        .admin
+        .find_by_id(&admin_id)
+        .await?
+        .ok_or_else(|| unauthorized("The admin belonging to this token not exists"))?;
+
+    if admin.is_disabled() {
+        return Err(unauthorized("The admin belonging to this token is disabled"));
+    }
+
+    // Viewers only read, handlers that need more than operator access check the role themselves
+    if !admin.has_role(AdminRole::Operator) && !is_read_only(req.method()) {
+        return Err(AppError::Handler(HandlerError::Auth(AuthHandlerError::Forbidden(
+            format!("The {} role has read only access", admin.role.as_str()),
+        ))));
+    }
 
     req.extensions_mut().insert(admin);
     Ok(next.run(req).await)
@@ -115,7 +118,7 @@ mod tests {
         models::{address::Address, admin::Admin},
         utils::{
             test_app_state::{create_test_app_state, generate_test_token}, // Assuming you have these from previous context
-            test_db::{create_persisted_address, reset_database},
+            test_db::create_persisted_address,
         },
     };
     use axum::{
@@ -142,7 +145,6 @@ mod tests {
     #[tokio::test]
     async fn test_jwt_auth_success() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         // 1. Setup User
         let user = create_persisted_address(&state.db.addresses, "auth_user_1").await;
@@ -202,7 +204,6 @@ mod tests {
     #[tokio::test]
     async fn test_jwt_auth_fails_user_not_found_in_db() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         // Generate token for a user ID that does NOT exist in DB
         let token = generate_test_token(&state.config.jwt.secret, "non_existent_user_id");
@@ -227,7 +228,52 @@ mod tests {
 
         let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
         let body_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
-        assert_eq!(body_json["message"], "The user belonging to this token not exists");
+        assert_eq!(body_json["code"], "UNAUTHORIZED");
+        assert_eq!(body_json["detail"], "The user belonging to this token not exists");
+    }
+
+    #[tokio::test]
+    async fn test_jwt_auth_fails_revoked_session() {
+        let state = create_test_app_state().await;
+        let user = create_persisted_address(&state.db.addresses, "auth_user_session").await;
+        let session = state
+            .db
+            .sessions
+            .create(&user.quan_address.0, "session-hash", Utc::now() + Duration::hours(1))
+            .await
+            .unwrap();
+
+        let claims = TokenClaims {
+            sub: user.quan_address.0.clone(),
+            iat: Utc::now().timestamp() as usize,
+            exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+            sid: Some(session.id.to_string()),
+        };
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(state.config.jwt.secret.as_bytes()),
+        )
+        .unwrap();
+
+        let router = Router::new()
+            .route("/protected", get(protected_handler))
+            .layer(from_fn_with_state(state.clone(), jwt_auth))
+            .with_state(state.clone());
+        let send = |router: Router| {
+            router.oneshot(
+                Request::builder()
+                    .uri("/protected")
+                    .header(http::header::AUTHORIZATION, format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        assert_eq!(send(router.clone()).await.unwrap().status(), StatusCode::OK);
+
+        state.db.sessions.revoke(&session.id).await.unwrap();
+        assert_eq!(send(router).await.unwrap().status(), StatusCode::UNAUTHORIZED);
     }
 
     #[tokio::test]
@@ -248,6 +294,10 @@ mod tests {
         // Expecting whatever extract_jwt_token_from_request returns (usually 400 or 401)
         // Assuming 401 for this assert, check your extraction logic if it returns 400
         assert!(response.status() == StatusCode::UNAUTHORIZED || response.status() == StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            "application/problem+json"
+        );
     }
 
     // --- ADMIN TESTS ---
@@ -255,7 +305,6 @@ mod tests {
     #[tokio::test]
     async fn test_jwt_admin_auth_success() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         // 1. Setup Admin in DB
         // Use your actual method to create an admin.
@@ -305,6 +354,56 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_jwt_admin_auth_enforces_role_and_disabled() {
+        let state = create_test_app_state().await;
+        let viewer = state
+            .db
+            .admin
+            .create("viewer_user", "hash", AdminRole::Viewer)
+            .await
+            .unwrap();
+
+        let claims = AdminClaims {
+            sub: viewer.id.to_string(),
+            exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+            iat: Utc::now().timestamp() as usize,
+        };
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(state.config.jwt.admin_secret.as_bytes()),
+        )
+        .unwrap();
+
+        let router = Router::new()
+            .route(
+                "/admin/protected",
+                get(protected_admin_handler).post(protected_admin_handler),
+            )
+            .layer(from_fn_with_state(state.clone(), jwt_admin_auth))
+            .with_state(state.clone());
+        let send = |router: Router, method: &str| {
+            router.oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri("/admin/protected")
+                    .header(http::header::AUTHORIZATION, format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        assert_eq!(send(router.clone(), "GET").await.unwrap().status(), StatusCode::OK);
+        assert_eq!(
+            send(router.clone(), "POST").await.unwrap().status(),
+            StatusCode::FORBIDDEN
+        );
+
+        state.db.admin.set_disabled(&viewer.id, true).await.unwrap();
+        assert_eq!(send(router, "GET").await.unwrap().status(), StatusCode::UNAUTHORIZED);
+    }
+
     #[tokio::test]
     async fn test_jwt_admin_auth_fails_wrong_secret() {
         let state = create_test_app_state().await;
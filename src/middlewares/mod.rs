@@ -1 +1,6 @@
+pub mod feature_flag;
+pub mod idempotency;
 pub mod jwt_auth;
+pub mod rate_limit;
+pub mod request_id;
+pub mod request_logging;
@@ -0,0 +1,243 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    config::RateLimitRule,
+    handlers::HandlerError,
+    http_server::AppState,
+    models::{address::Address, admin::Admin},
+    AppError,
+};
+
+pub const RATE_LIMITED_AUTH_CHALLENGE: &str = "auth_challenge";
+pub const RATE_LIMITED_AUTH_VERIFY: &str = "auth_verify";
+pub const RATE_LIMITED_AUTH_REFRESH: &str = "auth_refresh";
+pub const RATE_LIMITED_ADMIN_LOGIN: &str = "admin_login";
+pub const RATE_LIMITED_FRAUD_RULE_EVALUATION: &str = "fraud_rule_evaluation";
+
+/// Full buckets are dropped once this many are tracked, they behave the same as a missing one.
+const SWEEP_THRESHOLD: usize = 10_000;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    /// Rule the bucket was last used with, rules can change at runtime.
+    rule: RateLimitRule,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        let capacity = self.rule.capacity as f64;
+        self.tokens = (self.tokens + elapsed * self.rule.refill_per_minute as f64 / 60.0).min(capacity);
+        self.refilled_at = now;
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.rule.capacity as f64
+    }
+}
+
+/// Token buckets per route and identity, kept in process memory.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<(&'static str, String), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes a token from the bucket of `identity` on `route`. When the bucket is empty, returns how long until
+    /// the next token is available.
+    pub fn try_acquire(
+        &self,
+        route: &'static str,
+        identity: &str,
+        rule: &RateLimitRule,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if buckets.len() >= SWEEP_THRESHOLD {
+            buckets.retain(|_, bucket| {
+                bucket.refill(now);
+                !bucket.is_full()
+            });
+        }
+
+        let bucket = buckets.entry((route, identity.to_string())).or_insert_with(|| Bucket {
+            tokens: rule.capacity as f64,
+            refilled_at: now,
+            rule: *rule,
+        });
+        bucket.rule = *rule;
+        bucket.refill(now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+
+        if rule.refill_per_minute == 0 {
+            return Err(Duration::from_secs(60));
+        }
+        let missing = 1.0 - bucket.tokens;
+        Err(Duration::from_secs_f64(missing * 60.0 / rule.refill_per_minute as f64))
+    }
+}
+
+/// State of [`rate_limit`]: the app state and the name of the limited route.
+#[derive(Debug, Clone)]
+pub struct RateLimitScope {
+    pub state: AppState,
+    pub route: &'static str,
+}
+
+impl RateLimitScope {
+    pub fn new(state: AppState, route: &'static str) -> Self {
+        Self { state, route }
+    }
+}
+
+/// Who a request is counted against: the authenticated address or admin when the route sits behind
+/// `jwt_auth`/`jwt_admin_auth` (layer this middleware inside them), otherwise the client IP.
+fn identity(req: &Request, trust_forwarded_for: bool) -> String {
+    if let Some(address) = req.extensions().get::<Address>() {
+        return format!("address:{}", address.quan_address.0);
+    }
+    if let Some(admin) = req.extensions().get::<Admin>() {
+        return format!("admin:{}", admin.username);
+    }
+
+    let forwarded = trust_forwarded_for
+        .then(|| req.headers().get("x-forwarded-for"))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty());
+
+    let ip = forwarded.or_else(|| {
+        req.extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+    });
+
+    format!("ip:{}", ip.unwrap_or_else(|| "unknown".to_string()))
+}
+
+/// Rejects requests with 429 once the caller's bucket for the route is empty. The rule is read from the
+/// effective settings on every request, so limits can be tuned at runtime through the settings API.
+pub async fn rate_limit(State(scope): State<RateLimitScope>, req: Request, next: Next) -> Response {
+    let config = scope.state.settings.current().rate_limit.clone();
+    let Some(rule) = config.routes.get(scope.route).copied() else {
+        return next.run(req).await;
+    };
+
+    let identity = identity(&req, config.trust_forwarded_for);
+
+    match scope
+        .state
+        .rate_limiter
+        .try_acquire(scope.route, &identity, &rule, Instant::now())
+    {
+        Ok(()) => next.run(req).await,
+        Err(retry_after) => {
+            tracing::warn!("Rate limit hit on '{}' by {}", scope.route, identity);
+
+            let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            let err = AppError::Handler(HandlerError::RateLimited(format!(
+                "Too many requests, retry in {} seconds",
+                retry_after_secs
+            )));
+
+            ([(header::RETRY_AFTER, retry_after_secs.to_string())], err).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_app_state::create_test_app_state;
+    use axum::{body::Body, handler::Handler, http::StatusCode, middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    const RULE: RateLimitRule = RateLimitRule {
+        capacity: 2,
+        refill_per_minute: 60,
+    };
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let limiter = RateLimiter::new();
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire("route", "alice", &RULE, start).is_ok());
+        assert!(limiter.try_acquire("route", "alice", &RULE, start).is_ok());
+        let retry_after = limiter.try_acquire("route", "alice", &RULE, start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(1));
+
+        // Other identities and routes have their own buckets
+        assert!(limiter.try_acquire("route", "bob", &RULE, start).is_ok());
+        assert!(limiter.try_acquire("other", "alice", &RULE, start).is_ok());
+
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.try_acquire("route", "alice", &RULE, later).is_ok());
+        assert!(limiter.try_acquire("route", "alice", &RULE, later).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_middleware_rejects_when_empty() {
+        let state = create_test_app_state().await;
+        let capacity = state.config.rate_limit.routes[RATE_LIMITED_AUTH_CHALLENGE].capacity;
+
+        let router = Router::new()
+            .route(
+                "/limited",
+                post((|| async { "ok" }).layer(middleware::from_fn_with_state(
+                    RateLimitScope::new(state.clone(), RATE_LIMITED_AUTH_CHALLENGE),
+                    rate_limit,
+                ))),
+            )
+            .with_state(state);
+
+        let send = |router: Router| async move {
+            router
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/limited")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+        };
+
+        for _ in 0..capacity {
+            assert_eq!(send(router.clone()).await.status(), StatusCode::OK);
+        }
+
+        let response = send(router).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "RATE_LIMITED");
+    }
+}
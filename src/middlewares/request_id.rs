@@ -0,0 +1,87 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Caller supplied IDs longer than this are replaced, they end up in every log line of the request.
+const REQUEST_ID_MAX_LEN: usize = 128;
+
+/// Correlation ID of the current request, available as a request extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= REQUEST_ID_MAX_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+/// Gives every request a correlation ID, taken from `X-Request-Id` when the caller sent a usable one and
+/// generated otherwise. The handler runs inside a `request` span carrying the ID, so everything it logs,
+/// including the GraphQL client and repositories, can be correlated. Tasks spawned from a handler only keep
+/// the ID when they are instrumented with the current span. The ID is echoed in the response header.
+pub async fn request_id(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    req.extensions_mut().insert(RequestId(id.clone()));
+    let span = tracing::info_span!("request", request_id = %id, method = %req.method(), path = %req.uri().path());
+
+    let mut response = next.run(req).instrument(span).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    async fn echo_request_id(Extension(RequestId(id)): Extension<RequestId>) -> String {
+        id
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_honored_or_generated() {
+        let router = Router::new()
+            .route("/echo", get(echo_request_id))
+            .layer(axum::middleware::from_fn(request_id));
+
+        let send = |router: Router, id: Option<&'static str>| async move {
+            let mut request = Request::builder().uri("/echo");
+            if let Some(id) = id {
+                request = request.header(REQUEST_ID_HEADER, id);
+            }
+            router.oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+        };
+
+        let response = send(router.clone(), Some("client-id-1")).await;
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "client-id-1");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"client-id-1");
+
+        let response = send(router.clone(), Some("has spaces in it")).await;
+        let generated = response.headers()[REQUEST_ID_HEADER].to_str().unwrap();
+        assert!(Uuid::parse_str(generated).is_ok());
+
+        let response = send(router, None).await;
+        let generated = response.headers()[REQUEST_ID_HEADER].to_str().unwrap();
+        assert!(Uuid::parse_str(generated).is_ok());
+    }
+}
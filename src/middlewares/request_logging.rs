@@ -0,0 +1,212 @@
+use std::time::Instant;
+
+use axum::{
+    body::{to_bytes, Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+
+use crate::{config::RequestLogMode, http_server::AppState};
+
+const REDACTED: &str = "[REDACTED]";
+/// Logged bodies are cut off after this many characters. Larger bodies aren't buffered at all.
+const MAX_LOGGED_BODY_CHARS: usize = 4096;
+
+/// Logs requests according to the mode configured for their route group. The mode is read from the
+/// effective settings on every request, so it can be switched at runtime through the settings API.
+pub async fn log_requests(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let config = state.settings.current().request_logging.clone();
+    let group = route_group(req.uri().path()).to_string();
+    let mode = config.mode_for(&group);
+
+    if mode == RequestLogMode::None {
+        return next.run(req).await;
+    }
+
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let query = req
+        .uri()
+        .query()
+        .map(|q| redact_query(q, &config.redacted_fields))
+        .unwrap_or_default();
+    let started = Instant::now();
+
+    if mode == RequestLogMode::Metadata {
+        let response = next.run(req).await;
+        tracing::info!(
+            group = %group,
+            method = %method,
+            path = %path,
+            query = %query,
+            status = response.status().as_u16(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            "request"
+        );
+        return response;
+    }
+
+    let (parts, body) = req.into_parts();
+    let (request_body, logged_request_body) = capture(body, &parts.headers, &config.redacted_fields).await;
+
+    let response = next.run(Request::from_parts(parts, request_body)).await;
+
+    let (parts, body) = response.into_parts();
+    let (response_body, logged_response_body) = capture(body, &parts.headers, &config.redacted_fields).await;
+
+    tracing::info!(
+        group = %group,
+        method = %method,
+        path = %path,
+        query = %query,
+        status = parts.status.as_u16(),
+        latency_ms = started.elapsed().as_millis() as u64,
+        request_body = %logged_request_body,
+        response_body = %logged_response_body,
+        "request"
+    );
+
+    Response::from_parts(parts, response_body)
+}
+
+/// Buffers a body to log it, returning the body to pass on. Only bodies of a known size small enough to log
+/// whole are buffered, server-sent events, streamed exports and uploads are passed through untouched.
+async fn capture(body: Body, headers: &HeaderMap, redacted_fields: &[String]) -> (Body, String) {
+    let event_stream = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream"));
+
+    match body.size_hint().exact() {
+        Some(len) if !event_stream && len <= MAX_LOGGED_BODY_CHARS as u64 => {
+            match to_bytes(body, MAX_LOGGED_BODY_CHARS).await {
+                Ok(bytes) => {
+                    let logged = render_body(&bytes, redacted_fields);
+                    (Body::from(bytes), logged)
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to buffer body for logging: {}", e);
+                    (Body::empty(), String::new())
+                }
+            }
+        }
+        Some(len) => (body, format!("<{} bytes body not logged>", len)),
+        None => (body, "<streamed body not logged>".to_string()),
+    }
+}
+
+/// First path segment, ignoring an `/api` prefix if the router was not nested.
+fn route_group(path: &str) -> &str {
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    match segments.next() {
+        Some("api") => segments.next().unwrap_or(""),
+        Some(segment) => segment,
+        None => "",
+    }
+}
+
+fn is_redacted(key: &str, redacted_fields: &[String]) -> bool {
+    redacted_fields.iter().any(|f| f.eq_ignore_ascii_case(key))
+}
+
+fn redact_query(query: &str, redacted_fields: &[String]) -> String {
+    query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if is_redacted(key, redacted_fields) => format!("{}={}", key, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn redact_json(value: &mut Value, redacted_fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if is_redacted(key, redacted_fields) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(field, redacted_fields);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| redact_json(item, redacted_fields)),
+        _ => {}
+    }
+}
+
+/// Renders a body for logging. Only JSON bodies are logged, anything else could carry secrets we can't redact.
+fn render_body(body: &Bytes, redacted_fields: &[String]) -> String {
+    if body.is_empty() {
+        return String::new();
+    }
+
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut json) => {
+            redact_json(&mut json, redacted_fields);
+            json.to_string().chars().take(MAX_LOGGED_BODY_CHARS).collect()
+        }
+        Err(_) => format!("<{} bytes non-JSON body>", body.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields() -> Vec<String> {
+        vec!["signature".to_string(), "token".to_string()]
+    }
+
+    #[test]
+    fn test_route_group() {
+        assert_eq!(route_group("/auth/verify"), "auth");
+        assert_eq!(route_group("/api/raid-quests/1/teams"), "raid-quests");
+        assert_eq!(route_group("/"), "");
+    }
+
+    #[test]
+    fn test_redacts_nested_json_and_query() {
+        let body = Bytes::from(json!({"address": "qz1", "Signature": "abc", "inner": [{"token": "t"}]}).to_string());
+        let rendered: Value = serde_json::from_str(&render_body(&body, &fields())).unwrap();
+
+        assert_eq!(rendered["address"], "qz1");
+        assert_eq!(rendered["Signature"], REDACTED);
+        assert_eq!(rendered["inner"][0]["token"], REDACTED);
+
+        assert_eq!(
+            redact_query("page=1&token=secret", &fields()),
+            format!("page=1&token={}", REDACTED)
+        );
+        assert_eq!(
+            render_body(&Bytes::from("token=secret"), &fields()),
+            "<12 bytes non-JSON body>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_captures_only_small_bodies_of_known_size() {
+        let (body, logged) = capture(Body::from(r#"{"token":"t"}"#), &HeaderMap::new(), &fields()).await;
+        assert_eq!(logged, format!(r#"{{"token":"{}"}}"#, REDACTED));
+        assert_eq!(to_bytes(body, usize::MAX).await.unwrap(), r#"{"token":"t"}"#);
+
+        let chunks = vec![Ok::<_, std::io::Error>(Bytes::from_static(b"data: 1\n\n"))];
+        let (body, logged) = capture(
+            Body::from_stream(tokio_stream::iter(chunks)),
+            &HeaderMap::new(),
+            &fields(),
+        )
+        .await;
+        assert_eq!(logged, "<streamed body not logged>");
+        assert_eq!(to_bytes(body, usize::MAX).await.unwrap(), "data: 1\n\n");
+
+        let large = "x".repeat(MAX_LOGGED_BODY_CHARS + 1);
+        let (_, logged) = capture(Body::from(large), &HeaderMap::new(), &fields()).await;
+        assert_eq!(logged, format!("<{} bytes body not logged>", MAX_LOGGED_BODY_CHARS + 1));
+    }
+}
@@ -0,0 +1,197 @@
+//! A fake GraphQL indexer serving synthetic transfers, for running the stack locally without a chain indexer.
+
+use axum::{routing::post, Json, Router};
+use serde_json::{json, Value};
+
+use crate::services::graphql_client::{Account, Transfer};
+
+#[derive(Debug, Clone, Copy)]
+pub struct MockIndexerOptions {
+    /// Number of distinct accounts sending and receiving transfers.
+    pub accounts: usize,
+    pub transfers: usize,
+    /// Same seed, same data.
+    pub seed: u64,
+}
+
+/// SplitMix64, good enough for stable synthetic data without pulling in an RNG.
+fn next_u64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+pub fn synthetic_accounts(count: usize, seed: u64) -> Vec<String> {
+    let mut state = seed;
+    (0..count)
+        .map(|_| {
+            format!(
+                "qz{:016x}{:016x}{:016x}",
+                next_u64(&mut state),
+                next_u64(&mut state),
+                next_u64(&mut state)
+            )
+        })
+        .collect()
+}
+
+pub fn synthetic_transfers(options: &MockIndexerOptions) -> Vec<Transfer> {
+    let accounts = synthetic_accounts(options.accounts.max(2), options.seed);
+    let mut state = options.seed ^ 0xA5A5_A5A5_A5A5_A5A5;
+
+    (0..options.transfers)
+        .map(|i| {
+            let from = (next_u64(&mut state) % accounts.len() as u64) as usize;
+            // Never send to self.
+            let offset = 1 + (next_u64(&mut state) % (accounts.len() as u64 - 1)) as usize;
+            let to = (from + offset) % accounts.len();
+            let amount = 1 + next_u64(&mut state) % 1_000_000_000_000_000;
+
+            Transfer {
+                id: format!("{:010}-{:06}", 1000 + i * 3, i),
+                amount: amount.to_string(),
+                from: Account {
+                    id: accounts[from].clone(),
+                },
+                to: Account {
+                    id: accounts[to].clone(),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Serves one `transfersConnection` page. Cursors are offsets, like the ones subsquid hands out.
+fn transfers_page(transfers: &[Transfer], variables: &Value) -> Value {
+    let start = variables["after"]
+        .as_str()
+        .and_then(|cursor| cursor.parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(transfers.len());
+    let first = variables["first"]
+        .as_u64()
+        .map_or(transfers.len(), |first| first as usize);
+    let end = start.saturating_add(first).min(transfers.len());
+
+    let edges: Vec<Value> = transfers[start..end].iter().map(|t| json!({ "node": t })).collect();
+    let end_cursor = (end > start).then(|| end.to_string());
+
+    json!({
+        "transfersConnection": {
+            "edges": edges,
+            "pageInfo": { "hasNextPage": end < transfers.len(), "endCursor": end_cursor }
+        }
+    })
+}
+
+/// Router answering GraphQL POSTs on `/graphql`. Only the `transfersConnection` query is supported.
+pub fn mock_indexer_router(options: MockIndexerOptions) -> Router {
+    let transfers = synthetic_transfers(&options);
+
+    Router::new().route(
+        "/graphql",
+        post(move |Json(payload): Json<Value>| async move {
+            let query = payload["query"].as_str().unwrap_or_default();
+
+            if query.contains("transfersConnection") {
+                Json(json!({ "data": transfers_page(&transfers, &payload["variables"]) }))
+            } else {
+                Json(json!({ "errors": [{ "message": "Unsupported query, the mock indexer only serves transfers" }] }))
+            }
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::address::QuanAddress,
+        services::graphql_client::{GraphqlClient, GraphqlOperation, TransfersQuery},
+        utils::test_app_state::create_test_app_state,
+    };
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    fn options() -> MockIndexerOptions {
+        MockIndexerOptions {
+            accounts: 5,
+            transfers: 20,
+            seed: 7,
+        }
+    }
+
+    #[test]
+    fn test_synthetic_transfers_are_deterministic_and_valid() {
+        let first = synthetic_transfers(&options());
+        let second = synthetic_transfers(&options());
+
+        assert_eq!(first.len(), 20);
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a.id, b.id);
+            assert_eq!(a.from.id, b.from.id);
+            assert_eq!(a.amount, b.amount);
+            assert_ne!(a.from.id, a.to.id);
+            assert!(QuanAddress::from(&a.from.id).is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serves_transfers_query() {
+        let router = mock_indexer_router(options());
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/graphql")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        json!({ "query": TransfersQuery::QUERY, "variables": { "first": 15, "after": "10" } })
+                            .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body_json: Value = serde_json::from_slice(&body_bytes).unwrap();
+        let connection = &body_json["data"]["transfersConnection"];
+        assert_eq!(connection["edges"].as_array().unwrap().len(), 10);
+        assert_eq!(connection["pageInfo"]["hasNextPage"], false);
+        assert_eq!(connection["pageInfo"]["endCursor"], "20");
+    }
+
+    #[tokio::test]
+    async fn test_sync_pages_and_resumes_from_checkpoint() {
+        let state = create_test_app_state().await;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let indexer_url = format!("http://{}/graphql", listener.local_addr().unwrap());
+        let router = mock_indexer_router(MockIndexerOptions {
+            accounts: 4,
+            transfers: 20,
+            seed: 3,
+        });
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let client = GraphqlClient::new((*state.db).clone(), indexer_url, 7, state.config.retry.graphql.clone());
+
+        let (transfer_count, address_count) = client.sync_transfers_and_addresses().await.unwrap();
+        assert_eq!((transfer_count, address_count), (20, 4));
+        assert_eq!(
+            state.db.sync_state.find_cursor("transfers").await.unwrap().as_deref(),
+            Some("20")
+        );
+
+        let (transfer_count, address_count) = client.sync_transfers_and_addresses().await.unwrap();
+        assert_eq!((transfer_count, address_count), (0, 0));
+        assert_eq!(
+            state.db.sync_state.find_cursor("transfers").await.unwrap().as_deref(),
+            Some("20")
+        );
+    }
+}
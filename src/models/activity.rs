@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::models::validation::{Validate, ValidationErrors};
+
+/// Largest page of the activity timeline.
+pub const ACTIVITY_MAX_PAGE_SIZE: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    TransferSent,
+    TransferReceived,
+    /// The address referred another one.
+    Referral,
+    /// The address was referred.
+    ReferredBy,
+    OptIn,
+    RaidSubmission,
+}
+
+impl ActivityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityKind::TransferSent => "transfer_sent",
+            ActivityKind::TransferReceived => "transfer_received",
+            ActivityKind::Referral => "referral",
+            ActivityKind::ReferredBy => "referred_by",
+            ActivityKind::OptIn => "opt_in",
+            ActivityKind::RaidSubmission => "raid_submission",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, sqlx::Error> {
+        match value {
+            "transfer_sent" => Ok(ActivityKind::TransferSent),
+            "transfer_received" => Ok(ActivityKind::TransferReceived),
+            "referral" => Ok(ActivityKind::Referral),
+            "referred_by" => Ok(ActivityKind::ReferredBy),
+            "opt_in" => Ok(ActivityKind::OptIn),
+            "raid_submission" => Ok(ActivityKind::RaidSubmission),
+            other => Err(sqlx::Error::Decode(format!("Unknown activity kind '{}'", other).into())),
+        }
+    }
+}
+
+/// One entry of an address's timeline. `reference` is the id of the underlying record (transfer, referral,
+/// opt-in number or submission), `details` depends on the kind.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityEvent {
+    pub kind: ActivityKind,
+    pub occurred_at: DateTime<Utc>,
+    pub reference: String,
+    pub details: serde_json::Value,
+}
+
+impl<'r> FromRow<'r, PgRow> for ActivityEvent {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let kind = ActivityKind::parse(row.try_get("kind")?)?;
+        let occurred_at = row.try_get("occurred_at")?;
+        let reference = row.try_get("reference")?;
+        let details = row.try_get("details")?;
+
+        Ok(ActivityEvent {
+            kind,
+            occurred_at,
+            reference,
+            details,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+fn default_page_size() -> u32 {
+    25
+}
+
+impl Validate for ActivityQueryParams {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.check(self.page >= 1, "page", "must not be less than 1");
+        errors.check(
+            (1..=ACTIVITY_MAX_PAGE_SIZE).contains(&self.page_size),
+            "page_size",
+            format!("must be between 1 and {}", ACTIVITY_MAX_PAGE_SIZE),
+        );
+        errors.into_result()
+    }
+}
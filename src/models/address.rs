@@ -48,6 +48,11 @@ impl Address {
         })
     }
 }
+#[derive(Debug, Deserialize, Clone)]
+pub struct VanityReferralCodeInput {
+    pub referral_code: String,
+}
+
 impl<'r> FromRow<'r, PgRow> for Address {
     fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
         let quan_address = row.try_get("quan_address")?;
@@ -102,6 +107,8 @@ pub struct AddressFilter {
     pub min_referrals: Option<i32>,
     pub has_eth_address: Option<bool>,
     pub has_x_account: Option<bool>,
+    /// Banned addresses are only listed when this is set, `true` lists only them.
+    pub is_banned: Option<bool>,
 }
 
 // An unvalidated version that we can deserialize directly from JSON
@@ -119,4 +126,68 @@ pub struct AddressWithOptInAndAssociations {
     pub opt_in_number: Option<i32>,
     pub eth_address: Option<String>,
     pub x_username: Option<String>,
+    /// When the address was banned.
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Body format of the bulk address import and export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressDataFormat {
+    /// One JSON object per line.
+    #[default]
+    Ndjson,
+    /// `quan_address,referral_code` lines, the header line is optional.
+    Csv,
+}
+
+impl AddressDataFormat {
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+
+        match mime.to_ascii_lowercase().as_str() {
+            "text/csv" => Some(Self::Csv),
+            "application/x-ndjson" | "application/jsonl" => Some(Self::Ndjson),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Ndjson => "application/x-ndjson",
+            Self::Csv => "text/csv",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddressExportQuery {
+    #[serde(default)]
+    pub format: AddressDataFormat,
+}
+
+/// A row of a bulk import, the referral code is generated when missing.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AddressImportRow {
+    pub quan_address: String,
+    #[serde(default)]
+    pub referral_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AddressImportRowError {
+    /// 1-based line of the request body.
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AddressImportSummary {
+    pub total_rows: usize,
+    pub imported: u64,
+    /// Valid rows for addresses that already existed.
+    pub skipped_existing: u64,
+    pub failed: usize,
+    /// The first failed rows, `failed` has the full count.
+    pub errors: Vec<AddressImportRowError>,
 }
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::models::{address::AddressWithOptInAndAssociations, ModelError, ModelResult};
+
+const NOTE_BODY_MAX_LEN: usize = 10_000;
+const TICKET_REF_MAX_LEN: usize = 255;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AddressNote {
+    pub id: i32,
+    pub quan_address: String,
+    pub body: String,
+    pub ticket_ref: Option<String>,
+    pub author: String,
+    pub updated_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for AddressNote {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let id = row.try_get("id")?;
+        let quan_address = row.try_get("quan_address")?;
+        let body = row.try_get("body")?;
+        let ticket_ref = row.try_get("ticket_ref")?;
+        let author = row.try_get("author")?;
+        let updated_by = row.try_get("updated_by")?;
+        let created_at = row.try_get("created_at")?;
+        let updated_at = row.try_get("updated_at")?;
+
+        Ok(AddressNote {
+            id,
+            quan_address,
+            body,
+            ticket_ref,
+            author,
+            updated_by,
+            created_at,
+            updated_at,
+        })
+    }
+}
+
+/// A previous version of a note, captured when the note was edited.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AddressNoteRevision {
+    pub id: i32,
+    pub note_id: i32,
+    pub body: String,
+    pub ticket_ref: Option<String>,
+    pub edited_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for AddressNoteRevision {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let id = row.try_get("id")?;
+        let note_id = row.try_get("note_id")?;
+        let body = row.try_get("body")?;
+        let ticket_ref = row.try_get("ticket_ref")?;
+        let edited_by = row.try_get("edited_by")?;
+        let created_at = row.try_get("created_at")?;
+
+        Ok(AddressNoteRevision {
+            id,
+            note_id,
+            body,
+            ticket_ref,
+            edited_by,
+            created_at,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AddressNoteInput {
+    pub body: String,
+    pub ticket_ref: Option<String>,
+}
+
+impl AddressNoteInput {
+    /// Trims the input, treating a blank ticket reference as none.
+    pub fn validated(self) -> ModelResult<Self> {
+        let body = self.body.trim().to_string();
+        let ticket_ref = self.ticket_ref.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+
+        if body.is_empty() || body.chars().count() > NOTE_BODY_MAX_LEN {
+            tracing::error!("Invalid address note body length: {}", body.chars().count());
+            return Err(ModelError::InvalidInput);
+        }
+
+        if ticket_ref.as_ref().is_some_and(|t| t.len() > TICKET_REF_MAX_LEN) {
+            tracing::error!("Address note ticket reference is too long");
+            return Err(ModelError::InvalidInput);
+        }
+
+        Ok(Self { body, ticket_ref })
+    }
+}
+
+/// Admin view of a single address with its notes.
+#[derive(Debug, Serialize)]
+pub struct AddressDetail {
+    #[serde(flatten)]
+    pub address: AddressWithOptInAndAssociations,
+    pub notes: Vec<AddressNote>,
+}
@@ -3,11 +3,49 @@ use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgRow, FromRow, Row};
 use uuid::Uuid;
 
+use crate::models::validation::{Validate, ValidationErrors};
+
+const USERNAME_MAX_LEN: usize = 50;
+const PASSWORD_MIN_LEN: usize = 12;
+
+/// What an admin may do, each role includes the permissions of the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminRole {
+    /// Read only access.
+    Viewer,
+    /// Day to day operations: raids, moderation, fraud rules, imports.
+    Operator,
+    /// Also manages admins and runtime settings.
+    Superadmin,
+}
+
+impl AdminRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminRole::Viewer => "viewer",
+            AdminRole::Operator => "operator",
+            AdminRole::Superadmin => "superadmin",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, sqlx::Error> {
+        match value {
+            "viewer" => Ok(AdminRole::Viewer),
+            "operator" => Ok(AdminRole::Operator),
+            "superadmin" => Ok(AdminRole::Superadmin),
+            other => Err(sqlx::Error::Decode(format!("Unknown admin role '{}'", other).into())),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Admin {
     pub id: Uuid,
     pub username: String,
     pub password: String,
+    pub role: AdminRole,
+    pub disabled_at: Option<DateTime<Utc>>,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
@@ -16,6 +54,8 @@ impl<'r> FromRow<'r, PgRow> for Admin {
         let id = row.try_get("id")?;
         let username = row.try_get("username")?;
         let password = row.try_get("password")?;
+        let role = AdminRole::parse(row.try_get("role")?)?;
+        let disabled_at = row.try_get("disabled_at")?;
         let updated_at = row.try_get("updated_at")?;
         let created_at = row.try_get("created_at")?;
 
@@ -23,12 +63,79 @@ impl<'r> FromRow<'r, PgRow> for Admin {
             id,
             username,
             password,
+            role,
+            disabled_at,
             updated_at,
             created_at,
         })
     }
 }
 
+impl Admin {
+    pub fn has_role(&self, role: AdminRole) -> bool {
+        self.role >= role
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+}
+
+/// Admin as returned by the admin management endpoints, without the password hash.
+#[derive(Debug, Serialize)]
+pub struct AdminView {
+    pub id: Uuid,
+    pub username: String,
+    pub role: AdminRole,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Admin> for AdminView {
+    fn from(admin: Admin) -> Self {
+        Self {
+            id: admin.id,
+            username: admin.username,
+            role: admin.role,
+            disabled_at: admin.disabled_at,
+            updated_at: admin.updated_at,
+            created_at: admin.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAdminInput {
+    pub username: String,
+    pub password: String,
+    pub role: AdminRole,
+}
+
+impl Validate for CreateAdminInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let username = self.username.trim();
+        errors.check(
+            !username.is_empty() && username.len() <= USERNAME_MAX_LEN,
+            "username",
+            format!("must be 1-{} characters", USERNAME_MAX_LEN),
+        );
+        errors.check(
+            self.password.len() >= PASSWORD_MIN_LEN,
+            "password",
+            format!("must be at least {} characters", PASSWORD_MIN_LEN),
+        );
+
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAdminRoleInput {
+    pub role: AdminRole,
+}
+
 #[derive(Deserialize)]
 pub struct AdminLoginPayload {
     pub username: String,
@@ -44,6 +151,7 @@ pub struct AdminLoginResponse {
 pub struct AdminAuthCheckResponse {
     pub id: Uuid,
     pub username: String,
+    pub role: AdminRole,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -1,10 +1,15 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::services::signature_service::SignatureScheme;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenClaims {
     pub sub: String,
     pub iat: usize,
     pub exp: usize,
+    /// Session the token was issued for. Tokens from before sessions existed have none and can't be revoked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,9 +27,26 @@ pub struct VerifyLoginBody {
     pub address: String,
     pub public_key: String,
     pub signature: String,
+    /// Scheme of `public_key` and `signature`, Dilithium when omitted.
+    #[serde(default)]
+    pub scheme: SignatureScheme,
 }
 
 #[derive(Debug, Serialize)]
 pub struct VerifyLoginResponse {
     pub access_token: String,
+    /// Single use, `POST /auth/refresh` returns a new one with the next access token.
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshSessionBody {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LogoutQuery {
+    /// Ends every session of the address instead of only the current one.
+    #[serde(default)]
+    pub all: bool,
 }
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::models::{ModelError, ModelResult};
+
+const FLAG_KEY_MAX_LEN: usize = 64;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FeatureFlag {
+    pub key: String,
+    pub description: String,
+    pub enabled: bool,
+    pub rollout_percentage: i16,
+    pub allowlist: Vec<String>,
+    pub updated_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for FeatureFlag {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let key = row.try_get("key")?;
+        let description = row.try_get("description")?;
+        let enabled = row.try_get("enabled")?;
+        let rollout_percentage = row.try_get("rollout_percentage")?;
+        let allowlist = row.try_get("allowlist")?;
+        let updated_by = row.try_get("updated_by")?;
+        let created_at = row.try_get("created_at")?;
+        let updated_at = row.try_get("updated_at")?;
+
+        Ok(FeatureFlag {
+            key,
+            description,
+            enabled,
+            rollout_percentage,
+            allowlist,
+            updated_by,
+            created_at,
+            updated_at,
+        })
+    }
+}
+
+impl FeatureFlag {
+    /// Whether the flag is on for an address. Addresses are bucketed by a hash of the flag key and address,
+    /// so a given address keeps its decision as the rollout percentage grows, independently per flag.
+    pub fn is_enabled_for(&self, quan_address: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        if self.allowlist.iter().any(|a| a == quan_address) {
+            return true;
+        }
+
+        (rollout_bucket(&self.key, quan_address) as i16) < self.rollout_percentage
+    }
+}
+
+/// Stable bucket in `0..100`.
+pub fn rollout_bucket(key: &str, quan_address: &str) -> u8 {
+    let digest = Sha256::digest(format!("{}:{}", key, quan_address).as_bytes());
+    let value = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+
+    (value % 100) as u8
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FeatureFlagInput {
+    #[serde(default)]
+    pub description: String,
+    pub enabled: bool,
+    pub rollout_percentage: i16,
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+}
+
+impl FeatureFlagInput {
+    pub fn validate(&self, key: &str) -> ModelResult<()> {
+        let valid_key = !key.is_empty()
+            && key.len() <= FLAG_KEY_MAX_LEN
+            && key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.'));
+
+        if !valid_key {
+            tracing::error!("Invalid feature flag key: {}", key);
+            return Err(ModelError::InvalidInput);
+        }
+
+        if !(0..=100).contains(&self.rollout_percentage) {
+            tracing::error!("Invalid rollout percentage: {}", self.rollout_percentage);
+            return Err(ModelError::InvalidInput);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(enabled: bool, rollout_percentage: i16, allowlist: Vec<String>) -> FeatureFlag {
+        FeatureFlag {
+            key: "claims".to_string(),
+            description: String::new(),
+            enabled,
+            rollout_percentage,
+            allowlist,
+            updated_by: "admin".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_rollout_percentage_and_allowlist() {
+        let addresses: Vec<String> = (0..1000).map(|i| format!("qz_test_address_{}", i)).collect();
+
+        let none = flag(true, 0, vec![]);
+        assert!(addresses.iter().all(|a| !none.is_enabled_for(a)));
+
+        let all = flag(true, 100, vec![]);
+        assert!(addresses.iter().all(|a| all.is_enabled_for(a)));
+
+        let half = flag(true, 50, vec![]);
+        let enabled = addresses.iter().filter(|a| half.is_enabled_for(a)).count();
+        assert!((400..600).contains(&enabled), "got {}", enabled);
+
+        // Growing the rollout never turns the feature off for an address.
+        let more = flag(true, 60, vec![]);
+        assert!(addresses
+            .iter()
+            .filter(|a| half.is_enabled_for(a))
+            .all(|a| more.is_enabled_for(a)));
+
+        let allowlisted = flag(true, 0, vec![addresses[0].clone()]);
+        assert!(allowlisted.is_enabled_for(&addresses[0]));
+
+        let disabled = flag(false, 100, vec![addresses[0].clone()]);
+        assert!(!disabled.is_enabled_for(&addresses[0]));
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, types::Json, FromRow, Row};
+
+use crate::models::validation::{Validate, ValidationErrors};
+
+const RULE_NAME_MAX_LEN: usize = 64;
+
+/// What a rule matches. New kinds only need a variant here and a query in the repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FraudRuleDefinition {
+    /// Raiders with more than `max_submissions` valid submissions in the last `window_minutes`.
+    SubmissionVelocity { max_submissions: u32, window_minutes: u32 },
+    /// Submissions made less than `min_age_hours` after the raider's address was created.
+    NewAddressSubmissions { min_age_hours: u32 },
+    /// Submissions made less than `min_age_hours` after the raider linked (or re-linked) their X account.
+    RecentXAssociation { min_age_hours: u32 },
+    /// Addresses whose ETH payout address is used by more than `max_addresses` addresses. Addresses are compared
+    /// case-insensitively, so checksum variants of the same address are counted together.
+    SharedEthAddress { max_addresses: u32 },
+}
+
+impl Validate for FraudRuleDefinition {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        match self {
+            FraudRuleDefinition::SubmissionVelocity {
+                max_submissions,
+                window_minutes,
+            } => {
+                errors.check(*max_submissions > 0, "max_submissions", "must be positive");
+                errors.check(*window_minutes > 0, "window_minutes", "must be positive");
+            }
+            FraudRuleDefinition::NewAddressSubmissions { min_age_hours }
+            | FraudRuleDefinition::RecentXAssociation { min_age_hours } => {
+                errors.check(*min_age_hours > 0, "min_age_hours", "must be positive")
+            }
+            FraudRuleDefinition::SharedEthAddress { max_addresses } => {
+                errors.check(*max_addresses > 0, "max_addresses", "must be positive")
+            }
+        }
+
+        errors.into_result()
+    }
+}
+
+impl FraudRuleDefinition {
+    /// Whether the rule flags whole addresses instead of individual submissions.
+    pub fn is_address_level(&self) -> bool {
+        matches!(self, FraudRuleDefinition::SharedEthAddress { .. })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FraudRuleAction {
+    /// Only record a flag for review.
+    Flag,
+    /// Record a flag and mark the matched submissions invalid. For address level rules all submissions of the
+    /// address are invalidated.
+    Quarantine,
+}
+
+impl FraudRuleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FraudRuleAction::Flag => "flag",
+            FraudRuleAction::Quarantine => "quarantine",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, sqlx::Error> {
+        match value {
+            "flag" => Ok(FraudRuleAction::Flag),
+            "quarantine" => Ok(FraudRuleAction::Quarantine),
+            other => Err(sqlx::Error::Decode(
+                format!("Unknown fraud rule action '{}'", other).into(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FraudRule {
+    pub id: i32,
+    pub name: String,
+    pub definition: FraudRuleDefinition,
+    pub action: FraudRuleAction,
+    pub enabled: bool,
+    pub version: i32,
+    pub hit_count: i64,
+    pub last_hit_at: Option<DateTime<Utc>>,
+    pub updated_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for FraudRule {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let Json(definition) = row.try_get::<Json<FraudRuleDefinition>, _>("definition")?;
+        let action = FraudRuleAction::parse(row.try_get("action")?)?;
+
+        Ok(FraudRule {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            definition,
+            action,
+            enabled: row.try_get("enabled")?,
+            version: row.try_get("version")?,
+            hit_count: row.try_get("hit_count")?,
+            last_hit_at: row.try_get("last_hit_at")?,
+            updated_by: row.try_get("updated_by")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FraudRuleVersion {
+    pub rule_id: i32,
+    pub version: i32,
+    pub definition: FraudRuleDefinition,
+    pub action: FraudRuleAction,
+    pub enabled: bool,
+    pub changed_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for FraudRuleVersion {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let Json(definition) = row.try_get::<Json<FraudRuleDefinition>, _>("definition")?;
+        let action = FraudRuleAction::parse(row.try_get("action")?)?;
+
+        Ok(FraudRuleVersion {
+            rule_id: row.try_get("rule_id")?,
+            version: row.try_get("version")?,
+            definition,
+            action,
+            enabled: row.try_get("enabled")?,
+            changed_by: row.try_get("changed_by")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FraudFlag {
+    pub id: i64,
+    pub rule_id: i32,
+    pub rule_version: i32,
+    pub quan_address: String,
+    pub submission_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FraudRuleInput {
+    pub name: String,
+    pub definition: FraudRuleDefinition,
+    pub action: FraudRuleAction,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Validate for FraudRuleInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let name = self.name.trim();
+        errors.check(
+            !name.is_empty() && name.len() <= RULE_NAME_MAX_LEN,
+            "name",
+            format!("must be 1-{} characters", RULE_NAME_MAX_LEN),
+        );
+        errors.nest("definition", self.definition.validate());
+
+        errors.into_result()
+    }
+}
+
+/// Outcome of evaluating one rule.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FraudRuleEvaluation {
+    pub rule_id: i32,
+    pub name: String,
+    pub version: i32,
+    pub new_flags: u64,
+    pub quarantined_submissions: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FraudFlagQuery {
+    pub rule_id: Option<i32>,
+    pub quan_address: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_definition_parsing_and_validation() {
+        let definition: FraudRuleDefinition =
+            serde_json::from_value(json!({"type": "submission_velocity", "max_submissions": 5, "window_minutes": 10}))
+                .unwrap();
+        assert!(definition.validate().is_ok());
+
+        let definition: FraudRuleDefinition =
+            serde_json::from_value(json!({"type": "new_address_submissions", "min_age_hours": 0})).unwrap();
+        assert!(definition.validate().is_err());
+
+        let definition: FraudRuleDefinition =
+            serde_json::from_value(json!({"type": "shared_eth_address", "max_addresses": 1})).unwrap();
+        assert!(definition.validate().is_ok());
+        assert!(definition.is_address_level());
+
+        assert!(serde_json::from_value::<FraudRuleDefinition>(json!({"type": "unknown"})).is_err());
+    }
+}
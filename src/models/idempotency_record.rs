@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// A request made with an `Idempotency-Key`, and its response once handled.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct IdempotencyRecord {
+    pub owner: String,
+    pub idempotency_key: String,
+    /// SHA-256 of the method, path and body of the first request.
+    pub request_hash: String,
+    pub status_code: Option<i16>,
+    pub response_body: Option<Vec<u8>>,
+    pub content_type: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Whether background jobs are paused. The HTTP API keeps serving while paused.
+#[derive(Debug, Clone, Default, Serialize, FromRow)]
+pub struct PauseState {
+    pub paused: bool,
+    pub reason: Option<String>,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PauseInput {
+    /// Shown to other admins, e.g. the chain upgrade being waited for.
+    pub reason: Option<String>,
+}
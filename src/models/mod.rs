@@ -8,10 +8,26 @@ pub enum ModelError {
 
 pub type ModelResult<T> = Result<T, ModelError>;
 
+pub mod activity;
 pub mod address;
+pub mod address_note;
 pub mod admin;
 pub mod auth;
+pub mod feature_flag;
+pub mod fraud_rule;
+pub mod idempotency_record;
+pub mod maintenance;
+pub mod opt_in_stat;
+pub mod processed_transfer;
+pub mod program;
+pub mod raid_payout;
 pub mod raid_quest;
+pub mod raid_team;
+pub mod referral_reward;
 pub mod referrals;
 pub mod relevant_tweet;
+pub mod session;
+pub mod setting;
+pub mod sybil_score;
 pub mod tweet_author;
+pub mod validation;
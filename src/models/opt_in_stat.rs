@@ -0,0 +1,32 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DailyOptIns {
+    pub day: NaiveDate,
+    pub opt_ins: i64,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReferralSourceOptIns {
+    /// Referrer address, or `direct`.
+    pub referral_source: String,
+    pub opt_ins: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OptInStats {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub total: i64,
+    pub by_day: Vec<DailyOptIns>,
+    pub by_referral_source: Vec<ReferralSourceOptIns>,
+}
+
+/// Inclusive UTC day range, open ended when a bound is missing.
+#[derive(Debug, Default, Deserialize)]
+pub struct OptInStatsQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessedTransfer {
+    pub transfer_id: String,
+    pub from_address: String,
+    pub to_address: String,
+    pub amount: String,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+impl<'r> FromRow<'r, PgRow> for ProcessedTransfer {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let transfer_id = row.try_get("transfer_id")?;
+        let from_address = row.try_get("from_address")?;
+        let to_address = row.try_get("to_address")?;
+        let amount = row.try_get("amount")?;
+        let processed_at = row.try_get("processed_at")?;
+
+        Ok(ProcessedTransfer {
+            transfer_id,
+            from_address,
+            to_address,
+            amount,
+            processed_at,
+        })
+    }
+}
+
+/// An indexer page of transfers stored by the sync, whether or not its transfers were new.
+#[derive(Debug, Clone)]
+pub struct ProcessedTransferPage {
+    pub after_cursor: Option<String>,
+    pub end_cursor: String,
+    pub first_transfer_id: String,
+    pub last_transfer_id: String,
+}
+
+/// Transfers the sync skipped: it resumed after `resumed_after_cursor`, which no stored page ended at. The
+/// skipped transfers come after `after_transfer_id` (from the start if `None`) and before `before_transfer_id`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TransferGap {
+    pub after_transfer_id: Option<String>,
+    pub before_transfer_id: String,
+    pub resumed_after_cursor: String,
+}
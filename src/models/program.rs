@@ -0,0 +1,44 @@
+use serde::Serialize;
+
+use crate::models::{raid_quest::RaidScoringRules, raid_team::TeamScoreNormalization};
+
+/// User facing program parameters, derived from the effective configuration.
+#[derive(Debug, Serialize)]
+pub struct ProgramRules {
+    pub referral_codes: ReferralCodeRules,
+    pub raids: RaidRules,
+    pub session: SessionRules,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReferralCodeRules {
+    pub vanity_min_length: usize,
+    pub vanity_max_length: usize,
+    /// Vanity codes with these prefixes can only be granted by an admin.
+    pub reserved_prefixes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RaidRules {
+    /// How submissions of each active raid are scored, for raider and team leaderboards alike.
+    pub active_raids: Vec<ActiveRaidScoring>,
+    /// Scoring of raids created without their own rules.
+    pub default_scoring: RaidScoringRules,
+    pub team_score_normalizations: Vec<TeamScoreNormalization>,
+    pub default_team_score_normalization: TeamScoreNormalization,
+    pub team_name_max_length: usize,
+    pub team_leaderboard_default_limit: u32,
+    pub team_leaderboard_max_limit: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActiveRaidScoring {
+    pub raid_id: i32,
+    pub name: String,
+    pub scoring: RaidScoringRules,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionRules {
+    pub token_lifetime_hours: i64,
+}
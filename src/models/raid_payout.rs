@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Total weighted score of a raider in a raid.
+#[derive(Debug, Clone, FromRow)]
+pub struct RaiderScore {
+    pub raider_id: String,
+    pub score: f64,
+}
+
+/// Prize a raider gets from a raid's prize pool, before it is recorded.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RaidPayoutShare {
+    pub rank: i32,
+    pub raider_id: String,
+    pub score: f64,
+    /// Base units.
+    pub amount: String,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RaidPayout {
+    pub id: i64,
+    pub raid_id: i32,
+    pub raider_id: String,
+    pub rank: i32,
+    pub score: f64,
+    /// Base units.
+    pub amount: String,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub paid_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarkRaidPayoutsPaidInput {
+    pub payout_ids: Vec<i64>,
+}
@@ -2,6 +2,93 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgRow, FromRow, Row};
 
+use crate::models::validation::{Validate, ValidationErrors};
+
+const RAID_NAME_MAX_LEN: usize = 255;
+
+/// Score of a single valid submission `s` under the rules of its raid `rq`, capped at the raid's
+/// `max_submission_score` (`LEAST` ignores a NULL cap).
+pub const SUBMISSION_SCORE_SQL: &str = "LEAST(
+    rq.impression_weight * COALESCE(s.impression_count, 0)
+        + rq.like_weight * COALESCE(s.like_count, 0)
+        + rq.reply_weight * COALESCE(s.reply_count, 0)
+        + rq.retweet_weight * COALESCE(s.retweet_count, 0),
+    rq.max_submission_score
+)";
+
+/// Whether the raider of submission `s` has enough followers to count in raid `rq`. Follower counts come from
+/// the tweet author record of the raider's linked X account, raiders without one only count when the raid has
+/// no threshold.
+pub const SUBMISSION_ELIGIBLE_SQL: &str = "(
+    rq.min_follower_count = 0
+    OR EXISTS (
+        SELECT 1
+        FROM x_associations x
+        JOIN tweet_authors ta ON LOWER(ta.username) = LOWER(x.username)
+        WHERE x.quan_address = s.raider_id AND ta.followers_count >= rq.min_follower_count
+    )
+)";
+
+/// How submissions of a raid are scored on its leaderboards.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct RaidScoringRules {
+    pub impression_weight: f64,
+    pub like_weight: f64,
+    pub reply_weight: f64,
+    pub retweet_weight: f64,
+    /// Upper bound of a single submission's score, so one viral reply can't decide the raid.
+    pub max_submission_score: Option<f64>,
+    /// Submissions of raiders with fewer followers are ignored.
+    pub min_follower_count: i32,
+}
+
+impl Default for RaidScoringRules {
+    /// Scores submissions by their impressions only.
+    fn default() -> Self {
+        Self {
+            impression_weight: 1.0,
+            like_weight: 0.0,
+            reply_weight: 0.0,
+            retweet_weight: 0.0,
+            max_submission_score: None,
+            min_follower_count: 0,
+        }
+    }
+}
+
+/// Rejects negative or non-finite weights, a non-positive cap and a negative follower threshold.
+impl Validate for RaidScoringRules {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let weights = [
+            ("impression_weight", self.impression_weight),
+            ("like_weight", self.like_weight),
+            ("reply_weight", self.reply_weight),
+            ("retweet_weight", self.retweet_weight),
+        ];
+        for (field, weight) in weights {
+            errors.check(
+                weight.is_finite() && weight >= 0.0,
+                field,
+                "must be a non-negative number",
+            );
+        }
+        errors.check(
+            self.max_submission_score
+                .map_or(true, |cap| cap.is_finite() && cap > 0.0),
+            "max_submission_score",
+            "must be positive when set",
+        );
+        errors.check(
+            self.min_follower_count >= 0,
+            "min_follower_count",
+            "must not be negative",
+        );
+
+        errors.into_result()
+    }
+}
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RaidQuest {
     pub id: i32,
@@ -10,6 +97,13 @@ pub struct RaidQuest {
     pub end_date: Option<DateTime<Utc>>,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    pub scoring: RaidScoringRules,
+}
+
+impl RaidQuest {
+    pub fn is_active(&self) -> bool {
+        self.end_date.is_none() && self.start_date <= Utc::now()
+    }
 }
 
 impl<'r> FromRow<'r, PgRow> for RaidQuest {
@@ -20,6 +114,14 @@ impl<'r> FromRow<'r, PgRow> for RaidQuest {
         let end_date = row.try_get("end_date")?;
         let updated_at = row.try_get("updated_at")?;
         let created_at = row.try_get("created_at")?;
+        let scoring = RaidScoringRules {
+            impression_weight: row.try_get("impression_weight")?,
+            like_weight: row.try_get("like_weight")?,
+            reply_weight: row.try_get("reply_weight")?,
+            retweet_weight: row.try_get("retweet_weight")?,
+            max_submission_score: row.try_get("max_submission_score")?,
+            min_follower_count: row.try_get("min_follower_count")?,
+        };
 
         Ok(RaidQuest {
             id,
@@ -28,6 +130,7 @@ impl<'r> FromRow<'r, PgRow> for RaidQuest {
             end_date,
             updated_at,
             created_at,
+            scoring,
         })
     }
 }
@@ -60,4 +163,47 @@ pub struct RaidQuestFilter {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreateRaidQuest {
     pub name: String,
+    #[serde(default)]
+    pub scoring: RaidScoringRules,
+}
+
+impl Validate for CreateRaidQuest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let name = self.name.trim();
+        errors.check(
+            !name.is_empty() && name.len() <= RAID_NAME_MAX_LEN,
+            "name",
+            format!("must be 1-{} characters", RAID_NAME_MAX_LEN),
+        );
+        errors.nest("scoring", self.scoring.validate());
+
+        errors.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoring_rules_validation() {
+        assert!(RaidScoringRules::default().validate().is_ok());
+
+        let negative_weight = RaidScoringRules {
+            like_weight: -1.0,
+            ..Default::default()
+        };
+        assert!(negative_weight.validate().is_err());
+
+        let zero_cap = RaidScoringRules {
+            max_submission_score: Some(0.0),
+            ..Default::default()
+        };
+        assert!(zero_cap.validate().is_err());
+
+        let partial: RaidScoringRules = serde_json::from_str(r#"{"like_weight": 5}"#).unwrap();
+        assert_eq!(partial.impression_weight, 1.0);
+        assert_eq!(partial.like_weight, 5.0);
+    }
 }
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::models::{raid_quest::SUBMISSION_SCORE_SQL, ModelError, ModelResult};
+
+pub const TEAM_NAME_MAX_LEN: usize = 64;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RaidTeam {
+    pub id: i32,
+    pub raid_id: i32,
+    pub name: String,
+    pub created_by: String,
+    pub member_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for RaidTeam {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let id = row.try_get("id")?;
+        let raid_id = row.try_get("raid_id")?;
+        let name = row.try_get("name")?;
+        let created_by = row.try_get("created_by")?;
+        let member_count = row.try_get("member_count")?;
+        let created_at = row.try_get("created_at")?;
+        let updated_at = row.try_get("updated_at")?;
+
+        Ok(RaidTeam {
+            id,
+            raid_id,
+            name,
+            created_by,
+            member_count,
+            created_at,
+            updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateRaidTeam {
+    pub name: String,
+}
+
+impl CreateRaidTeam {
+    /// Returns the trimmed team name, rejecting empty or overly long names.
+    pub fn validated_name(&self) -> ModelResult<String> {
+        let name = self.name.trim();
+
+        if name.is_empty() || name.chars().count() > TEAM_NAME_MAX_LEN {
+            tracing::error!("Invalid raid team name length: {}", name.chars().count());
+            return Err(ModelError::InvalidInput);
+        }
+
+        Ok(name.to_string())
+    }
+}
+
+/// How team totals are adjusted for team size when ranking.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamScoreNormalization {
+    /// Raw sum of member submission scores. Favors large teams.
+    #[default]
+    None,
+    /// Average score per member.
+    PerMember,
+    /// Sum divided by the square root of the member count, a middle ground between the two.
+    SqrtMembers,
+}
+
+impl TeamScoreNormalization {
+    pub const ALL: [TeamScoreNormalization; 3] = [Self::None, Self::PerMember, Self::SqrtMembers];
+
+    /// Team score over the grouped submissions `s` of raid `rq`, see [`SUBMISSION_SCORE_SQL`].
+    pub fn to_sql_expr(self) -> String {
+        let total = format!("COALESCE(SUM({}), 0)::DOUBLE PRECISION", SUBMISSION_SCORE_SQL);
+
+        match self {
+            TeamScoreNormalization::None => total,
+            TeamScoreNormalization::PerMember => format!("{} / COUNT(DISTINCT m.quan_address)", total),
+            TeamScoreNormalization::SqrtMembers => format!("{} / SQRT(COUNT(DISTINCT m.quan_address))", total),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TeamLeaderboardQuery {
+    #[serde(default)]
+    pub normalization: TeamScoreNormalization,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TeamLeaderboardEntry {
+    pub rank: i64,
+    pub team_id: i32,
+    pub name: String,
+    pub member_count: i64,
+    pub total_submissions: i64,
+    pub total_impressions: i64,
+    pub total_replies: i64,
+    pub total_retweets: i64,
+    pub total_likes: i64,
+    pub score: f64,
+}
+
+impl<'r> FromRow<'r, PgRow> for TeamLeaderboardEntry {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(TeamLeaderboardEntry {
+            rank: row.try_get("rank")?,
+            team_id: row.try_get("team_id")?,
+            name: row.try_get("name")?,
+            member_count: row.try_get("member_count")?,
+            total_submissions: row.try_get("total_submissions")?,
+            total_impressions: row.try_get("total_impressions")?,
+            total_replies: row.try_get("total_replies")?,
+            total_retweets: row.try_get("total_retweets")?,
+            total_likes: row.try_get("total_likes")?,
+            score: row.try_get("score")?,
+        })
+    }
+}
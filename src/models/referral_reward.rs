@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReferralReward {
+    pub id: i64,
+    pub referrer_address: String,
+    pub referee_address: String,
+    /// Base units.
+    pub amount: String,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub paid_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Unpaid rewards of one referrer, as exported for payout.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReferralPayout {
+    pub referrer_address: String,
+    pub reward_ids: Vec<i64>,
+    /// Sum of the rewards in base units.
+    pub total_amount: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarkRewardsPaidInput {
+    pub reward_ids: Vec<i64>,
+}
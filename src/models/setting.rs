@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{postgres::PgRow, types::Json, FromRow, Row};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Setting {
+    pub key: String,
+    pub value: Value,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for Setting {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let key = row.try_get("key")?;
+        let Json(value) = row.try_get::<Json<Value>, _>("value")?;
+        let updated_by = row.try_get("updated_by")?;
+        let updated_at = row.try_get("updated_at")?;
+        let created_at = row.try_get("created_at")?;
+
+        Ok(Setting {
+            key,
+            value,
+            updated_by,
+            updated_at,
+            created_at,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SettingAudit {
+    pub id: i64,
+    pub key: String,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    pub changed_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, PgRow> for SettingAudit {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let id = row.try_get("id")?;
+        let key = row.try_get("key")?;
+        let old_value = row.try_get::<Option<Json<Value>>, _>("old_value")?.map(|v| v.0);
+        let new_value = row.try_get::<Option<Json<Value>>, _>("new_value")?.map(|v| v.0);
+        let changed_by = row.try_get("changed_by")?;
+        let created_at = row.try_get("created_at")?;
+
+        Ok(SettingAudit {
+            id,
+            key,
+            old_value,
+            new_value,
+            changed_by,
+            created_at,
+        })
+    }
+}
+
+/// Admin facing view of an overridable setting.
+#[derive(Debug, Serialize, Clone)]
+pub struct SettingView {
+    pub key: String,
+    pub file_value: Value,
+    pub override_value: Option<Value>,
+    pub effective_value: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSettingInput {
+    pub value: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingAuditSortColumn {
+    CreatedAt,
+    Key,
+}
+
+impl SettingAuditSortColumn {
+    pub fn to_sql_column(&self) -> &'static str {
+        match self {
+            SettingAuditSortColumn::CreatedAt => "sa.created_at",
+            SettingAuditSortColumn::Key => "sa.key",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SettingAuditFilter {
+    pub key: Option<String>,
+}
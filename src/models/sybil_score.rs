@@ -0,0 +1,60 @@
+use sqlx::FromRow;
+
+/// Funding clusters of this size or bigger get the full funding weight.
+const SATURATED_CLUSTER_SIZE: f64 = 20.0;
+/// Registrations within the burst window at which the burst signal saturates.
+const SATURATED_BURST_SIZE: f64 = 10.0;
+const SATURATED_CIRCULAR_COUNTERPARTIES: f64 = 3.0;
+
+const FUNDING_WEIGHT: f64 = 40.0;
+const BURST_WEIGHT: f64 = 30.0;
+const CIRCULAR_WEIGHT: f64 = 30.0;
+
+/// Transfer graph signals of one address, see the `address_sybil_scores` migration for their meaning.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct SybilSignals {
+    pub quan_address: String,
+    pub funding_cluster_size: i64,
+    pub burst_size: i64,
+    pub circular_counterparties: i64,
+}
+
+impl SybilSignals {
+    /// Weighted risk from 0 to 100. Each signal grows linearly until it saturates.
+    pub fn score(&self) -> i16 {
+        let ratio = |value: i64, saturated: f64| (value.max(0) as f64 / saturated).min(1.0);
+
+        let funding = ratio(self.funding_cluster_size - 1, SATURATED_CLUSTER_SIZE - 1.0);
+        let burst = ratio(self.burst_size, SATURATED_BURST_SIZE);
+        let circular = ratio(self.circular_counterparties, SATURATED_CIRCULAR_COUNTERPARTIES);
+
+        (funding * FUNDING_WEIGHT + burst * BURST_WEIGHT + circular * CIRCULAR_WEIGHT).round() as i16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals(funding_cluster_size: i64, burst_size: i64, circular_counterparties: i64) -> SybilSignals {
+        SybilSignals {
+            quan_address: "qz".to_string(),
+            funding_cluster_size,
+            burst_size,
+            circular_counterparties,
+        }
+    }
+
+    #[test]
+    fn test_score_weights_and_saturation() {
+        // Unfunded or solely funded addresses carry no risk
+        assert_eq!(signals(0, 0, 0).score(), 0);
+        assert_eq!(signals(1, 0, 0).score(), 0);
+
+        assert_eq!(signals(20, 0, 0).score(), 40);
+        assert_eq!(signals(500, 0, 0).score(), 40);
+        assert_eq!(signals(1, 5, 0).score(), 15);
+        assert_eq!(signals(1, 0, 3).score(), 30);
+        assert_eq!(signals(20, 10, 3).score(), 100);
+    }
+}
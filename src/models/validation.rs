@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+/// Problem with one field of a request. Nested fields are named by their path, e.g. `scoring.like_weight`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Every problem found in a request, answered with 422 and the list of fields.
+#[derive(Debug, Default, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Validation failed on {}", self.fields().join(", "))]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn single(field: &str, message: impl Into<String>) -> Self {
+        let mut errors = Self::new();
+        errors.add(field, message);
+        errors
+    }
+
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Records `message` for `field` unless `valid` holds.
+    pub fn check(&mut self, valid: bool, field: &str, message: impl Into<String>) {
+        if !valid {
+            self.add(field, message);
+        }
+    }
+
+    /// Adds the problems of a nested value under `prefix`.
+    pub fn nest(&mut self, prefix: &str, result: Result<(), ValidationErrors>) {
+        if let Err(nested) = result {
+            self.errors.extend(nested.errors.into_iter().map(|e| FieldError {
+                field: format!("{}.{}", prefix, e.field),
+                message: e.message,
+            }));
+        }
+    }
+
+    pub fn fields(&self) -> Vec<&str> {
+        self.errors.iter().map(|e| e.field.as_str()).collect()
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// Request DTOs that can check themselves, see `handlers::validation` for the extractors running it.
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collects_and_nests_errors() {
+        let mut nested = ValidationErrors::new();
+        nested.check(false, "like_weight", "must not be negative");
+        nested.check(true, "reply_weight", "must not be negative");
+
+        let mut errors = ValidationErrors::new();
+        errors.check(false, "name", "must not be empty");
+        errors.nest("scoring", nested.into_result());
+        errors.nest("other", Ok(()));
+
+        let err = errors.into_result().unwrap_err();
+        assert_eq!(err.fields(), vec!["name", "scoring.like_weight"]);
+        assert_eq!(err.to_string(), "Validation failed on name, scoring.like_weight");
+        assert!(ValidationErrors::new().into_result().is_ok());
+    }
+}
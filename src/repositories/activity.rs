@@ -0,0 +1,148 @@
+use sqlx::PgPool;
+
+use crate::{models::activity::ActivityEvent, repositories::DbResult};
+
+/// Every source of the timeline, one row per event. Transfers carry the time they were synced, the indexer's
+/// block time isn't stored.
+const ACTIVITY_SOURCES: &str = r#"
+    SELECT 'transfer_sent' AS kind, processed_at AS occurred_at, transfer_id AS reference,
+        jsonb_build_object('counterparty', to_address, 'amount', amount) AS details
+    FROM processed_transfers WHERE from_address = $1
+    UNION ALL
+    SELECT 'transfer_received', processed_at, transfer_id,
+        jsonb_build_object('counterparty', from_address, 'amount', amount)
+    FROM processed_transfers WHERE to_address = $1
+    UNION ALL
+    SELECT 'referral', created_at, id::TEXT, jsonb_build_object('referee', referee_address)
+    FROM referrals WHERE referrer_address = $1
+    UNION ALL
+    SELECT 'referred_by', created_at, id::TEXT, jsonb_build_object('referrer', referrer_address)
+    FROM referrals WHERE referee_address = $1
+    UNION ALL
+    SELECT 'opt_in', created_at, opt_in_number::TEXT, jsonb_build_object('opt_in_number', opt_in_number)
+    FROM opt_ins WHERE quan_address = $1
+    UNION ALL
+    SELECT 'raid_submission', created_at, id,
+        jsonb_build_object(
+            'raid_id', raid_id,
+            'impression_count', impression_count,
+            'like_count', like_count,
+            'reply_count', reply_count,
+            'retweet_count', retweet_count,
+            'is_invalid', is_invalid
+        )
+    FROM raid_submissions WHERE raider_id = $1
+"#;
+
+#[derive(Clone, Debug)]
+pub struct ActivityRepository {
+    read_pool: PgPool,
+}
+
+impl ActivityRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            read_pool: pool.clone(),
+        }
+    }
+
+    /// Serves the timeline from `read_pool`, e.g. a read replica.
+    pub fn with_read_pool(mut self, read_pool: &PgPool) -> Self {
+        self.read_pool = read_pool.clone();
+        self
+    }
+
+    /// Events of `quan_address`, newest first.
+    pub async fn find_for_address(&self, quan_address: &str, limit: u32, offset: u32) -> DbResult<Vec<ActivityEvent>> {
+        let events = sqlx::query_as::<_, ActivityEvent>(&format!(
+            "SELECT * FROM ({}) activity ORDER BY occurred_at DESC, kind, reference LIMIT $2 OFFSET $3",
+            ACTIVITY_SOURCES
+        ))
+        .bind(quan_address)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    pub async fn count_for_address(&self, quan_address: &str) -> DbResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM ({}) activity", ACTIVITY_SOURCES))
+            .bind(quan_address)
+            .fetch_one(&self.read_pool)
+            .await?;
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::activity::ActivityKind,
+        utils::{
+            test_app_state::create_test_app_state,
+            test_db::{create_persisted_address, create_persisted_opt_in, RaidBuilder, SubmissionBuilder},
+        },
+    };
+
+    #[tokio::test]
+    async fn test_timeline_merges_sources_newest_first() {
+        let state = create_test_app_state().await;
+        let db = &state.db;
+        let raider = create_persisted_address(&db.addresses, "activity_raider").await;
+        let referee = create_persisted_address(&db.addresses, "activity_referee").await;
+        let raider_id = &raider.quan_address.0;
+        let referee_id = &referee.quan_address.0;
+
+        create_persisted_opt_in(&db.pool, raider_id).await;
+        sqlx::query("INSERT INTO referrals (referrer_address, referee_address) VALUES ($1, $2)")
+            .bind(raider_id)
+            .bind(referee_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO processed_transfers (transfer_id, from_address, to_address, amount) VALUES ($1, $2, $3, $4)",
+        )
+        .bind("activity-transfer-1")
+        .bind(referee_id)
+        .bind(raider_id)
+        .bind("1000")
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        let raid_id = RaidBuilder::new("Activity raid").create(db).await;
+        SubmissionBuilder::new("activity-submission-1", raid_id, raider_id)
+            .impressions(7)
+            .create(&db.pool)
+            .await;
+
+        let activity = ActivityRepository::new(&db.pool);
+        assert_eq!(activity.count_for_address(raider_id).await.unwrap(), 4);
+
+        let events = activity.find_for_address(raider_id, 10, 0).await.unwrap();
+        let kinds: Vec<ActivityKind> = events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ActivityKind::RaidSubmission,
+                ActivityKind::TransferReceived,
+                ActivityKind::Referral,
+                ActivityKind::OptIn,
+            ]
+        );
+        assert_eq!(events[0].details["impression_count"], 7);
+        assert_eq!(events[1].details["counterparty"], referee_id.as_str());
+
+        let page = activity.find_for_address(raider_id, 2, 2).await.unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].kind, ActivityKind::Referral);
+
+        let referee_events = activity.find_for_address(referee_id, 10, 0).await.unwrap();
+        let kinds: Vec<ActivityKind> = referee_events.iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, vec![ActivityKind::TransferSent, ActivityKind::ReferredBy]);
+    }
+}
@@ -1,4 +1,6 @@
-use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::collections::HashMap;
+
+use sqlx::{PgExecutor, PgPool, Postgres, QueryBuilder};
 
 use crate::{
     db_persistence::DbError,
@@ -10,6 +12,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct AddressRepository {
     pool: PgPool,
+    read_pool: PgPool,
 }
 impl AddressRepository {
     fn build_base_query_with_optin_and_associations<'a>(
@@ -76,10 +79,26 @@ impl AddressRepository {
                 query_builder.push_condition(" x.username IS NULL ", &mut where_started);
             }
         }
+
+        // Filter: Banned, hidden unless asked for
+        match filters.is_banned {
+            Some(true) => query_builder.push_condition(" a.deleted_at IS NOT NULL ", &mut where_started),
+            Some(false) | None => query_builder.push_condition(" a.deleted_at IS NULL ", &mut where_started),
+        }
     }
 
     pub fn new(pool: &PgPool) -> Self {
-        Self { pool: pool.clone() }
+        Self {
+            pool: pool.clone(),
+            read_pool: pool.clone(),
+        }
+    }
+
+    /// Serves the admin list and the export from `read_pool`, e.g. a read replica. Auth and referral lookups stay on
+    /// the primary.
+    pub fn with_read_pool(mut self, read_pool: &PgPool) -> Self {
+        self.read_pool = read_pool.clone();
+        self
     }
 
     // Update count_filtered to pass the filters
@@ -95,7 +114,7 @@ impl AddressRepository {
 
         let count = query_builder
             .build_query_scalar()
-            .fetch_one(&self.pool)
+            .fetch_one(&self.read_pool)
             .await
             .map_err(DbError::Database)?;
 
@@ -127,6 +146,12 @@ impl AddressRepository {
     }
 
     pub async fn create_many(&self, addresses: Vec<Address>) -> DbResult<u64> {
+        Self::create_many_with(&self.pool, addresses).await
+    }
+
+    /// Inserts addresses, skipping ones that already exist. Takes any executor so it can run inside a
+    /// [`crate::db_persistence::UnitOfWork`].
+    pub async fn create_many_with<'e>(executor: impl PgExecutor<'e>, addresses: Vec<Address>) -> DbResult<u64> {
         if addresses.is_empty() {
             return Ok(0);
         }
@@ -153,21 +178,24 @@ impl AddressRepository {
         .bind(&quan_addresses)
         .bind(&referral_codes)
         .bind(&referrals_counts)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         Ok(result.rows_affected())
     }
 
+    /// Banned addresses are not returned, see [`Self::is_banned`].
     pub async fn find_by_id(&self, id: &str) -> DbResult<Option<Address>> {
-        let address = sqlx::query_as::<_, Address>("SELECT * FROM addresses WHERE quan_address = $1")
-            .bind(id)
-            .fetch_optional(&self.pool)
-            .await?;
+        let address =
+            sqlx::query_as::<_, Address>("SELECT * FROM addresses WHERE quan_address = $1 AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
 
         Ok(address)
     }
 
+    /// Includes banned addresses, their codes stay taken. Use it for collision checks.
     pub async fn find_by_referral_code(&self, referral_code: &str) -> DbResult<Option<Address>> {
         let address = sqlx::query_as::<_, Address>("SELECT * FROM addresses WHERE referral_code = $1")
             .bind(referral_code)
@@ -177,6 +205,136 @@ impl AddressRepository {
         Ok(address)
     }
 
+    /// Owner of a referral code that can still be referred by, banned addresses are not returned.
+    pub async fn find_referrer_by_code(&self, referral_code: &str) -> DbResult<Option<Address>> {
+        let address =
+            sqlx::query_as::<_, Address>("SELECT * FROM addresses WHERE referral_code = $1 AND deleted_at IS NULL")
+                .bind(referral_code)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(address)
+    }
+
+    /// Up to `limit` addresses ordered by address, starting after `after`. Banned addresses are left out. Used to
+    /// page through all addresses for exports.
+    pub async fn find_page_after(&self, after: Option<&str>, limit: i64) -> DbResult<Vec<Address>> {
+        let addresses = sqlx::query_as::<_, Address>(
+            r#"
+        SELECT * FROM addresses
+        WHERE deleted_at IS NULL AND ($1::VARCHAR IS NULL OR quan_address > $1)
+        ORDER BY quan_address ASC
+        LIMIT $2
+        "#,
+        )
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(addresses)
+    }
+
+    pub async fn is_banned(&self, quan_address: &str) -> DbResult<bool> {
+        let banned =
+            sqlx::query_scalar::<_, bool>("SELECT deleted_at IS NOT NULL FROM addresses WHERE quan_address = $1")
+                .bind(quan_address)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(banned.unwrap_or(false))
+    }
+
+    /// Bans an address. Banning it again keeps the original ban time and admin.
+    pub async fn soft_delete(&self, quan_address: &str, deleted_by: &str) -> DbResult<()> {
+        let result = sqlx::query(
+            r#"
+        UPDATE addresses
+        SET deleted_at = COALESCE(deleted_at, NOW()), deleted_by = COALESCE(deleted_by, $2)
+        WHERE quan_address = $1
+        "#,
+        )
+        .bind(quan_address)
+        .bind(deleted_by)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::AddressNotFound(quan_address.to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub async fn restore(&self, quan_address: &str) -> DbResult<()> {
+        let result = sqlx::query("UPDATE addresses SET deleted_at = NULL, deleted_by = NULL WHERE quan_address = $1")
+            .bind(quan_address)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::AddressNotFound(quan_address.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a `referral_code -> quan_address` map for the given codes that are already taken.
+    pub async fn find_owners_by_referral_codes(&self, referral_codes: &[String]) -> DbResult<HashMap<String, String>> {
+        if referral_codes.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT referral_code, quan_address FROM addresses WHERE referral_code = ANY($1)",
+        )
+        .bind(referral_codes)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Replaces the referral code of an address with a vanity code. Unless `allow_override` is set,
+    /// this only succeeds if no vanity code was set before.
+    pub async fn set_vanity_referral_code(
+        &self,
+        quan_address: &str,
+        referral_code: &str,
+        allow_override: bool,
+    ) -> DbResult<Option<Address>> {
+        let result = sqlx::query_as::<_, Address>(
+            r#"
+        UPDATE addresses
+        SET referral_code = $1, vanity_code_set_at = NOW()
+        WHERE quan_address = $2 AND ($3 OR vanity_code_set_at IS NULL)
+        RETURNING *
+        "#,
+        )
+        .bind(referral_code)
+        .bind(quan_address)
+        .bind(allow_override)
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(address) => Ok(address),
+            Err(sqlx::Error::Database(db_err)) => {
+                // Check specifically for Unique Violation (Postgres Code 23505)
+                if let Some(code) = db_err.code() {
+                    if code == "23505" {
+                        return Err(DbError::UniqueViolation(format!(
+                            "Referral code '{}' is already taken",
+                            referral_code
+                        )));
+                    }
+                }
+                Err(DbError::Database(sqlx::Error::Database(db_err)))
+            }
+            Err(e) => Err(DbError::Database(e)),
+        }
+    }
+
     #[cfg(test)]
     pub async fn find_all(&self) -> DbResult<Vec<Address>> {
         let addresses = sqlx::query_as::<_, Address>("SELECT * FROM addresses")
@@ -186,7 +344,16 @@ impl AddressRepository {
         Ok(addresses)
     }
 
+    /// Pool-based form of [`Self::increment_referrals_count_with`], not used by the main server binary.
+    #[allow(dead_code)]
     pub async fn increment_referrals_count(&self, quan_address: &str) -> DbResult<i32> {
+        Self::increment_referrals_count_with(&self.pool, quan_address).await
+    }
+
+    pub async fn increment_referrals_count_with<'e>(
+        executor: impl PgExecutor<'e>,
+        quan_address: &str,
+    ) -> DbResult<i32> {
         let new_count = sqlx::query_scalar::<_, i32>(
             r#"
         UPDATE addresses
@@ -196,12 +363,43 @@ impl AddressRepository {
         "#,
         )
         .bind(quan_address)
-        .fetch_one(&self.pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(new_count)
     }
 
+    pub async fn find_with_optin_and_associations_by_id(
+        &self,
+        quan_address: &str,
+    ) -> DbResult<Option<AddressWithOptInAndAssociations>> {
+        let address = sqlx::query_as::<_, AddressWithOptInAndAssociations>(
+            r#"
+            SELECT
+                a.quan_address,
+                a.referral_code,
+                a.referrals_count,
+                a.created_at,
+                a.updated_at,
+                CASE WHEN o.quan_address IS NOT NULL THEN TRUE ELSE FALSE END AS is_opted_in,
+                o.opt_in_number,
+                e.eth_address,
+                x.username as x_username,
+                a.deleted_at
+            FROM addresses a
+            LEFT JOIN opt_ins o ON a.quan_address = o.quan_address
+            LEFT JOIN eth_associations e ON a.quan_address = e.quan_address
+            LEFT JOIN x_associations x ON a.quan_address = x.quan_address
+            WHERE a.quan_address = $1
+            "#,
+        )
+        .bind(quan_address)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(address)
+    }
+
     pub async fn find_all_with_optin_and_associations(
         &self,
         params: &ListQueryParams<AddressSortColumn>,
@@ -218,7 +416,8 @@ impl AddressRepository {
                 CASE WHEN o.quan_address IS NOT NULL THEN TRUE ELSE FALSE END AS is_opted_in,
                 o.opt_in_number,
                 e.eth_address,
-                x.username as x_username
+                x.username as x_username,
+                a.deleted_at
             "#,
         );
 
@@ -239,7 +438,7 @@ impl AddressRepository {
 
         let addresses = query_builder
             .build_query_as::<AddressWithOptInAndAssociations>()
-            .fetch_all(&self.pool)
+            .fetch_all(&self.read_pool)
             .await
             .map_err(DbError::Database)?;
 
@@ -256,21 +455,15 @@ mod tests {
     use crate::utils::test_app_state::create_test_app_state;
     use crate::utils::test_db::{
         create_persisted_address, create_persisted_eth_association, create_persisted_opt_in,
-        create_persisted_x_association, reset_database,
+        create_persisted_x_association, isolated_pool,
     };
-    use sqlx::PgPool;
 
     // Helper function to set up a test repository using the app's config loader.
     // Note: This requires a `config/test.toml` file or equivalent environment
-    // variables (e.g., `TASKMASTER_DATA_DATABASE_URL`) for the tests to run.
+    // variables (e.g., `TASKMASTER_DATA__DATABASE_URL`) for the tests to run.
     async fn setup_test_repository() -> AddressRepository {
         let config = Config::load_test_env().expect("Failed to load configuration for tests");
-        let pool = PgPool::connect(config.get_database_url())
-            .await
-            .expect("Failed to create pool.");
-
-        // Clean database before each test
-        reset_database(&pool).await;
+        let pool = isolated_pool(config.get_database_url()).await;
 
         AddressRepository::new(&pool)
     }
@@ -337,6 +530,44 @@ mod tests {
         assert!(found.is_none());
     }
 
+    #[tokio::test]
+    async fn test_soft_delete_and_restore() {
+        let repo = setup_test_repository().await;
+        let address = create_mock_address("banned", "BANNED");
+        repo.create(&address).await.unwrap();
+        let id = &address.quan_address.0;
+
+        repo.soft_delete(id, "admin").await.unwrap();
+        assert!(repo.is_banned(id).await.unwrap());
+        assert!(repo.find_by_id(id).await.unwrap().is_none());
+        assert!(repo
+            .find_referrer_by_code(&address.referral_code)
+            .await
+            .unwrap()
+            .is_none());
+        // The code stays taken
+        assert!(repo
+            .find_by_referral_code(&address.referral_code)
+            .await
+            .unwrap()
+            .is_some());
+        // Admins still see banned addresses in the detail view
+        let detail = repo.find_with_optin_and_associations_by_id(id).await.unwrap().unwrap();
+        assert!(detail.deleted_at.is_some());
+
+        repo.restore(id).await.unwrap();
+        assert!(!repo.is_banned(id).await.unwrap());
+        assert!(repo.find_by_id(id).await.unwrap().is_some());
+        assert!(repo
+            .find_referrer_by_code(&address.referral_code)
+            .await
+            .unwrap()
+            .is_some());
+
+        let err = repo.soft_delete("non_existent_id", "admin").await.unwrap_err();
+        assert!(matches!(err, DbError::AddressNotFound(_)));
+    }
+
     #[tokio::test]
     async fn test_find_all() {
         let repo = setup_test_repository().await;
@@ -385,6 +616,57 @@ mod tests {
         assert_eq!(all.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_find_owners_by_referral_codes() {
+        let repo = setup_test_repository().await;
+        let address = create_mock_address("401", "REF401");
+        repo.create(&address).await.unwrap();
+
+        let owners = repo
+            .find_owners_by_referral_codes(&["ref401".to_string(), "ref402".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(owners.len(), 1);
+        assert_eq!(owners.get("ref401"), Some(&address.quan_address.0));
+    }
+
+    #[tokio::test]
+    async fn test_set_vanity_referral_code_only_once() {
+        let repo = setup_test_repository().await;
+        let address = create_mock_address("451", "REF451");
+        let other = create_mock_address("452", "REF452");
+        repo.create(&address).await.unwrap();
+        repo.create(&other).await.unwrap();
+
+        let updated = repo
+            .set_vanity_referral_code(&address.quan_address.0, "moonshot", false)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.referral_code, "moonshot");
+
+        // A second user request is refused, an admin override is not.
+        let refused = repo
+            .set_vanity_referral_code(&address.quan_address.0, "moonshot-2", false)
+            .await
+            .unwrap();
+        assert!(refused.is_none());
+
+        let overridden = repo
+            .set_vanity_referral_code(&address.quan_address.0, "quantus-og", true)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(overridden.referral_code, "quantus-og");
+
+        let taken = repo
+            .set_vanity_referral_code(&other.quan_address.0, "quantus-og", false)
+            .await
+            .unwrap_err();
+        assert!(matches!(taken, DbError::UniqueViolation(_)));
+    }
+
     #[tokio::test]
     async fn test_increment_referrals_count() {
         let repo = setup_test_repository().await;
@@ -404,7 +686,6 @@ mod tests {
     #[tokio::test]
     async fn test_find_all_with_optin_and_associations_data_integrity() {
         let state = create_test_app_state().await;
-        reset_database(&state.db.pool).await;
 
         let address = create_persisted_address(&state.db.addresses, "REF501").await;
         create_persisted_opt_in(&state.db.pool, &address.quan_address.0).await;
@@ -437,6 +718,7 @@ mod tests {
                     min_referrals: None,
                     has_eth_address: None,
                     has_x_account: None,
+                    is_banned: None,
                 },
             )
             .await
@@ -0,0 +1,184 @@
+use sqlx::PgPool;
+
+use crate::{
+    db_persistence::DbError,
+    models::address_note::{AddressNote, AddressNoteInput, AddressNoteRevision},
+    repositories::DbResult,
+};
+
+#[derive(Clone, Debug)]
+pub struct AddressNoteRepository {
+    pool: PgPool,
+}
+
+impl AddressNoteRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create(&self, quan_address: &str, input: &AddressNoteInput, author: &str) -> DbResult<AddressNote> {
+        let note = sqlx::query_as::<_, AddressNote>(
+            r#"
+        INSERT INTO address_notes (quan_address, body, ticket_ref, author, updated_by)
+        VALUES ($1, $2, $3, $4, $4)
+        RETURNING *
+        "#,
+        )
+        .bind(quan_address)
+        .bind(&input.body)
+        .bind(&input.ticket_ref)
+        .bind(author)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Updates a note, keeping its previous content as a revision.
+    pub async fn update(
+        &self,
+        quan_address: &str,
+        note_id: i32,
+        input: &AddressNoteInput,
+        edited_by: &str,
+    ) -> DbResult<AddressNote> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous = sqlx::query_as::<_, AddressNote>(
+            "SELECT * FROM address_notes WHERE id = $1 AND quan_address = $2 FOR UPDATE",
+        )
+        .bind(note_id)
+        .bind(quan_address)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DbError::RecordNotFound(format!("Address note {} not found", note_id)))?;
+
+        sqlx::query(
+            "INSERT INTO address_note_revisions (note_id, body, ticket_ref, edited_by) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(note_id)
+        .bind(&previous.body)
+        .bind(&previous.ticket_ref)
+        .bind(edited_by)
+        .execute(&mut *tx)
+        .await?;
+
+        let note = sqlx::query_as::<_, AddressNote>(
+            r#"
+        UPDATE address_notes
+        SET body = $1, ticket_ref = $2, updated_by = $3
+        WHERE id = $4
+        RETURNING *
+        "#,
+        )
+        .bind(&input.body)
+        .bind(&input.ticket_ref)
+        .bind(edited_by)
+        .bind(note_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(note)
+    }
+
+    pub async fn find_all_by_address(&self, quan_address: &str) -> DbResult<Vec<AddressNote>> {
+        let notes = sqlx::query_as::<_, AddressNote>(
+            "SELECT * FROM address_notes WHERE quan_address = $1 ORDER BY created_at DESC, id DESC",
+        )
+        .bind(quan_address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notes)
+    }
+
+    /// Previous versions of a note, most recent first.
+    pub async fn find_revisions(&self, quan_address: &str, note_id: i32) -> DbResult<Vec<AddressNoteRevision>> {
+        let revisions = sqlx::query_as::<_, AddressNoteRevision>(
+            r#"
+        SELECT r.*
+        FROM address_note_revisions r
+        JOIN address_notes n ON n.id = r.note_id
+        WHERE r.note_id = $1 AND n.quan_address = $2
+        ORDER BY r.created_at DESC, r.id DESC
+        "#,
+        )
+        .bind(note_id)
+        .bind(quan_address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(revisions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::create_persisted_address};
+
+    fn note_input(body: &str, ticket_ref: Option<&str>) -> AddressNoteInput {
+        AddressNoteInput {
+            body: body.to_string(),
+            ticket_ref: ticket_ref.map(String::from),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_revisions() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.address_notes;
+
+        let address = create_persisted_address(&state.db.addresses, "noted").await;
+        let quan_address = &address.quan_address.0;
+
+        let note = repo
+            .create(quan_address, &note_input("Reported missing reward", None), "alice")
+            .await
+            .unwrap();
+        assert_eq!(note.author, "alice");
+
+        let updated = repo
+            .update(
+                quan_address,
+                note.id,
+                &note_input("Reward resent", Some("SUP-42")),
+                "bob",
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.author, "alice");
+        assert_eq!(updated.updated_by, "bob");
+        assert_eq!(updated.ticket_ref.as_deref(), Some("SUP-42"));
+
+        let revisions = repo.find_revisions(quan_address, note.id).await.unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].body, "Reported missing reward");
+        assert_eq!(revisions[0].edited_by, "bob");
+
+        let notes = repo.find_all_by_address(quan_address).await.unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].body, "Reward resent");
+    }
+
+    #[tokio::test]
+    async fn test_update_note_of_other_address() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.address_notes;
+
+        let owner = create_persisted_address(&state.db.addresses, "owner").await;
+        let other = create_persisted_address(&state.db.addresses, "other").await;
+        let note = repo
+            .create(&owner.quan_address.0, &note_input("Note", None), "alice")
+            .await
+            .unwrap();
+
+        let err = repo
+            .update(&other.quan_address.0, note.id, &note_input("Edit", None), "bob")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DbError::RecordNotFound(_)));
+    }
+}
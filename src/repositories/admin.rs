@@ -1,7 +1,11 @@
 use sqlx::{PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
-use crate::{models::admin::Admin, repositories::DbResult};
+use crate::{
+    db_persistence::DbError,
+    models::admin::{Admin, AdminRole},
+    repositories::DbResult,
+};
 
 #[derive(Clone, Debug)]
 pub struct AdminRepository {
@@ -35,4 +39,59 @@ impl AdminRepository {
 
         Ok(admin)
     }
+
+    pub async fn find_all(&self) -> DbResult<Vec<Admin>> {
+        let mut qb = AdminRepository::create_select_base_query();
+        qb.push(" ORDER BY created_at ASC");
+
+        let admins = qb.build_query_as().fetch_all(&self.pool).await?;
+
+        Ok(admins)
+    }
+
+    /// Stores a new admin, `password_hash` must already be an Argon2 hash.
+    pub async fn create(&self, username: &str, password_hash: &str, role: AdminRole) -> DbResult<Admin> {
+        let admin =
+            sqlx::query_as::<_, Admin>("INSERT INTO admins (username, password, role) VALUES ($1, $2, $3) RETURNING *")
+                .bind(username)
+                .bind(password_hash)
+                .bind(role.as_str())
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|err| match &err {
+                    sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+                        DbError::UniqueViolation(format!("Admin with username {} already exists", username))
+                    }
+                    _ => DbError::Database(err),
+                })?;
+
+        Ok(admin)
+    }
+
+    pub async fn update_role(&self, id: &Uuid, role: AdminRole) -> DbResult<Option<Admin>> {
+        let admin = sqlx::query_as::<_, Admin>("UPDATE admins SET role = $2 WHERE id = $1 RETURNING *")
+            .bind(id)
+            .bind(role.as_str())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(admin)
+    }
+
+    pub async fn set_disabled(&self, id: &Uuid, disabled: bool) -> DbResult<Option<Admin>> {
+        let admin = sqlx::query_as::<_, Admin>(
+            r#"
+        UPDATE admins
+        SET disabled_at = CASE WHEN $2 THEN COALESCE(disabled_at, NOW()) ELSE NULL END
+        WHERE id = $1
+        RETURNING *
+        "#,
+        )
+        .bind(id)
+        .bind(disabled)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(admin)
+    }
 }
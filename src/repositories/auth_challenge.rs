@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::{http_server::Challenge, repositories::DbResult};
+
+#[derive(Clone, Debug)]
+pub struct AuthChallengeRepository {
+    pool: PgPool,
+}
+
+impl AuthChallengeRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create(&self, temp_session_id: &str, challenge: &Challenge) -> DbResult<()> {
+        sqlx::query("INSERT INTO auth_challenges (temp_session_id, challenge, created_at) VALUES ($1, $2, $3)")
+            .bind(temp_session_id)
+            .bind(&challenge.challenge)
+            .bind(challenge.created_at)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, temp_session_id: &str) -> DbResult<Option<Challenge>> {
+        let challenge = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+            "SELECT challenge, created_at FROM auth_challenges WHERE temp_session_id = $1",
+        )
+        .bind(temp_session_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(challenge.map(|(challenge, created_at)| Challenge { challenge, created_at }))
+    }
+
+    pub async fn delete(&self, temp_session_id: &str) -> DbResult<()> {
+        sqlx::query("DELETE FROM auth_challenges WHERE temp_session_id = $1")
+            .bind(temp_session_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_created_before(&self, cutoff: DateTime<Utc>) -> DbResult<u64> {
+        let result = sqlx::query("DELETE FROM auth_challenges WHERE created_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,76 @@
+use sqlx::PgPool;
+
+use crate::{
+    db_persistence::DbError,
+    models::feature_flag::{FeatureFlag, FeatureFlagInput},
+    repositories::DbResult,
+};
+
+#[derive(Clone, Debug)]
+pub struct FeatureFlagRepository {
+    pool: PgPool,
+}
+
+impl FeatureFlagRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn find_all(&self) -> DbResult<Vec<FeatureFlag>> {
+        let flags = sqlx::query_as::<_, FeatureFlag>("SELECT * FROM feature_flags ORDER BY key")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(flags)
+    }
+
+    /// Used by the `RequireFeature` guard.
+    pub async fn find_by_key(&self, key: &str) -> DbResult<Option<FeatureFlag>> {
+        let flag = sqlx::query_as::<_, FeatureFlag>("SELECT * FROM feature_flags WHERE key = $1")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(flag)
+    }
+
+    pub async fn upsert(&self, key: &str, input: &FeatureFlagInput, updated_by: &str) -> DbResult<FeatureFlag> {
+        let flag = sqlx::query_as::<_, FeatureFlag>(
+            r#"
+        INSERT INTO feature_flags (key, description, enabled, rollout_percentage, allowlist, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (key)
+        DO UPDATE SET
+            description = EXCLUDED.description,
+            enabled = EXCLUDED.enabled,
+            rollout_percentage = EXCLUDED.rollout_percentage,
+            allowlist = EXCLUDED.allowlist,
+            updated_by = EXCLUDED.updated_by
+        RETURNING *
+        "#,
+        )
+        .bind(key)
+        .bind(&input.description)
+        .bind(input.enabled)
+        .bind(input.rollout_percentage)
+        .bind(&input.allowlist)
+        .bind(updated_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(flag)
+    }
+
+    pub async fn delete(&self, key: &str) -> DbResult<()> {
+        let result = sqlx::query("DELETE FROM feature_flags WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::RecordNotFound(format!("Feature flag '{}' not found", key)));
+        }
+
+        Ok(())
+    }
+}
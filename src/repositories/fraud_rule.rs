@@ -0,0 +1,372 @@
+use sqlx::{types::Json, PgConnection, PgPool, Postgres, QueryBuilder};
+
+use crate::{
+    db_persistence::DbError,
+    models::fraud_rule::{
+        FraudFlag, FraudFlagQuery, FraudRule, FraudRuleAction, FraudRuleDefinition, FraudRuleEvaluation,
+        FraudRuleInput, FraudRuleVersion,
+    },
+    repositories::{DbResult, QueryBuilderExt},
+};
+
+#[derive(Clone, Debug)]
+pub struct FraudRuleRepository {
+    pool: PgPool,
+}
+
+/// Appends a `SELECT quan_address, submission_id` of everything the definition currently matches.
+fn push_matches(qb: &mut QueryBuilder<'_, Postgres>, definition: &FraudRuleDefinition) {
+    match definition {
+        FraudRuleDefinition::SubmissionVelocity {
+            max_submissions,
+            window_minutes,
+        } => {
+            qb.push(
+                r#"
+            SELECT rs.raider_id, rs.id
+            FROM raid_submissions rs
+            JOIN (
+                SELECT raider_id
+                FROM raid_submissions
+                WHERE is_invalid = false AND created_at >= NOW() - make_interval(mins => "#,
+            );
+            qb.push_bind(*window_minutes as i32);
+            qb.push(
+                r#")
+                GROUP BY raider_id
+                HAVING COUNT(*) > "#,
+            );
+            qb.push_bind(*max_submissions as i64);
+            qb.push(
+                r#"
+            ) fast ON fast.raider_id = rs.raider_id
+            WHERE rs.is_invalid = false AND rs.created_at >= NOW() - make_interval(mins => "#,
+            );
+            qb.push_bind(*window_minutes as i32);
+            qb.push(")");
+        }
+        FraudRuleDefinition::NewAddressSubmissions { min_age_hours } => {
+            qb.push(
+                r#"
+            SELECT rs.raider_id, rs.id
+            FROM raid_submissions rs
+            JOIN addresses a ON a.quan_address = rs.raider_id
+            WHERE rs.is_invalid = false AND rs.created_at < a.created_at + make_interval(hours => "#,
+            );
+            qb.push_bind(*min_age_hours as i32);
+            qb.push(")");
+        }
+        FraudRuleDefinition::RecentXAssociation { min_age_hours } => {
+            qb.push(
+                r#"
+            SELECT rs.raider_id, rs.id
+            FROM raid_submissions rs
+            JOIN x_associations x ON x.quan_address = rs.raider_id
+            WHERE rs.is_invalid = false AND rs.created_at < x.created_at + make_interval(hours => "#,
+            );
+            qb.push_bind(*min_age_hours as i32);
+            qb.push(")");
+        }
+        FraudRuleDefinition::SharedEthAddress { max_addresses } => {
+            qb.push(
+                r#"
+            SELECT e.quan_address, NULL::VARCHAR
+            FROM eth_associations e
+            WHERE LOWER(e.eth_address) IN (
+                SELECT LOWER(eth_address)
+                FROM eth_associations
+                GROUP BY LOWER(eth_address)
+                HAVING COUNT(*) > "#,
+            );
+            qb.push_bind(*max_addresses as i64);
+            qb.push(")");
+        }
+    }
+}
+
+async fn insert_version(conn: &mut PgConnection, rule: &FraudRule) -> DbResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO fraud_rule_versions (rule_id, version, definition, action, enabled, changed_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(rule.id)
+    .bind(rule.version)
+    .bind(Json(&rule.definition))
+    .bind(rule.action.as_str())
+    .bind(rule.enabled)
+    .bind(&rule.updated_by)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+impl FraudRuleRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn find_all(&self) -> DbResult<Vec<FraudRule>> {
+        let rules = sqlx::query_as::<_, FraudRule>("SELECT * FROM fraud_rules ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rules)
+    }
+
+    pub async fn create(&self, input: &FraudRuleInput, created_by: &str) -> DbResult<FraudRule> {
+        let mut tx = self.pool.begin().await?;
+
+        let rule = sqlx::query_as::<_, FraudRule>(
+            r#"
+        INSERT INTO fraud_rules (name, definition, action, enabled, updated_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+        )
+        .bind(input.name.trim())
+        .bind(Json(&input.definition))
+        .bind(input.action.as_str())
+        .bind(input.enabled)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        insert_version(&mut tx, &rule).await?;
+        tx.commit().await?;
+
+        Ok(rule)
+    }
+
+    /// Replaces a rule and bumps its version. Flags already recorded keep pointing at the version that raised them.
+    pub async fn update(&self, id: i32, input: &FraudRuleInput, updated_by: &str) -> DbResult<FraudRule> {
+        let mut tx = self.pool.begin().await?;
+
+        let rule = sqlx::query_as::<_, FraudRule>(
+            r#"
+        UPDATE fraud_rules
+        SET name = $1, definition = $2, action = $3, enabled = $4, updated_by = $5, version = version + 1
+        WHERE id = $6
+        RETURNING *
+        "#,
+        )
+        .bind(input.name.trim())
+        .bind(Json(&input.definition))
+        .bind(input.action.as_str())
+        .bind(input.enabled)
+        .bind(updated_by)
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DbError::RecordNotFound(format!("Fraud rule {} not found", id)))?;
+
+        insert_version(&mut tx, &rule).await?;
+        tx.commit().await?;
+
+        Ok(rule)
+    }
+
+    /// All versions of a rule, most recent first.
+    pub async fn find_versions(&self, id: i32) -> DbResult<Vec<FraudRuleVersion>> {
+        let versions = sqlx::query_as::<_, FraudRuleVersion>(
+            "SELECT * FROM fraud_rule_versions WHERE rule_id = $1 ORDER BY version DESC",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(versions)
+    }
+
+    pub async fn find_flags(&self, query: &FraudFlagQuery) -> DbResult<Vec<FraudFlag>> {
+        let mut qb = QueryBuilder::<Postgres>::new("SELECT * FROM fraud_flags");
+        let mut where_started = false;
+
+        if let Some(rule_id) = query.rule_id {
+            qb.push_condition("rule_id = ", &mut where_started);
+            qb.push_bind(rule_id);
+        }
+        if let Some(quan_address) = &query.quan_address {
+            qb.push_condition("quan_address = ", &mut where_started);
+            qb.push_bind(quan_address);
+        }
+        qb.push(" ORDER BY created_at DESC, id DESC");
+
+        let flags = qb.build_query_as::<FraudFlag>().fetch_all(&self.pool).await?;
+
+        Ok(flags)
+    }
+
+    /// Runs a rule against the current data. Matches already flagged by this version of the rule are skipped, so
+    /// evaluating repeatedly only counts new hits.
+    pub async fn evaluate(&self, rule: &FraudRule) -> DbResult<FraudRuleEvaluation> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "INSERT INTO fraud_flags (rule_id, rule_version, quan_address, submission_id) SELECT ",
+        );
+        qb.push_bind(rule.id);
+        qb.push(", ");
+        qb.push_bind(rule.version);
+        qb.push(", m.quan_address, m.submission_id FROM (");
+        push_matches(&mut qb, &rule.definition);
+        qb.push(
+            r#"
+        ) AS m (quan_address, submission_id)
+        ON CONFLICT (rule_id, rule_version, quan_address, COALESCE(submission_id, '')) DO NOTHING
+        RETURNING quan_address, submission_id
+        "#,
+        );
+
+        let hits: Vec<(String, Option<String>)> = qb.build_query_as().fetch_all(&mut *tx).await?;
+
+        let new_flags = hits.len() as u64;
+        let mut quarantined_submissions = 0;
+        if !hits.is_empty() {
+            sqlx::query("UPDATE fraud_rules SET hit_count = hit_count + $1, last_hit_at = NOW() WHERE id = $2")
+                .bind(new_flags as i64)
+                .bind(rule.id)
+                .execute(&mut *tx)
+                .await?;
+
+            if rule.action == FraudRuleAction::Quarantine {
+                let address_level = rule.definition.is_address_level();
+                let (quan_addresses, submission_ids): (Vec<String>, Vec<String>) = if address_level {
+                    (hits.into_iter().map(|(address, _)| address).collect(), Vec::new())
+                } else {
+                    (Vec::new(), hits.into_iter().filter_map(|(_, id)| id).collect())
+                };
+
+                quarantined_submissions = sqlx::query(
+                    r#"
+                UPDATE raid_submissions
+                SET is_invalid = true
+                WHERE is_invalid = false AND (id = ANY($1) OR raider_id = ANY($2))
+                "#,
+                )
+                .bind(&submission_ids)
+                .bind(&quan_addresses)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(FraudRuleEvaluation {
+            rule_id: rule.id,
+            name: rule.name.clone(),
+            version: rule.version,
+            new_flags,
+            quarantined_submissions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::raid_quest::CreateRaidQuest,
+        utils::{
+            test_app_state::create_test_app_state,
+            test_db::{create_persisted_address, SubmissionBuilder},
+        },
+    };
+
+    fn velocity_rule(max_submissions: u32, action: FraudRuleAction) -> FraudRuleInput {
+        FraudRuleInput {
+            name: "velocity".to_string(),
+            definition: FraudRuleDefinition::SubmissionVelocity {
+                max_submissions,
+                window_minutes: 60,
+            },
+            action,
+            enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_quarantines_once_per_version() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.fraud_rules;
+
+        let raid_id = state
+            .db
+            .raid_quests
+            .create(&CreateRaidQuest {
+                name: "Raid".to_string(),
+                scoring: Default::default(),
+            })
+            .await
+            .unwrap();
+        let farmer = create_persisted_address(&state.db.addresses, "farmer").await;
+        let raider = create_persisted_address(&state.db.addresses, "raider").await;
+        for i in 0..3 {
+            SubmissionBuilder::new(&format!("f{}", i), raid_id, &farmer.quan_address.0)
+                .create(&state.db.pool)
+                .await;
+        }
+        SubmissionBuilder::new("r0", raid_id, &raider.quan_address.0)
+            .create(&state.db.pool)
+            .await;
+
+        let rule = repo
+            .create(&velocity_rule(2, FraudRuleAction::Quarantine), "alice")
+            .await
+            .unwrap();
+
+        let evaluation = repo.evaluate(&rule).await.unwrap();
+        assert_eq!(evaluation.new_flags, 3);
+        assert_eq!(evaluation.quarantined_submissions, 3);
+
+        // Quarantined submissions no longer count towards the velocity
+        let evaluation = repo.evaluate(&rule).await.unwrap();
+        assert_eq!(evaluation.new_flags, 0);
+
+        let rule = repo.find_all().await.unwrap().remove(0);
+        assert_eq!(rule.hit_count, 3);
+        assert!(rule.last_hit_at.is_some());
+
+        let flags = repo
+            .find_flags(&FraudFlagQuery {
+                rule_id: Some(rule.id),
+                quan_address: None,
+            })
+            .await
+            .unwrap();
+        assert!(flags.iter().all(|flag| flag.quan_address == farmer.quan_address.0));
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_versions() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.fraud_rules;
+
+        let rule = repo
+            .create(&velocity_rule(5, FraudRuleAction::Flag), "alice")
+            .await
+            .unwrap();
+        let updated = repo
+            .update(rule.id, &velocity_rule(3, FraudRuleAction::Quarantine), "bob")
+            .await
+            .unwrap();
+        assert_eq!(updated.version, 2);
+        assert_eq!(updated.action, FraudRuleAction::Quarantine);
+
+        let versions = repo.find_versions(rule.id).await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].version, 2);
+        assert_eq!(versions[0].changed_by, "bob");
+        assert_eq!(versions[1].changed_by, "alice");
+
+        let err = repo
+            .update(rule.id + 1, &velocity_rule(3, FraudRuleAction::Flag), "bob")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DbError::RecordNotFound(_)));
+    }
+}
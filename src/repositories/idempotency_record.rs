@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::{models::idempotency_record::IdempotencyRecord, repositories::DbResult};
+
+#[derive(Clone, Debug)]
+pub struct IdempotencyRecordRepository {
+    pool: PgPool,
+}
+
+impl IdempotencyRecordRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Claims the key for a request. Returns false when the owner already used the key, unless that request is
+    /// still pending since before `abandoned_before` with the same hash. The client went away or the handler
+    /// panicked then, so the key is claimed again rather than stuck until it expires.
+    pub async fn try_start(
+        &self,
+        owner: &str,
+        idempotency_key: &str,
+        request_hash: &str,
+        abandoned_before: DateTime<Utc>,
+    ) -> DbResult<bool> {
+        let result = sqlx::query(
+            r#"
+        INSERT INTO idempotency_records (owner, idempotency_key, request_hash)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (owner, idempotency_key) DO UPDATE SET created_at = NOW()
+        WHERE idempotency_records.status_code IS NULL
+            AND idempotency_records.request_hash = EXCLUDED.request_hash
+            AND idempotency_records.created_at < $4
+        "#,
+        )
+        .bind(owner)
+        .bind(idempotency_key)
+        .bind(request_hash)
+        .bind(abandoned_before)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn find(&self, owner: &str, idempotency_key: &str) -> DbResult<Option<IdempotencyRecord>> {
+        let record = sqlx::query_as::<_, IdempotencyRecord>(
+            "SELECT * FROM idempotency_records WHERE owner = $1 AND idempotency_key = $2",
+        )
+        .bind(owner)
+        .bind(idempotency_key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn complete(
+        &self,
+        owner: &str,
+        idempotency_key: &str,
+        status_code: u16,
+        response_body: &[u8],
+        content_type: Option<&str>,
+    ) -> DbResult<()> {
+        sqlx::query(
+            r#"
+        UPDATE idempotency_records
+        SET status_code = $3, response_body = $4, content_type = $5
+        WHERE owner = $1 AND idempotency_key = $2
+        "#,
+        )
+        .bind(owner)
+        .bind(idempotency_key)
+        .bind(status_code as i16)
+        .bind(response_body)
+        .bind(content_type)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Releases a key so the request can be retried.
+    pub async fn delete(&self, owner: &str, idempotency_key: &str) -> DbResult<()> {
+        sqlx::query("DELETE FROM idempotency_records WHERE owner = $1 AND idempotency_key = $2")
+            .bind(owner)
+            .bind(idempotency_key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_created_before(&self, cutoff: DateTime<Utc>) -> DbResult<u64> {
+        let result = sqlx::query("DELETE FROM idempotency_records WHERE created_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,69 @@
+use sqlx::PgPool;
+
+use crate::{models::maintenance::PauseState, repositories::DbResult};
+
+#[derive(Clone, Debug)]
+pub struct MaintenanceRepository {
+    pool: PgPool,
+}
+
+impl MaintenanceRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn find_pause_state(&self) -> DbResult<PauseState> {
+        let state = sqlx::query_as::<_, PauseState>(
+            "SELECT paused, reason, updated_by, updated_at FROM maintenance_pause WHERE id",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(state.unwrap_or_default())
+    }
+
+    pub async fn set_paused(&self, paused: bool, reason: Option<&str>, updated_by: &str) -> DbResult<PauseState> {
+        let state = sqlx::query_as::<_, PauseState>(
+            r#"
+        INSERT INTO maintenance_pause (id, paused, reason, updated_by) VALUES (TRUE, $1, $2, $3)
+        ON CONFLICT (id) DO UPDATE SET
+            paused = EXCLUDED.paused,
+            reason = EXCLUDED.reason,
+            updated_by = EXCLUDED.updated_by,
+            updated_at = NOW()
+        RETURNING paused, reason, updated_by, updated_at
+        "#,
+        )
+        .bind(paused)
+        .bind(reason)
+        .bind(updated_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_app_state::create_test_app_state;
+
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.maintenance;
+
+        assert!(!repo.find_pause_state().await.unwrap().paused);
+
+        repo.set_paused(true, Some("runtime upgrade"), "admin").await.unwrap();
+        let paused = repo.find_pause_state().await.unwrap();
+        assert!(paused.paused);
+        assert_eq!(paused.reason.as_deref(), Some("runtime upgrade"));
+        assert_eq!(paused.updated_by.as_deref(), Some("admin"));
+
+        let resumed = repo.set_paused(false, None, "other-admin").await.unwrap();
+        assert!(!resumed.paused);
+        assert_eq!(resumed.reason, None);
+        assert!(!repo.find_pause_state().await.unwrap().paused);
+    }
+}
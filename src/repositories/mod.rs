@@ -4,11 +4,27 @@ use crate::db_persistence::DbError;
 
 pub type DbResult<T> = Result<T, DbError>;
 
+pub mod activity;
 pub mod address;
+pub mod address_note;
 pub mod admin;
+pub mod auth_challenge;
+pub mod feature_flag;
+pub mod fraud_rule;
+pub mod idempotency_record;
+pub mod maintenance;
+pub mod opt_in_stat;
+pub mod processed_transfer;
+pub mod raid_payout;
 pub mod raid_quest;
+pub mod raid_team;
 pub mod referral;
+pub mod referral_reward;
 pub mod relevant_tweet;
+pub mod session;
+pub mod setting;
+pub mod sybil_score;
+pub mod sync_state;
 pub mod tweet_author;
 
 pub trait QueryBuilderExt {
@@ -30,3 +46,15 @@ impl<'a> QueryBuilderExt for QueryBuilder<'a, Postgres> {
 pub fn calculate_page_offset(page: u32, page_size: u32) -> u32 {
     (page - 1) * page_size
 }
+
+/// Turns a search into a `to_tsquery` expression matching each word as a prefix, so `@sam_al` finds `sam_alt`.
+/// Operator characters are dropped, `None` means nothing is left to search for.
+pub fn prefix_tsquery(search: &str) -> Option<String> {
+    let terms: Vec<String> = search
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("{}:*", term))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" & "))
+}
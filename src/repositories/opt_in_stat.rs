@@ -0,0 +1,132 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use crate::{
+    models::opt_in_stat::{DailyOptIns, ReferralSourceOptIns},
+    repositories::DbResult,
+};
+
+/// Reads the pre-aggregated opt-in counts that triggers on `opt_ins` maintain.
+#[derive(Clone, Debug)]
+pub struct OptInStatRepository {
+    pool: PgPool,
+    read_pool: PgPool,
+}
+
+impl OptInStatRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self {
+            pool: pool.clone(),
+            read_pool: pool.clone(),
+        }
+    }
+
+    /// Serves the opt-in stats from `read_pool`, e.g. a read replica.
+    pub fn with_read_pool(mut self, read_pool: &PgPool) -> Self {
+        self.read_pool = read_pool.clone();
+        self
+    }
+
+    pub async fn find_by_day(&self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> DbResult<Vec<DailyOptIns>> {
+        let days = sqlx::query_as::<_, DailyOptIns>(
+            r#"
+            SELECT day, SUM(opt_ins)::BIGINT AS opt_ins
+            FROM opt_in_daily_stats
+            WHERE ($1::DATE IS NULL OR day >= $1) AND ($2::DATE IS NULL OR day <= $2)
+            GROUP BY day
+            HAVING SUM(opt_ins) > 0
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(days)
+    }
+
+    pub async fn find_by_referral_source(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        limit: u32,
+    ) -> DbResult<Vec<ReferralSourceOptIns>> {
+        let sources = sqlx::query_as::<_, ReferralSourceOptIns>(
+            r#"
+            SELECT referral_source, SUM(opt_ins)::BIGINT AS opt_ins
+            FROM opt_in_daily_stats
+            WHERE ($1::DATE IS NULL OR day >= $1) AND ($2::DATE IS NULL OR day <= $2)
+            GROUP BY referral_source
+            HAVING SUM(opt_ins) > 0
+            ORDER BY opt_ins DESC, referral_source ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit as i64)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(sources)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{
+        test_app_state::create_test_app_state,
+        test_db::{create_persisted_address, create_persisted_opt_in},
+    };
+
+    #[tokio::test]
+    async fn test_stats_follow_opt_in_events() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.opt_in_stats;
+
+        let referrer = create_persisted_address(&state.db.addresses, "referrer")
+            .await
+            .quan_address
+            .0;
+        let referee = create_persisted_address(&state.db.addresses, "referee")
+            .await
+            .quan_address
+            .0;
+        let direct = create_persisted_address(&state.db.addresses, "direct")
+            .await
+            .quan_address
+            .0;
+        sqlx::query("INSERT INTO referrals (referrer_address, referee_address) VALUES ($1, $2)")
+            .bind(&referrer)
+            .bind(&referee)
+            .execute(&state.db.pool)
+            .await
+            .unwrap();
+
+        create_persisted_opt_in(&state.db.pool, &referee).await;
+        create_persisted_opt_in(&state.db.pool, &direct).await;
+
+        let days = repo.find_by_day(None, None).await.unwrap();
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].opt_ins, 2);
+
+        let sources = repo.find_by_referral_source(None, None, 10).await.unwrap();
+        let count_for = |source: &str| sources.iter().find(|s| s.referral_source == source).map(|s| s.opt_ins);
+        assert_eq!(count_for(&referrer), Some(1));
+        assert_eq!(count_for("direct"), Some(1));
+
+        sqlx::query("DELETE FROM opt_ins WHERE quan_address = $1")
+            .bind(&direct)
+            .execute(&state.db.pool)
+            .await
+            .unwrap();
+
+        let sources = repo.find_by_referral_source(None, None, 10).await.unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].referral_source, referrer);
+
+        let tomorrow = days[0].day.succ_opt().unwrap();
+        assert!(repo.find_by_day(Some(tomorrow), None).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+
+use sqlx::{PgExecutor, PgPool};
+
+use crate::{
+    models::processed_transfer::{ProcessedTransfer, ProcessedTransferPage, TransferGap},
+    repositories::DbResult,
+};
+
+#[derive(Clone, Debug)]
+pub struct ProcessedTransferRepository {
+    pool: PgPool,
+}
+
+impl ProcessedTransferRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Pool-based form of [`Self::create_many_with`], not used by the main server binary.
+    #[allow(dead_code)]
+    pub async fn create_many(&self, transfers: Vec<ProcessedTransfer>) -> DbResult<u64> {
+        Self::create_many_with(&self.pool, transfers).await
+    }
+
+    /// Takes any executor so it can run inside a [`crate::db_persistence::UnitOfWork`].
+    pub async fn create_many_with<'e>(
+        executor: impl PgExecutor<'e>,
+        transfers: Vec<ProcessedTransfer>,
+    ) -> DbResult<u64> {
+        if transfers.is_empty() {
+            return Ok(0);
+        }
+
+        let mut transfer_ids = Vec::with_capacity(transfers.len());
+        let mut from_addresses = Vec::with_capacity(transfers.len());
+        let mut to_addresses = Vec::with_capacity(transfers.len());
+        let mut amounts = Vec::with_capacity(transfers.len());
+
+        for transfer in transfers {
+            transfer_ids.push(transfer.transfer_id);
+            from_addresses.push(transfer.from_address);
+            to_addresses.push(transfer.to_address);
+            amounts.push(transfer.amount);
+        }
+
+        let result = sqlx::query(
+            r#"
+        INSERT INTO processed_transfers (transfer_id, from_address, to_address, amount)
+        SELECT * FROM UNNEST($1, $2, $3, $4)
+        ON CONFLICT (transfer_id) DO NOTHING
+        "#,
+        )
+        .bind(&transfer_ids)
+        .bind(&from_addresses)
+        .bind(&to_addresses)
+        .bind(&amounts)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Records a page the sync stored, for [`Self::find_gaps`].
+    pub async fn create_page_with<'e>(executor: impl PgExecutor<'e>, page: &ProcessedTransferPage) -> DbResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO processed_transfer_pages (after_cursor, end_cursor, first_transfer_id, last_transfer_id)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(&page.after_cursor)
+        .bind(&page.end_cursor)
+        .bind(&page.first_transfer_id)
+        .bind(&page.last_transfer_id)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Ranges the sync skipped, e.g. because its cursor was moved by hand. Each one starts where the closest
+    /// earlier page ended.
+    pub async fn find_gaps(&self) -> DbResult<Vec<TransferGap>> {
+        let gaps = sqlx::query_as::<_, TransferGap>(
+            r#"
+            SELECT
+                (
+                    SELECT MAX(earlier.last_transfer_id)
+                    FROM processed_transfer_pages earlier
+                    WHERE earlier.last_transfer_id < page.first_transfer_id
+                ) AS after_transfer_id,
+                page.first_transfer_id AS before_transfer_id,
+                page.after_cursor AS resumed_after_cursor
+            FROM processed_transfer_pages page
+            WHERE page.after_cursor IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM processed_transfer_pages previous WHERE previous.end_cursor = page.after_cursor
+              )
+            ORDER BY page.first_transfer_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(gaps)
+    }
+
+    /// Returns the subset of `transfer_ids` that has already been ingested.
+    pub async fn find_processed_ids(&self, transfer_ids: &[String]) -> DbResult<HashSet<String>> {
+        if transfer_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let ids =
+            sqlx::query_scalar::<_, String>("SELECT transfer_id FROM processed_transfers WHERE transfer_id = ANY($1)")
+                .bind(transfer_ids)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    pub async fn find_by_id(&self, transfer_id: &str) -> DbResult<Option<ProcessedTransfer>> {
+        let transfer =
+            sqlx::query_as::<_, ProcessedTransfer>("SELECT * FROM processed_transfers WHERE transfer_id = $1")
+                .bind(transfer_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(transfer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_app_state::create_test_app_state;
+
+    fn processed(id: &str) -> ProcessedTransfer {
+        ProcessedTransfer {
+            transfer_id: id.to_string(),
+            from_address: "qz_test_address_from".to_string(),
+            to_address: "qz_test_address_to".to_string(),
+            amount: "1000".to_string(),
+            processed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_many_is_idempotent() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.processed_transfers;
+
+        let inserted = repo
+            .create_many(vec![processed("t-1"), processed("t-2")])
+            .await
+            .unwrap();
+        assert_eq!(inserted, 2);
+
+        let inserted = repo
+            .create_many(vec![processed("t-2"), processed("t-3")])
+            .await
+            .unwrap();
+        assert_eq!(inserted, 1);
+
+        let known = repo
+            .find_processed_ids(&["t-1".to_string(), "t-3".to_string(), "t-4".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(known, HashSet::from(["t-1".to_string(), "t-3".to_string()]));
+
+        let found = repo.find_by_id("t-2").await.unwrap().unwrap();
+        assert_eq!(found.amount, "1000");
+        assert!(found.processed_at.is_some());
+        assert!(repo.find_by_id("t-4").await.unwrap().is_none());
+    }
+
+    fn page(after_cursor: Option<&str>, end_cursor: &str, first: &str, last: &str) -> ProcessedTransferPage {
+        ProcessedTransferPage {
+            after_cursor: after_cursor.map(str::to_string),
+            end_cursor: end_cursor.to_string(),
+            first_transfer_id: first.to_string(),
+            last_transfer_id: last.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_gaps() {
+        let state = create_test_app_state().await;
+        let pool = &state.db.pool;
+
+        ProcessedTransferRepository::create_page_with(pool, &page(None, "2", "t-01", "t-02"))
+            .await
+            .unwrap();
+        ProcessedTransferRepository::create_page_with(pool, &page(Some("2"), "4", "t-03", "t-04"))
+            .await
+            .unwrap();
+        assert!(state.db.processed_transfers.find_gaps().await.unwrap().is_empty());
+
+        // The cursor was moved from 4 to 8 by hand
+        ProcessedTransferRepository::create_page_with(pool, &page(Some("8"), "10", "t-09", "t-10"))
+            .await
+            .unwrap();
+
+        let gaps = state.db.processed_transfers.find_gaps().await.unwrap();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].after_transfer_id.as_deref(), Some("t-04"));
+        assert_eq!(gaps[0].before_transfer_id, "t-09");
+        assert_eq!(gaps[0].resumed_after_cursor, "8");
+    }
+}
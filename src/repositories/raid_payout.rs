@@ -0,0 +1,244 @@
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+use crate::{
+    models::{
+        raid_payout::{RaidPayout, RaidPayoutShare, RaiderScore},
+        raid_quest::{SUBMISSION_ELIGIBLE_SQL, SUBMISSION_SCORE_SQL},
+    },
+    repositories::DbResult,
+};
+
+#[derive(Clone, Debug)]
+pub struct RaidPayoutRepository {
+    pool: PgPool,
+}
+
+impl RaidPayoutRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// The `limit` best raiders of a raid by their weighted score, see [`SUBMISSION_SCORE_SQL`]. Banned raiders,
+    /// raiders below the follower threshold and raiders without a positive score are left out.
+    pub async fn find_top_raiders(&self, raid_id: i32, limit: u32) -> DbResult<Vec<RaiderScore>> {
+        Self::find_top_raiders_with(&self.pool, raid_id, limit).await
+    }
+
+    /// Takes any executor so it can run inside a [`crate::db_persistence::UnitOfWork`].
+    pub async fn find_top_raiders_with<'e>(
+        executor: impl PgExecutor<'e>,
+        raid_id: i32,
+        limit: u32,
+    ) -> DbResult<Vec<RaiderScore>> {
+        let query = format!(
+            r#"
+        SELECT s.raider_id, SUM({score})::DOUBLE PRECISION AS score
+        FROM raid_submissions s
+        JOIN raid_quests rq ON rq.id = s.raid_id
+        JOIN addresses a ON a.quan_address = s.raider_id AND a.deleted_at IS NULL
+        WHERE s.raid_id = $1 AND s.is_invalid = false AND {eligible}
+        GROUP BY s.raider_id
+        HAVING SUM({score}) > 0
+        ORDER BY score DESC, s.raider_id ASC
+        LIMIT $2
+        "#,
+            score = SUBMISSION_SCORE_SQL,
+            eligible = SUBMISSION_ELIGIBLE_SQL,
+        );
+
+        let raiders = sqlx::query_as::<_, RaiderScore>(&query)
+            .bind(raid_id)
+            .bind(limit as i64)
+            .fetch_all(executor)
+            .await?;
+
+        Ok(raiders)
+    }
+
+    /// Records the payouts of a raid in place of its unpaid ones, so finishing a raid again splits the prize pool
+    /// anew instead of adding to the earlier split. Returns the number of payouts recorded.
+    pub async fn replace_unpaid_with(
+        conn: &mut PgConnection,
+        raid_id: i32,
+        shares: &[RaidPayoutShare],
+    ) -> DbResult<u64> {
+        Self::delete_unpaid_with(&mut *conn, raid_id).await?;
+        if shares.is_empty() {
+            return Ok(0);
+        }
+
+        let mut raider_ids = Vec::with_capacity(shares.len());
+        let mut ranks = Vec::with_capacity(shares.len());
+        let mut scores = Vec::with_capacity(shares.len());
+        let mut amounts = Vec::with_capacity(shares.len());
+
+        for share in shares {
+            raider_ids.push(share.raider_id.clone());
+            ranks.push(share.rank);
+            scores.push(share.score);
+            amounts.push(share.amount.clone());
+        }
+
+        let result = sqlx::query(
+            r#"
+        INSERT INTO raid_payouts (raid_id, raider_id, rank, score, amount)
+        SELECT $1, * FROM UNNEST($2::VARCHAR[], $3::INTEGER[], $4::DOUBLE PRECISION[], $5::VARCHAR[])
+        "#,
+        )
+        .bind(raid_id)
+        .bind(raider_ids)
+        .bind(ranks)
+        .bind(scores)
+        .bind(amounts)
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_unpaid_with<'e>(executor: impl PgExecutor<'e>, raid_id: i32) -> DbResult<u64> {
+        let result = sqlx::query("DELETE FROM raid_payouts WHERE raid_id = $1 AND paid_at IS NULL")
+            .bind(raid_id)
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn has_paid_with<'e>(executor: impl PgExecutor<'e>, raid_id: i32) -> DbResult<bool> {
+        let paid = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM raid_payouts WHERE raid_id = $1 AND paid_at IS NOT NULL)",
+        )
+        .bind(raid_id)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(paid)
+    }
+
+    pub async fn find_by_raid(&self, raid_id: i32) -> DbResult<Vec<RaidPayout>> {
+        let payouts =
+            sqlx::query_as::<_, RaidPayout>("SELECT * FROM raid_payouts WHERE raid_id = $1 ORDER BY rank ASC, id ASC")
+                .bind(raid_id)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(payouts)
+    }
+
+    /// Marks payouts of a raid as paid. Payouts that were already paid are left untouched. Returns the number
+    /// updated.
+    pub async fn mark_paid(&self, raid_id: i32, payout_ids: &[i64], paid_by: &str) -> DbResult<u64> {
+        let result = sqlx::query(
+            r#"
+        UPDATE raid_payouts SET paid_at = NOW(), paid_by = $3
+        WHERE raid_id = $1 AND id = ANY($2) AND paid_at IS NULL
+        "#,
+        )
+        .bind(raid_id)
+        .bind(payout_ids)
+        .bind(paid_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::raid_quest::CreateRaidQuest,
+        utils::{
+            test_app_state::create_test_app_state,
+            test_db::{create_persisted_address, SubmissionBuilder},
+        },
+    };
+
+    #[tokio::test]
+    async fn test_top_raiders_and_payouts_replaced_until_paid() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.raid_payouts;
+
+        let raid_id = state
+            .db
+            .raid_quests
+            .create(&CreateRaidQuest {
+                name: "Raid".to_string(),
+                scoring: Default::default(),
+            })
+            .await
+            .unwrap();
+        let a = create_persisted_address(&state.db.addresses, "a").await.quan_address.0;
+        let b = create_persisted_address(&state.db.addresses, "b").await.quan_address.0;
+        let idle = create_persisted_address(&state.db.addresses, "idle")
+            .await
+            .quan_address
+            .0;
+
+        SubmissionBuilder::new("s1", raid_id, &a)
+            .impressions(100)
+            .create(&state.db.pool)
+            .await;
+        SubmissionBuilder::new("s2", raid_id, &a)
+            .impressions(50)
+            .create(&state.db.pool)
+            .await;
+        SubmissionBuilder::new("s3", raid_id, &b)
+            .impressions(200)
+            .create(&state.db.pool)
+            .await;
+        SubmissionBuilder::new("s4", raid_id, &idle)
+            .create(&state.db.pool)
+            .await;
+
+        let top = repo.find_top_raiders(raid_id, 10).await.unwrap();
+        let ranked: Vec<(&str, f64)> = top.iter().map(|r| (r.raider_id.as_str(), r.score)).collect();
+        assert_eq!(ranked, vec![(b.as_str(), 200.0), (a.as_str(), 150.0)]);
+
+        let share = |rank: i32, raider_id: &str, amount: &str| RaidPayoutShare {
+            rank,
+            raider_id: raider_id.to_string(),
+            score: 0.0,
+            amount: amount.to_string(),
+        };
+        let mut conn = state.db.pool.acquire().await.unwrap();
+        let first = vec![share(1, &b, "1000")];
+        assert_eq!(
+            RaidPayoutRepository::replace_unpaid_with(&mut conn, raid_id, &first)
+                .await
+                .unwrap(),
+            1
+        );
+        // A second split replaces the first instead of adding to it
+        let second = vec![share(1, &a, "600"), share(2, &b, "400")];
+        assert_eq!(
+            RaidPayoutRepository::replace_unpaid_with(&mut conn, raid_id, &second)
+                .await
+                .unwrap(),
+            2
+        );
+
+        let payouts = repo.find_by_raid(raid_id).await.unwrap();
+        let amounts: Vec<&str> = payouts.iter().map(|p| p.amount.as_str()).collect();
+        assert_eq!(amounts, vec!["600", "400"]);
+
+        assert!(!RaidPayoutRepository::has_paid_with(&state.db.pool, raid_id)
+            .await
+            .unwrap());
+        assert_eq!(repo.mark_paid(raid_id, &[payouts[0].id], "admin").await.unwrap(), 1);
+        assert_eq!(repo.mark_paid(raid_id, &[payouts[0].id], "admin").await.unwrap(), 0);
+        assert!(RaidPayoutRepository::has_paid_with(&state.db.pool, raid_id)
+            .await
+            .unwrap());
+
+        assert_eq!(
+            RaidPayoutRepository::delete_unpaid_with(&state.db.pool, raid_id)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(repo.find_by_raid(raid_id).await.unwrap().len(), 1);
+    }
+}
@@ -1,16 +1,19 @@
 use chrono::Utc;
-use sqlx::{PgPool, Postgres, QueryBuilder};
+use sqlx::{PgExecutor, PgPool, Postgres, QueryBuilder};
 
 use crate::{
     db_persistence::DbError,
     handlers::ListQueryParams,
-    models::raid_quest::{CreateRaidQuest, RaidQuest, RaidQuestFilter, RaidQuestSortColumn},
+    models::raid_quest::{CreateRaidQuest, RaidQuest, RaidQuestFilter, RaidQuestSortColumn, RaidScoringRules},
     repositories::{calculate_page_offset, DbResult, QueryBuilderExt},
+    services::event_bus::{EventBus, LiveEvent, RaidQuestChange},
 };
 
 #[derive(Clone, Debug)]
 pub struct RaidQuestRepository {
     pool: PgPool,
+    read_pool: PgPool,
+    events: EventBus,
 }
 
 impl RaidQuestRepository {
@@ -46,8 +49,23 @@ impl RaidQuestRepository {
         }
     }
 
-    pub fn new(pool: &PgPool) -> Self {
-        Self { pool: pool.clone() }
+    pub fn new(pool: &PgPool, events: &EventBus) -> Self {
+        Self {
+            pool: pool.clone(),
+            read_pool: pool.clone(),
+            events: events.clone(),
+        }
+    }
+
+    /// Serves the raid lists from `read_pool`, e.g. a read replica.
+    pub fn with_read_pool(mut self, read_pool: &PgPool) -> Self {
+        self.read_pool = read_pool.clone();
+        self
+    }
+
+    /// Also called for changes written through a [`crate::db_persistence::UnitOfWork`], once it is committed.
+    pub fn publish(&self, raid_id: i32, change: RaidQuestChange) {
+        self.events.publish(LiveEvent::RaidQuestChanged { raid_id, change });
     }
 
     pub async fn find_all(
@@ -84,7 +102,7 @@ impl RaidQuestRepository {
 
         let tweets = query_builder
             .build_query_as::<RaidQuest>()
-            .fetch_all(&self.pool)
+            .fetch_all(&self.read_pool)
             .await
             .map_err(DbError::Database)?;
 
@@ -94,34 +112,49 @@ impl RaidQuestRepository {
     pub async fn create(&self, new_quest: &CreateRaidQuest) -> DbResult<i32> {
         let start_date = Utc::now();
 
-        let result = sqlx::query_scalar::<_, i32>(
+        let id = sqlx::query_scalar::<_, i32>(
             "
-            INSERT INTO raid_quests (name, start_date) 
-            VALUES ($1, $2)
+            INSERT INTO raid_quests (
+                name, start_date, impression_weight, like_weight, reply_weight, retweet_weight,
+                max_submission_score, min_follower_count
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             RETURNING id
             ",
         )
         .bind(&new_quest.name)
         .bind(start_date)
+        .bind(new_quest.scoring.impression_weight)
+        .bind(new_quest.scoring.like_weight)
+        .bind(new_quest.scoring.reply_weight)
+        .bind(new_quest.scoring.retweet_weight)
+        .bind(new_quest.scoring.max_submission_score)
+        .bind(new_quest.scoring.min_follower_count)
         .fetch_one(&self.pool)
-        .await;
-
-        match result {
-            Ok(id) => Ok(id),
-            Err(sqlx::Error::Database(db_err)) => {
-                // Check specifically for Exclusion Violation (Postgres Code 23P01)
-                if let Some(code) = db_err.code() {
-                    if code == "23P01" {
-                        return Err(DbError::UniqueViolation(
-                            "Cannot create raid: Another raid is currently active or overlaps with this time range."
-                                .to_string(),
-                        ));
-                    }
-                }
-                Err(DbError::Database(sqlx::Error::Database(db_err)))
-            }
-            Err(e) => Err(DbError::Database(e)),
-        }
+        .await?;
+        self.publish(id, RaidQuestChange::Created);
+
+        Ok(id)
+    }
+
+    pub async fn find_by_id(&self, id: i32) -> DbResult<Option<RaidQuest>> {
+        let quest = sqlx::query_as::<_, RaidQuest>("SELECT * FROM raid_quests WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(quest)
+    }
+
+    /// All raids that have started and are not finished yet, oldest first.
+    pub async fn find_all_active(&self) -> DbResult<Vec<RaidQuest>> {
+        let quests = sqlx::query_as::<_, RaidQuest>(
+            "SELECT * FROM raid_quests WHERE start_date <= NOW() AND end_date IS NULL ORDER BY start_date ASC, id ASC",
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(quests)
     }
 
     pub async fn delete_by_id(&self, id: i32) -> DbResult<Option<RaidQuest>> {
@@ -130,16 +163,27 @@ impl RaidQuestRepository {
         qb.push_bind(id);
         qb.push(" RETURNING *");
 
-        let quest = qb.build_query_as().fetch_optional(&self.pool).await?;
+        let quest: Option<RaidQuest> = qb.build_query_as().fetch_optional(&self.pool).await?;
+        if quest.is_some() {
+            self.publish(id, RaidQuestChange::Deleted);
+        }
 
         Ok(quest)
     }
 
     pub async fn finish(&self, id: i32) -> DbResult<()> {
+        Self::finish_with(&self.pool, id).await?;
+        self.publish(id, RaidQuestChange::Finished);
+
+        Ok(())
+    }
+
+    /// Takes any executor so it can run inside a [`crate::db_persistence::UnitOfWork`]. Doesn't publish.
+    pub async fn finish_with<'e>(executor: impl PgExecutor<'e>, id: i32) -> DbResult<()> {
         let result = sqlx::query("UPDATE raid_quests SET end_date = $1 WHERE id = $2")
             .bind(Utc::now())
             .bind(id)
-            .execute(&self.pool)
+            .execute(executor)
             .await?;
 
         if result.rows_affected() == 0 {
@@ -150,33 +194,52 @@ impl RaidQuestRepository {
     }
 
     pub async fn make_active(&self, id: i32) -> DbResult<()> {
+        Self::make_active_with(&self.pool, id).await?;
+        self.publish(id, RaidQuestChange::Reactivated);
+
+        Ok(())
+    }
+
+    /// Takes any executor so it can run inside a [`crate::db_persistence::UnitOfWork`]. Doesn't publish.
+    pub async fn make_active_with<'e>(executor: impl PgExecutor<'e>, id: i32) -> DbResult<()> {
         let result = sqlx::query("UPDATE raid_quests SET end_date = NULL WHERE id = $1")
             .bind(id)
-            .execute(&self.pool)
-            .await;
+            .execute(executor)
+            .await?;
 
-        match result {
-            Ok(result) => {
-                if result.rows_affected() == 0 {
-                    return Err(DbError::RecordNotFound(format!("Raid Quest {} not found", id)));
-                }
+        if result.rows_affected() == 0 {
+            return Err(DbError::RecordNotFound(format!("Raid Quest {} not found", id)));
+        }
 
-                Ok(())
-            }
-            Err(sqlx::Error::Database(db_err)) => {
-                // Check specifically for Exclusion Violation (Postgres Code 23P01)
-                if let Some(code) = db_err.code() {
-                    if code == "23P01" {
-                        return Err(DbError::UniqueViolation(
-                            "Cannot revert active raid: Another raid is currently active or overlaps with this time range."
-                                .to_string(),
-                        ));
-                    }
-                }
-                Err(DbError::Database(sqlx::Error::Database(db_err)))
-            }
-            Err(e) => Err(DbError::Database(e)),
+        Ok(())
+    }
+
+    pub async fn update_scoring(&self, id: i32, scoring: &RaidScoringRules) -> DbResult<()> {
+        let result = sqlx::query(
+            "
+            UPDATE raid_quests
+            SET impression_weight = $1, like_weight = $2, reply_weight = $3, retweet_weight = $4,
+                max_submission_score = $5, min_follower_count = $6
+            WHERE id = $7
+            ",
+        )
+        .bind(scoring.impression_weight)
+        .bind(scoring.like_weight)
+        .bind(scoring.reply_weight)
+        .bind(scoring.retweet_weight)
+        .bind(scoring.max_submission_score)
+        .bind(scoring.min_follower_count)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::RecordNotFound(format!("Raid Quest {} not found", id)));
         }
+
+        self.publish(id, RaidQuestChange::ScoringChanged);
+
+        Ok(())
     }
 
     pub async fn count_filtered(
@@ -190,7 +253,7 @@ impl RaidQuestRepository {
 
         let count = query_builder
             .build_query_scalar()
-            .fetch_one(&self.pool)
+            .fetch_one(&self.read_pool)
             .await
             .map_err(DbError::Database)?;
 
@@ -202,7 +265,7 @@ impl RaidQuestRepository {
         QueryBuilder::new("SELECT * FROM raid_quests")
     }
 
-    /// Finds the single currently active raid quest (test helper).
+    /// Finds the most recently started active raid quest (test helper).
     #[cfg(test)]
     pub async fn find_active(&self) -> DbResult<Option<RaidQuest>> {
         let mut qb = Self::create_select_base_query();
@@ -223,8 +286,7 @@ impl RaidQuestRepository {
 mod tests {
     use super::*;
     use crate::config::Config;
-    use crate::utils::test_db::reset_database;
-    use sqlx::PgPool;
+    use crate::utils::test_db::isolated_pool;
 
     // -------------------------------------------------------------------------
     // Setup & Helpers
@@ -232,18 +294,16 @@ mod tests {
 
     async fn setup_test_repository() -> RaidQuestRepository {
         let config = Config::load_test_env().expect("Failed to load configuration for tests");
-        let pool = PgPool::connect(config.get_database_url())
-            .await
-            .expect("Failed to create pool.");
-
-        // Clean database before each test
-        reset_database(&pool).await;
+        let pool = isolated_pool(config.get_database_url()).await;
 
-        RaidQuestRepository::new(&pool)
+        RaidQuestRepository::new(&pool, &EventBus::new())
     }
 
     fn create_mock_quest_input(name: &str) -> CreateRaidQuest {
-        CreateRaidQuest { name: name.to_string() }
+        CreateRaidQuest {
+            name: name.to_string(),
+            scoring: Default::default(),
+        }
     }
 
     // -------------------------------------------------------------------------
@@ -260,32 +320,26 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_create_active_constraint_violation() {
+    async fn test_create_concurrent_active_raids() {
         let repo = setup_test_repository().await;
 
-        // 1. Create first active raid (Start: NOW, End: NULL)
-        let input1 = create_mock_quest_input("Raid One");
-        repo.create(&input1).await.expect("First raid should succeed");
+        // 1. Create two raids running at the same time (Start: NOW, End: NULL)
+        let id_us = repo.create(&create_mock_quest_input("Raid US")).await.unwrap();
+        let id_eu = repo.create(&create_mock_quest_input("Raid EU")).await.unwrap();
 
-        // 2. Attempt to create second active raid immediately
-        let input2 = create_mock_quest_input("Raid Two");
-        let repo_err = repo.create(&input2).await.unwrap_err();
-        match repo_err {
-            DbError::UniqueViolation(_) => {}
-            err => panic!("Expected UniqueViolation, got {:?}", err),
-        }
+        // 2. Both are reported as active
+        let active = repo.find_all_active().await.unwrap();
+        let active_ids: Vec<i32> = active.iter().map(|q| q.id).collect();
+        assert_eq!(active_ids, vec![id_us, id_eu]);
 
-        let db_err =
-            sqlx::query_scalar::<_, i32>("INSERT INTO raid_quests (name, start_date) VALUES ($1, $2) RETURNING id")
-                .bind("Raid Three")
-                .bind(Utc::now())
-                .fetch_one(&repo.pool)
-                .await
-                .unwrap_err();
-        match db_err {
-            sqlx::Error::Database(db_err) => assert_eq!(db_err.code().as_deref(), Some("23P01")),
-            err => panic!("Expected exclusion violation (23P01), got {:?}", err),
-        }
+        // 3. Finishing one leaves the other running, and it can be reactivated alongside it
+        repo.finish(id_us).await.unwrap();
+        let active = repo.find_all_active().await.unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].id, id_eu);
+
+        repo.make_active(id_us).await.unwrap();
+        assert_eq!(repo.find_all_active().await.unwrap().len(), 2);
     }
 
     #[tokio::test]
@@ -0,0 +1,420 @@
+use sqlx::{PgPool, QueryBuilder};
+
+use crate::{
+    db_persistence::DbError,
+    models::{
+        raid_quest::SUBMISSION_ELIGIBLE_SQL,
+        raid_team::{RaidTeam, TeamLeaderboardEntry, TeamScoreNormalization},
+    },
+    repositories::DbResult,
+    services::event_bus::{EventBus, LiveEvent},
+};
+
+const TEAM_SELECT: &str = r#"
+    SELECT t.*, COUNT(m.quan_address) AS member_count
+    FROM raid_teams t
+    LEFT JOIN raid_team_members m ON m.team_id = t.id
+"#;
+
+#[derive(Clone, Debug)]
+pub struct RaidTeamRepository {
+    pool: PgPool,
+    read_pool: PgPool,
+    events: EventBus,
+}
+
+impl RaidTeamRepository {
+    pub fn new(pool: &PgPool, events: &EventBus) -> Self {
+        Self {
+            pool: pool.clone(),
+            read_pool: pool.clone(),
+            events: events.clone(),
+        }
+    }
+
+    /// Serves team lists and leaderboards from `read_pool`, e.g. a read replica.
+    pub fn with_read_pool(mut self, read_pool: &PgPool) -> Self {
+        self.read_pool = read_pool.clone();
+        self
+    }
+
+    /// Maps constraint violations on team tables to readable errors.
+    fn map_team_error(err: sqlx::Error) -> DbError {
+        if let sqlx::Error::Database(db_err) = &err {
+            match (db_err.code().as_deref(), db_err.constraint()) {
+                (Some("23505"), Some("raid_team_members_pkey")) => {
+                    return DbError::UniqueViolation("Address already belongs to a team in this raid".to_string())
+                }
+                (Some("23505"), Some("idx_raid_teams_raid_name")) => {
+                    return DbError::UniqueViolation("A team with this name already exists in this raid".to_string())
+                }
+                (Some("23503"), _) => return DbError::RecordNotFound("Raid team not found".to_string()),
+                _ => {}
+            }
+        }
+
+        DbError::Database(err)
+    }
+
+    /// Creates a team and adds its creator as the first member.
+    pub async fn create(&self, raid_id: i32, name: &str, created_by: &str) -> DbResult<RaidTeam> {
+        let mut tx = self.pool.begin().await?;
+
+        let team_id = sqlx::query_scalar::<_, i32>(
+            "INSERT INTO raid_teams (raid_id, name, created_by) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(raid_id)
+        .bind(name)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_team_error)?;
+
+        sqlx::query("INSERT INTO raid_team_members (raid_id, team_id, quan_address) VALUES ($1, $2, $3)")
+            .bind(raid_id)
+            .bind(team_id)
+            .bind(created_by)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_team_error)?;
+
+        tx.commit().await?;
+        self.events.publish(LiveEvent::LeaderboardChanged { raid_id });
+
+        self.find_by_id(raid_id, team_id)
+            .await?
+            .ok_or_else(|| DbError::RecordNotFound(format!("Raid team {} not found", team_id)))
+    }
+
+    pub async fn join(&self, raid_id: i32, team_id: i32, quan_address: &str) -> DbResult<()> {
+        sqlx::query("INSERT INTO raid_team_members (raid_id, team_id, quan_address) VALUES ($1, $2, $3)")
+            .bind(raid_id)
+            .bind(team_id)
+            .bind(quan_address)
+            .execute(&self.pool)
+            .await
+            .map_err(Self::map_team_error)?;
+        self.events.publish(LiveEvent::LeaderboardChanged { raid_id });
+
+        Ok(())
+    }
+
+    pub async fn leave(&self, raid_id: i32, quan_address: &str) -> DbResult<()> {
+        let result = sqlx::query("DELETE FROM raid_team_members WHERE raid_id = $1 AND quan_address = $2")
+            .bind(raid_id)
+            .bind(quan_address)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::RecordNotFound(
+                "Address is not a member of a team in this raid".to_string(),
+            ));
+        }
+        self.events.publish(LiveEvent::LeaderboardChanged { raid_id });
+
+        Ok(())
+    }
+
+    /// Tells leaderboard readers that a team member changed outside the team tables, e.g. was banned or unbanned.
+    /// Publishes a change for every raid the address is in a team of.
+    pub async fn notify_member_changed(&self, quan_address: &str) -> DbResult<()> {
+        let raid_ids = sqlx::query_scalar::<_, i32>("SELECT raid_id FROM raid_team_members WHERE quan_address = $1")
+            .bind(quan_address)
+            .fetch_all(&self.pool)
+            .await?;
+
+        for raid_id in raid_ids {
+            self.events.publish(LiveEvent::LeaderboardChanged { raid_id });
+        }
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, raid_id: i32, team_id: i32) -> DbResult<Option<RaidTeam>> {
+        let mut qb = QueryBuilder::new(TEAM_SELECT);
+        qb.push(" WHERE t.raid_id = ");
+        qb.push_bind(raid_id);
+        qb.push(" AND t.id = ");
+        qb.push_bind(team_id);
+        qb.push(" GROUP BY t.id");
+
+        let team = qb.build_query_as::<RaidTeam>().fetch_optional(&self.pool).await?;
+
+        Ok(team)
+    }
+
+    pub async fn find_all_by_raid(&self, raid_id: i32) -> DbResult<Vec<RaidTeam>> {
+        let mut qb = QueryBuilder::new(TEAM_SELECT);
+        qb.push(" WHERE t.raid_id = ");
+        qb.push_bind(raid_id);
+        qb.push(" GROUP BY t.id ORDER BY t.name ASC");
+
+        let teams = qb.build_query_as::<RaidTeam>().fetch_all(&self.read_pool).await?;
+
+        Ok(teams)
+    }
+
+    /// Ranks the teams of a raid by the (optionally size normalized) scores of their members' valid
+    /// submissions, weighted by the raid's scoring rules. Banned members and members below the raid's
+    /// follower threshold don't count.
+    pub async fn find_leaderboard(
+        &self,
+        raid_id: i32,
+        normalization: TeamScoreNormalization,
+        limit: u32,
+    ) -> DbResult<Vec<TeamLeaderboardEntry>> {
+        let score = normalization.to_sql_expr();
+
+        let mut qb = QueryBuilder::new("SELECT RANK() OVER (ORDER BY ");
+        qb.push(&score);
+        qb.push(
+            r#" DESC) AS rank,
+                t.id AS team_id,
+                t.name,
+                COUNT(DISTINCT m.quan_address) AS member_count,
+                COUNT(s.id) AS total_submissions,
+                COALESCE(SUM(s.impression_count), 0)::BIGINT AS total_impressions,
+                COALESCE(SUM(s.reply_count), 0)::BIGINT AS total_replies,
+                COALESCE(SUM(s.retweet_count), 0)::BIGINT AS total_retweets,
+                COALESCE(SUM(s.like_count), 0)::BIGINT AS total_likes,
+            "#,
+        );
+        qb.push(&score);
+        qb.push(
+            r#" AS score
+            FROM raid_teams t
+            JOIN raid_quests rq ON rq.id = t.raid_id
+            JOIN raid_team_members m ON m.team_id = t.id
+            JOIN addresses ma ON ma.quan_address = m.quan_address AND ma.deleted_at IS NULL
+            LEFT JOIN raid_submissions s
+                ON s.raid_id = t.raid_id AND s.raider_id = m.quan_address AND s.is_invalid = false
+                AND "#,
+        );
+        qb.push(SUBMISSION_ELIGIBLE_SQL);
+        qb.push(" WHERE t.raid_id = ");
+        qb.push_bind(raid_id);
+        qb.push(" GROUP BY t.id, t.name ORDER BY rank ASC, t.id ASC LIMIT ");
+        qb.push_bind(limit as i64);
+
+        let entries = qb
+            .build_query_as::<TeamLeaderboardEntry>()
+            .fetch_all(&self.read_pool)
+            .await?;
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::raid_quest::{CreateRaidQuest, RaidScoringRules},
+        utils::{
+            test_app_state::create_test_app_state,
+            test_db::{create_persisted_address, SubmissionBuilder},
+        },
+    };
+
+    #[tokio::test]
+    async fn test_membership_is_unique_per_raid() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.raid_teams;
+
+        let raid_id = state
+            .db
+            .raid_quests
+            .create(&CreateRaidQuest {
+                name: "Raid".to_string(),
+                scoring: Default::default(),
+            })
+            .await
+            .unwrap();
+        let alice = create_persisted_address(&state.db.addresses, "alice").await;
+        let bob = create_persisted_address(&state.db.addresses, "bob").await;
+
+        let team_a = repo.create(raid_id, "Team A", &alice.quan_address.0).await.unwrap();
+        assert_eq!(team_a.member_count, 1);
+
+        let err = repo.create(raid_id, "team a", &bob.quan_address.0).await.unwrap_err();
+        assert!(matches!(err, DbError::UniqueViolation(_)));
+
+        let err = repo.create(raid_id, "Team B", &alice.quan_address.0).await.unwrap_err();
+        assert!(matches!(err, DbError::UniqueViolation(_)));
+
+        let err = repo.join(raid_id, 9999, &bob.quan_address.0).await.unwrap_err();
+        assert!(matches!(err, DbError::RecordNotFound(_)));
+
+        repo.join(raid_id, team_a.id, &bob.quan_address.0).await.unwrap();
+        let err = repo.join(raid_id, team_a.id, &bob.quan_address.0).await.unwrap_err();
+        assert!(matches!(err, DbError::UniqueViolation(_)));
+
+        repo.leave(raid_id, &bob.quan_address.0).await.unwrap();
+        let teams = repo.find_all_by_raid(raid_id).await.unwrap();
+        assert_eq!(teams.len(), 1);
+        assert_eq!(teams[0].member_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_member_changes_reach_their_raids() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.raid_teams;
+
+        let raid_id = state
+            .db
+            .raid_quests
+            .create(&CreateRaidQuest {
+                name: "Raid".to_string(),
+                scoring: Default::default(),
+            })
+            .await
+            .unwrap();
+        let alice = create_persisted_address(&state.db.addresses, "alice")
+            .await
+            .quan_address
+            .0;
+        repo.create(raid_id, "Team A", &alice).await.unwrap();
+
+        let mut events = state.db.events.subscribe();
+        repo.notify_member_changed(&alice).await.unwrap();
+        assert_eq!(events.try_recv().unwrap(), LiveEvent::LeaderboardChanged { raid_id });
+
+        // Addresses without a team don't touch any leaderboard
+        repo.notify_member_changed("no_team").await.unwrap();
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_leaderboard_normalization() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.raid_teams;
+
+        let raid_id = state
+            .db
+            .raid_quests
+            .create(&CreateRaidQuest {
+                name: "Raid".to_string(),
+                scoring: Default::default(),
+            })
+            .await
+            .unwrap();
+        let a1 = create_persisted_address(&state.db.addresses, "a1").await.quan_address.0;
+        let a2 = create_persisted_address(&state.db.addresses, "a2").await.quan_address.0;
+        let a3 = create_persisted_address(&state.db.addresses, "a3").await.quan_address.0;
+        let b1 = create_persisted_address(&state.db.addresses, "b1").await.quan_address.0;
+
+        // Big team: 3 members, 300 impressions. Small team: 1 member, 200 impressions.
+        let big = repo.create(raid_id, "Big", &a1).await.unwrap();
+        repo.join(raid_id, big.id, &a2).await.unwrap();
+        repo.join(raid_id, big.id, &a3).await.unwrap();
+        let small = repo.create(raid_id, "Small", &b1).await.unwrap();
+
+        SubmissionBuilder::new("s1", raid_id, &a1)
+            .impressions(100)
+            .create(&state.db.pool)
+            .await;
+        SubmissionBuilder::new("s2", raid_id, &a2)
+            .impressions(100)
+            .create(&state.db.pool)
+            .await;
+        SubmissionBuilder::new("s3", raid_id, &a3)
+            .impressions(100)
+            .create(&state.db.pool)
+            .await;
+        SubmissionBuilder::new("s4", raid_id, &b1)
+            .impressions(200)
+            .create(&state.db.pool)
+            .await;
+
+        let raw = repo
+            .find_leaderboard(raid_id, TeamScoreNormalization::None, 10)
+            .await
+            .unwrap();
+        assert_eq!(raw[0].team_id, big.id);
+        assert_eq!(raw[0].total_impressions, 300);
+        assert_eq!(raw[0].member_count, 3);
+        assert_eq!(raw[0].rank, 1);
+
+        let per_member = repo
+            .find_leaderboard(raid_id, TeamScoreNormalization::PerMember, 10)
+            .await
+            .unwrap();
+        assert_eq!(per_member[0].team_id, small.id);
+        assert_eq!(per_member[0].score, 200.0);
+        assert_eq!(per_member[1].score, 100.0);
+
+        let sqrt = repo
+            .find_leaderboard(raid_id, TeamScoreNormalization::SqrtMembers, 10)
+            .await
+            .unwrap();
+        assert_eq!(sqrt[0].team_id, small.id);
+    }
+
+    #[tokio::test]
+    async fn test_leaderboard_uses_raid_scoring_rules() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.raid_teams;
+
+        let raid_id = state
+            .db
+            .raid_quests
+            .create(&CreateRaidQuest {
+                name: "Raid".to_string(),
+                scoring: RaidScoringRules {
+                    like_weight: 5.0,
+                    max_submission_score: Some(120.0),
+                    ..Default::default()
+                },
+            })
+            .await
+            .unwrap();
+        let a = create_persisted_address(&state.db.addresses, "a").await.quan_address.0;
+        let b = create_persisted_address(&state.db.addresses, "b").await.quan_address.0;
+        let team_a = repo.create(raid_id, "A", &a).await.unwrap();
+        repo.create(raid_id, "B", &b).await.unwrap();
+
+        // A: 100 impressions + 10 likes = 150, capped at 120. B: 50 impressions + 10 likes = 100.
+        SubmissionBuilder::new("s1", raid_id, &a)
+            .impressions(100)
+            .create(&state.db.pool)
+            .await;
+        SubmissionBuilder::new("s2", raid_id, &b)
+            .impressions(50)
+            .create(&state.db.pool)
+            .await;
+        sqlx::query("UPDATE raid_submissions SET like_count = 10")
+            .execute(&state.db.pool)
+            .await
+            .unwrap();
+
+        let leaderboard = repo
+            .find_leaderboard(raid_id, TeamScoreNormalization::None, 10)
+            .await
+            .unwrap();
+        assert_eq!(leaderboard[0].team_id, team_a.id);
+        assert_eq!(leaderboard[0].score, 120.0);
+        assert_eq!(leaderboard[1].score, 100.0);
+
+        // Without a matching X account no raider reaches a follower threshold
+        state
+            .db
+            .raid_quests
+            .update_scoring(
+                raid_id,
+                &RaidScoringRules {
+                    min_follower_count: 10,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let leaderboard = repo
+            .find_leaderboard(raid_id, TeamScoreNormalization::None, 10)
+            .await
+            .unwrap();
+        assert!(leaderboard
+            .iter()
+            .all(|entry| entry.score == 0.0 && entry.total_submissions == 0));
+    }
+}
@@ -1,4 +1,4 @@
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 
 use crate::{models::referrals::Referral, repositories::DbResult};
 
@@ -11,7 +11,14 @@ impl ReferralRepository {
         Self { pool: pool.clone() }
     }
 
+    /// Pool-based form of [`Self::create_with`], not used by the main server binary.
+    #[allow(dead_code)]
     pub async fn create(&self, new_referral: &Referral) -> DbResult<i32> {
+        Self::create_with(&self.pool, new_referral).await
+    }
+
+    /// Takes any executor so it can run inside a [`crate::db_persistence::UnitOfWork`].
+    pub async fn create_with<'e>(executor: impl PgExecutor<'e>, new_referral: &Referral) -> DbResult<i32> {
         let created_id = sqlx::query_scalar::<_, i32>(
             "
         INSERT INTO referrals (referrer_address, referee_address) 
@@ -21,7 +28,7 @@ impl ReferralRepository {
         )
         .bind(new_referral.referrer_address.0.clone())
         .bind(new_referral.referee_address.0.clone())
-        .fetch_one(&self.pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(created_id)
@@ -54,19 +61,13 @@ mod tests {
         config::Config,
         models::referrals::{Referral, ReferralData},
         repositories::address::AddressRepository,
-        utils::test_db::{create_persisted_address, reset_database},
+        utils::test_db::{create_persisted_address, isolated_pool},
     };
-    use sqlx::PgPool;
 
     // Helper function to set up test repositories.
-    // Cleans both tables to ensure a fresh state and handle foreign keys.
     async fn setup_test_repositories() -> (AddressRepository, ReferralRepository) {
         let config = Config::load_test_env().expect("Failed to load configuration for tests");
-        let pool = PgPool::connect(config.get_database_url())
-            .await
-            .expect("Failed to create pool.");
-
-        reset_database(&pool).await;
+        let pool = isolated_pool(config.get_database_url()).await;
 
         (AddressRepository::new(&pool), ReferralRepository::new(&pool))
     }
@@ -184,4 +185,43 @@ mod tests {
 
         assert!(results.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_unit_of_work_rolls_back_on_drop() {
+        let state = crate::utils::test_app_state::create_test_app_state().await;
+
+        let referrer = create_persisted_address(&state.db.addresses, "referrer_uow").await;
+        let referee = create_persisted_address(&state.db.addresses, "referee_uow").await;
+        let referral = Referral::new(ReferralData {
+            referrer_address: referrer.quan_address.0.clone(),
+            referee_address: referee.quan_address.0.clone(),
+        })
+        .unwrap();
+
+        {
+            let mut uow = state.db.begin().await.unwrap();
+            ReferralRepository::create_with(uow.conn(), &referral).await.unwrap();
+            AddressRepository::increment_referrals_count_with(uow.conn(), &referrer.quan_address.0)
+                .await
+                .unwrap();
+            // Dropped without commit, e.g. because a later step failed.
+        }
+
+        let found = state
+            .db
+            .referrals
+            .find_by_referee(referee.quan_address.0.clone())
+            .await
+            .unwrap();
+        assert!(found.is_none());
+
+        let referrer = state
+            .db
+            .addresses
+            .find_by_id(&referrer.quan_address.0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(referrer.referrals_count, 0);
+    }
 }
@@ -0,0 +1,150 @@
+use sqlx::PgPool;
+
+use crate::{
+    models::referral_reward::{ReferralPayout, ReferralReward},
+    repositories::DbResult,
+};
+
+#[derive(Clone, Debug)]
+pub struct ReferralRewardRepository {
+    pool: PgPool,
+}
+
+impl ReferralRewardRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Grants `amount` to the referrer of every referee with at least `min_transfers` outgoing transfers among
+    /// the synced ones. Referees that were already rewarded or score above `max_sybil_score` are skipped, and
+    /// banned addresses neither earn nor count.
+    /// Returns the number of new rewards.
+    pub async fn grant_for_active_referees(
+        &self,
+        min_transfers: i64,
+        amount: &str,
+        max_sybil_score: i16,
+    ) -> DbResult<u64> {
+        let result = sqlx::query(
+            r#"
+        INSERT INTO referral_rewards (referrer_address, referee_address, amount)
+        SELECT r.referrer_address, r.referee_address, $2
+        FROM referrals r
+        JOIN (
+            SELECT from_address
+            FROM processed_transfers
+            GROUP BY from_address
+            HAVING COUNT(*) >= $1
+        ) active ON active.from_address = r.referee_address
+        JOIN addresses referrer ON referrer.quan_address = r.referrer_address AND referrer.deleted_at IS NULL
+        JOIN addresses referee ON referee.quan_address = r.referee_address AND referee.deleted_at IS NULL
+        LEFT JOIN address_sybil_scores s ON s.quan_address = r.referee_address
+        WHERE COALESCE(s.score, 0) <= $3
+        ON CONFLICT (referee_address) DO NOTHING
+        "#,
+        )
+        .bind(min_transfers)
+        .bind(amount)
+        .bind(max_sybil_score)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_by_referrer(&self, referrer_address: &str) -> DbResult<Vec<ReferralReward>> {
+        let rewards = sqlx::query_as::<_, ReferralReward>(
+            "SELECT * FROM referral_rewards WHERE referrer_address = $1 ORDER BY created_at DESC, id DESC",
+        )
+        .bind(referrer_address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rewards)
+    }
+
+    /// Unpaid rewards grouped per referrer.
+    pub async fn find_unpaid_payouts(&self) -> DbResult<Vec<ReferralPayout>> {
+        let payouts = sqlx::query_as::<_, ReferralPayout>(
+            r#"
+        SELECT
+            referrer_address,
+            ARRAY_AGG(id ORDER BY id) AS reward_ids,
+            SUM(amount::NUMERIC)::TEXT AS total_amount
+        FROM referral_rewards
+        WHERE paid_at IS NULL
+        GROUP BY referrer_address
+        ORDER BY referrer_address
+        "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(payouts)
+    }
+
+    /// Marks rewards as paid. Rewards that were already paid are left untouched. Returns the number updated.
+    pub async fn mark_paid(&self, reward_ids: &[i64], paid_by: &str) -> DbResult<u64> {
+        let result = sqlx::query(
+            "UPDATE referral_rewards SET paid_at = NOW(), paid_by = $2 WHERE id = ANY($1) AND paid_at IS NULL",
+        )
+        .bind(reward_ids)
+        .bind(paid_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::referrals::{Referral, ReferralData},
+        utils::{
+            test_app_state::create_test_app_state,
+            test_db::{create_persisted_address, TransferBuilder},
+        },
+    };
+
+    #[tokio::test]
+    async fn test_rewards_granted_once_for_active_referees() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.referral_rewards;
+
+        let referrer = create_persisted_address(&state.db.addresses, "referrer").await;
+        let active = create_persisted_address(&state.db.addresses, "active").await;
+        let idle = create_persisted_address(&state.db.addresses, "idle").await;
+        for referee in [&active, &idle] {
+            let referral = Referral::new(ReferralData {
+                referrer_address: referrer.quan_address.0.clone(),
+                referee_address: referee.quan_address.0.clone(),
+            })
+            .unwrap();
+            state.db.referrals.create(&referral).await.unwrap();
+        }
+
+        TransferBuilder::new("t1", &active.quan_address.0, &idle.quan_address.0)
+            .create(&state.db.pool)
+            .await;
+        TransferBuilder::new("t2", &active.quan_address.0, &idle.quan_address.0)
+            .create(&state.db.pool)
+            .await;
+
+        assert_eq!(repo.grant_for_active_referees(2, "500", 100).await.unwrap(), 1);
+        assert_eq!(repo.grant_for_active_referees(2, "500", 100).await.unwrap(), 0);
+
+        let rewards = repo.find_by_referrer(&referrer.quan_address.0).await.unwrap();
+        assert_eq!(rewards.len(), 1);
+        assert_eq!(rewards[0].referee_address, active.quan_address.0);
+
+        let payouts = repo.find_unpaid_payouts().await.unwrap();
+        assert_eq!(payouts.len(), 1);
+        assert_eq!(payouts[0].total_amount, "500");
+
+        assert_eq!(repo.mark_paid(&payouts[0].reward_ids, "admin").await.unwrap(), 1);
+        assert_eq!(repo.mark_paid(&payouts[0].reward_ids, "admin").await.unwrap(), 0);
+        assert!(repo.find_unpaid_payouts().await.unwrap().is_empty());
+    }
+}
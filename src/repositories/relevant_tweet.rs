@@ -4,17 +4,27 @@ use crate::{
     db_persistence::DbError,
     handlers::ListQueryParams,
     models::relevant_tweet::{RelevantTweet, TweetFilter, TweetSortColumn, TweetWithAuthor},
-    repositories::{calculate_page_offset, DbResult, QueryBuilderExt},
+    repositories::{calculate_page_offset, prefix_tsquery, DbResult, QueryBuilderExt},
 };
 
 #[derive(Clone, Debug)]
 pub struct RelevantTweetRepository {
     pool: PgPool,
+    read_pool: PgPool,
 }
 
 impl RelevantTweetRepository {
     pub fn new(pool: &PgPool) -> Self {
-        Self { pool: pool.clone() }
+        Self {
+            pool: pool.clone(),
+            read_pool: pool.clone(),
+        }
+    }
+
+    /// Serves the tweet list from `read_pool`, e.g. a read replica.
+    pub fn with_read_pool(mut self, read_pool: &PgPool) -> Self {
+        self.read_pool = read_pool.clone();
+        self
     }
 
     fn build_base_query_with_authors<'a>(
@@ -34,14 +44,14 @@ impl RelevantTweetRepository {
                 query_builder.push(" WHERE (");
                 where_started = true;
 
-                query_builder.push("text_fts @@ websearch_to_tsquery('english', ");
+                query_builder.push("rt.text_fts @@ websearch_to_tsquery('english', ");
                 query_builder.push_bind(s.clone());
                 query_builder.push(")");
 
-                // Allow searching by author username as well
-                query_builder.push(" OR ta.username ILIKE ");
-                query_builder.push_bind(format!("%{}%", s));
-                query_builder.push(") ");
+                // Allow searching by author username as well, including partial handles
+                query_builder.push(" OR ta.search_fts @@ to_tsquery('simple', ");
+                query_builder.push_bind(prefix_tsquery(s));
+                query_builder.push(")) ");
             }
         }
 
@@ -83,7 +93,7 @@ impl RelevantTweetRepository {
 
         let count = query_builder
             .build_query_scalar()
-            .fetch_one(&self.pool)
+            .fetch_one(&self.read_pool)
             .await
             .map_err(DbError::Database)?;
 
@@ -109,13 +119,22 @@ impl RelevantTweetRepository {
 
         self.build_base_query_with_authors(&mut query_builder, &params.search, filters);
 
-        // Sorting
+        // Sorting, searches without an explicit sort are ranked by relevance
         query_builder.push(" ORDER BY ");
-        let sort_col = params.sort_by.as_ref().unwrap_or(&TweetSortColumn::CreatedAt);
-        query_builder.push(sort_col.to_sql_column());
+        match (&params.sort_by, params.search.as_deref()) {
+            (None, Some(s)) if !s.is_empty() => {
+                query_builder.push("ts_rank(rt.text_fts, websearch_to_tsquery('english', ");
+                query_builder.push_bind(s.to_string());
+                query_builder.push(")) DESC");
+            }
+            (sort_by, _) => {
+                let sort_col = sort_by.as_ref().unwrap_or(&TweetSortColumn::CreatedAt);
+                query_builder.push(sort_col.to_sql_column());
 
-        query_builder.push(" ");
-        query_builder.push(params.order.to_string());
+                query_builder.push(" ");
+                query_builder.push(params.order.to_string());
+            }
+        }
 
         // Secondary sort for stability
         query_builder.push(", rt.id ASC");
@@ -129,7 +148,7 @@ impl RelevantTweetRepository {
 
         let tweets = query_builder
             .build_query_as::<TweetWithAuthor>()
-            .fetch_all(&self.pool)
+            .fetch_all(&self.read_pool)
             .await
             .map_err(DbError::Database)?;
 
@@ -219,21 +238,15 @@ mod tests {
     use crate::{
         models::{relevant_tweet::NewTweetPayload, tweet_author::NewAuthorPayload},
         repositories::tweet_author::TweetAuthorRepository,
-        utils::test_db::reset_database,
+        utils::test_db::isolated_pool,
         Config,
     };
     use chrono::Utc;
-    use sqlx::PgPool;
 
     // --- Helpers to create dummy data ---
     async fn setup_test_repository() -> (RelevantTweetRepository, TweetAuthorRepository) {
         let config = Config::load_test_env().expect("Failed to load configuration for tests");
-        let pool = PgPool::connect(config.get_database_url())
-            .await
-            .expect("Failed to create pool.");
-
-        // Clean database before each test
-        reset_database(&pool).await;
+        let pool = isolated_pool(config.get_database_url()).await;
 
         (RelevantTweetRepository::new(&pool), TweetAuthorRepository::new(&pool))
     }
@@ -305,4 +318,44 @@ mod tests {
             "Should update existing record on conflict"
         );
     }
+
+    #[tokio::test]
+    async fn test_search_ranks_by_relevance() {
+        let (repo, author_repo) = setup_test_repository().await;
+        seed_author(&author_repo, "author_1", "rust_dev").await;
+
+        repo.upsert_many(&[
+            create_payload("tweet_1", "author_1", "Quantum computers are coming"),
+            create_payload("tweet_2", "author_1", "Quantum safe wallets for the quantum era"),
+            create_payload("tweet_3", "author_1", "Nothing to see here"),
+        ])
+        .await
+        .unwrap();
+
+        let params = ListQueryParams {
+            page: 1,
+            page_size: 10,
+            search: Some("quantum".to_string()),
+            sort_by: None,
+            order: crate::handlers::SortDirection::Desc,
+        };
+        let filters = TweetFilter {
+            author_username: None,
+            min_likes: None,
+            min_impressions: None,
+            created_after: None,
+        };
+
+        let tweets = repo.find_all_with_authors(&params, &filters).await.unwrap();
+        let ids: Vec<_> = tweets.iter().map(|t| t.tweet.id.as_str()).collect();
+        assert_eq!(ids, vec!["tweet_2", "tweet_1"]);
+        assert_eq!(repo.count_filtered(&params, &filters).await.unwrap(), 2);
+
+        // Author handles are searchable too
+        let by_author = ListQueryParams {
+            search: Some("rust_dev".to_string()),
+            ..params
+        };
+        assert_eq!(repo.count_filtered(&by_author, &filters).await.unwrap(), 3);
+    }
 }
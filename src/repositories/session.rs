@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{models::session::Session, repositories::DbResult};
+
+#[derive(Clone, Debug)]
+pub struct SessionRepository {
+    pool: PgPool,
+}
+
+impl SessionRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create(
+        &self,
+        quan_address: &str,
+        refresh_token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> DbResult<Session> {
+        let session = sqlx::query_as::<_, Session>(
+            r#"
+        INSERT INTO sessions (id, quan_address, refresh_token_hash, expires_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *
+        "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(quan_address)
+        .bind(refresh_token_hash)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// Whether access tokens of the session are still accepted.
+    pub async fn is_active(&self, id: &Uuid) -> DbResult<bool> {
+        let active = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM sessions WHERE id = $1 AND revoked_at IS NULL AND expires_at > NOW())",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(active)
+    }
+
+    /// Swaps the refresh token of the active session holding `refresh_token_hash` and extends it. Returns
+    /// `None` when no active session holds the token, so each refresh token can only be used once.
+    pub async fn rotate(
+        &self,
+        refresh_token_hash: &str,
+        new_refresh_token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> DbResult<Option<Session>> {
+        let session = sqlx::query_as::<_, Session>(
+            r#"
+        UPDATE sessions
+        SET refresh_token_hash = $2, expires_at = $3, refreshed_at = NOW()
+        WHERE refresh_token_hash = $1 AND revoked_at IS NULL AND expires_at > NOW()
+        RETURNING *
+        "#,
+        )
+        .bind(refresh_token_hash)
+        .bind(new_refresh_token_hash)
+        .bind(expires_at)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    pub async fn revoke(&self, id: &Uuid) -> DbResult<bool> {
+        let result = sqlx::query("UPDATE sessions SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn revoke_all_for_address(&self, quan_address: &str) -> DbResult<u64> {
+        let result =
+            sqlx::query("UPDATE sessions SET revoked_at = NOW() WHERE quan_address = $1 AND revoked_at IS NULL")
+                .bind(quan_address)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Drops sessions that ended before `cutoff`, whether they expired or were revoked.
+    pub async fn delete_ended_before(&self, cutoff: DateTime<Utc>) -> DbResult<u64> {
+        let result = sqlx::query("DELETE FROM sessions WHERE expires_at < $1 OR revoked_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::create_persisted_address};
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_rotate_and_revoke_session() {
+        let state = create_test_app_state().await;
+        let address = create_persisted_address(&state.db.addresses, "session").await;
+        let sessions = &state.db.sessions;
+        let expires_at = Utc::now() + Duration::hours(1);
+
+        let session = sessions
+            .create(&address.quan_address.0, "hash-1", expires_at)
+            .await
+            .unwrap();
+        assert!(sessions.is_active(&session.id).await.unwrap());
+
+        let rotated = sessions.rotate("hash-1", "hash-2", expires_at).await.unwrap().unwrap();
+        assert_eq!(rotated.id, session.id);
+        assert!(rotated.refreshed_at.is_some());
+        // The old refresh token can't be used again
+        assert!(sessions.rotate("hash-1", "hash-3", expires_at).await.unwrap().is_none());
+
+        assert!(sessions.revoke(&session.id).await.unwrap());
+        assert!(!sessions.is_active(&session.id).await.unwrap());
+        assert!(sessions.rotate("hash-2", "hash-3", expires_at).await.unwrap().is_none());
+
+        let expired = sessions
+            .create(&address.quan_address.0, "hash-4", Utc::now() - Duration::minutes(1))
+            .await
+            .unwrap();
+        assert!(!sessions.is_active(&expired.id).await.unwrap());
+        assert_eq!(sessions.delete_ended_before(Utc::now()).await.unwrap(), 2);
+    }
+}
@@ -0,0 +1,219 @@
+use serde_json::Value;
+use sqlx::{types::Json, PgPool, Postgres, QueryBuilder};
+
+use crate::{
+    db_persistence::DbError,
+    handlers::ListQueryParams,
+    models::setting::{Setting, SettingAudit, SettingAuditFilter, SettingAuditSortColumn},
+    repositories::{calculate_page_offset, DbResult, QueryBuilderExt},
+};
+
+#[derive(Clone, Debug)]
+pub struct SettingRepository {
+    pool: PgPool,
+}
+
+impl SettingRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    fn build_audit_base_query<'a>(
+        &self,
+        query_builder: &mut QueryBuilder<'a, Postgres>,
+        search: &Option<String>,
+        filters: &SettingAuditFilter,
+    ) {
+        query_builder.push(" FROM settings_audit sa ");
+
+        let mut where_started = false;
+
+        if let Some(s) = search {
+            if !s.is_empty() {
+                query_builder.push_condition(" (sa.key ILIKE ", &mut where_started);
+                query_builder.push_bind(format!("%{}%", s));
+                query_builder.push(" OR sa.changed_by ILIKE ");
+                query_builder.push_bind(format!("%{}%", s));
+                query_builder.push(") ");
+            }
+        }
+
+        if let Some(key) = filters.key.clone() {
+            query_builder.push_condition(" sa.key = ", &mut where_started);
+            query_builder.push_bind(key);
+        }
+    }
+
+    pub async fn find_all(&self) -> DbResult<Vec<Setting>> {
+        let settings = sqlx::query_as::<_, Setting>("SELECT * FROM settings ORDER BY key")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(settings)
+    }
+
+    /// Stores an override and records the change in `settings_audit` in the same transaction.
+    pub async fn upsert(&self, key: &str, value: &Value, changed_by: &str) -> DbResult<Setting> {
+        let mut tx = self.pool.begin().await?;
+
+        let old_value = sqlx::query_scalar::<_, Json<Value>>("SELECT value FROM settings WHERE key = $1 FOR UPDATE")
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let setting = sqlx::query_as::<_, Setting>(
+            r#"
+        INSERT INTO settings (key, value, updated_by)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (key)
+        DO UPDATE SET value = EXCLUDED.value, updated_by = EXCLUDED.updated_by
+        RETURNING *
+        "#,
+        )
+        .bind(key)
+        .bind(Json(value))
+        .bind(changed_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("INSERT INTO settings_audit (key, old_value, new_value, changed_by) VALUES ($1, $2, $3, $4)")
+            .bind(key)
+            .bind(old_value)
+            .bind(Json(value))
+            .bind(changed_by)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(setting)
+    }
+
+    /// Removes an override, falling back to the file configuration. The removal is audited with a NULL
+    /// `new_value`.
+    pub async fn delete(&self, key: &str, changed_by: &str) -> DbResult<Setting> {
+        let mut tx = self.pool.begin().await?;
+
+        let setting = sqlx::query_as::<_, Setting>("DELETE FROM settings WHERE key = $1 RETURNING *")
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| DbError::RecordNotFound(format!("No override set for '{}'", key)))?;
+
+        sqlx::query("INSERT INTO settings_audit (key, old_value, new_value, changed_by) VALUES ($1, $2, NULL, $3)")
+            .bind(key)
+            .bind(Json(&setting.value))
+            .bind(changed_by)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(setting)
+    }
+
+    pub async fn count_audit_filtered(
+        &self,
+        params: &ListQueryParams<SettingAuditSortColumn>,
+        filters: &SettingAuditFilter,
+    ) -> Result<i64, DbError> {
+        let mut query_builder = QueryBuilder::new("SELECT COUNT(sa.id)");
+
+        self.build_audit_base_query(&mut query_builder, &params.search, filters);
+
+        let count = query_builder
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(DbError::Database)?;
+
+        Ok(count)
+    }
+
+    pub async fn find_audit(
+        &self,
+        params: &ListQueryParams<SettingAuditSortColumn>,
+        filters: &SettingAuditFilter,
+    ) -> Result<Vec<SettingAudit>, DbError> {
+        let mut query_builder = QueryBuilder::new("SELECT sa.*");
+
+        self.build_audit_base_query(&mut query_builder, &params.search, filters);
+
+        query_builder.push(" ORDER BY ");
+        let sort_col = params.sort_by.as_ref().unwrap_or(&SettingAuditSortColumn::CreatedAt);
+        query_builder.push(sort_col.to_sql_column());
+        query_builder.push(" ");
+        query_builder.push(params.order.to_string());
+        query_builder.push(", sa.id DESC");
+
+        let offset = calculate_page_offset(params.page, params.page_size);
+        query_builder.push(" LIMIT ");
+        query_builder.push_bind(params.page_size as i64);
+        query_builder.push(" OFFSET ");
+        query_builder.push_bind(offset as i64);
+
+        let entries = query_builder
+            .build_query_as::<SettingAudit>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(DbError::Database)?;
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::SortDirection;
+    use crate::utils::test_app_state::create_test_app_state;
+    use serde_json::json;
+
+    fn audit_params() -> ListQueryParams<SettingAuditSortColumn> {
+        ListQueryParams {
+            page: 1,
+            page_size: 10,
+            search: None,
+            sort_by: None,
+            order: SortDirection::Desc,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_and_delete_are_audited() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.settings;
+
+        repo.upsert("jwt.exp_in_hours", &json!(12), "admin_a").await.unwrap();
+        let updated = repo.upsert("jwt.exp_in_hours", &json!(6), "admin_b").await.unwrap();
+        assert_eq!(updated.value, json!(6));
+        assert_eq!(updated.updated_by, "admin_b");
+
+        let all = repo.find_all().await.unwrap();
+        assert_eq!(all.len(), 1);
+
+        repo.delete("jwt.exp_in_hours", "admin_a").await.unwrap();
+        assert!(repo.find_all().await.unwrap().is_empty());
+
+        let filters = SettingAuditFilter {
+            key: Some("jwt.exp_in_hours".to_string()),
+        };
+        assert_eq!(repo.count_audit_filtered(&audit_params(), &filters).await.unwrap(), 3);
+
+        let audit = repo.find_audit(&audit_params(), &filters).await.unwrap();
+        assert_eq!(audit[0].old_value, Some(json!(6)));
+        assert_eq!(audit[0].new_value, None);
+        assert_eq!(audit[1].old_value, Some(json!(12)));
+        assert_eq!(audit[1].new_value, Some(json!(6)));
+        assert_eq!(audit[2].old_value, None);
+        assert_eq!(audit[2].changed_by, "admin_a");
+    }
+
+    #[tokio::test]
+    async fn test_delete_missing_override() {
+        let state = create_test_app_state().await;
+
+        let err = state.db.settings.delete("jwt.exp_in_hours", "admin").await.unwrap_err();
+        assert!(matches!(err, DbError::RecordNotFound(_)));
+    }
+}
@@ -0,0 +1,186 @@
+use sqlx::PgPool;
+
+use crate::{models::sybil_score::SybilSignals, repositories::DbResult};
+
+#[derive(Clone, Debug)]
+pub struct SybilScoreRepository {
+    pool: PgPool,
+}
+
+impl SybilScoreRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Computes the transfer graph signals of every registered address from the synced transfers. The first
+    /// funder of an address is the sender of its earliest ingested incoming transfer.
+    pub async fn find_signals(&self, burst_window_minutes: i32) -> DbResult<Vec<SybilSignals>> {
+        let signals = sqlx::query_as::<_, SybilSignals>(
+            r#"
+        WITH first_funding AS (
+            SELECT DISTINCT ON (to_address) to_address AS quan_address, from_address AS funder
+            FROM processed_transfers
+            WHERE from_address <> to_address
+            ORDER BY to_address, processed_at, transfer_id
+        ),
+        clusters AS (
+            SELECT funder, COUNT(*) AS size
+            FROM first_funding
+            GROUP BY funder
+        ),
+        -- Registered addresses of the same funder registered within the window, counted in one pass per funder
+        bursts AS (
+            SELECT
+                f.quan_address,
+                COUNT(*) OVER (
+                    PARTITION BY f.funder
+                    ORDER BY a.created_at
+                    RANGE BETWEEN make_interval(mins => $1) PRECEDING AND make_interval(mins => $1) FOLLOWING
+                ) - 1 AS size
+            FROM first_funding f
+            JOIN addresses a ON a.quan_address = f.quan_address
+        ),
+        circular AS (
+            SELECT sent.from_address AS quan_address, COUNT(DISTINCT sent.to_address) AS counterparties
+            FROM processed_transfers sent
+            JOIN processed_transfers received
+                ON received.from_address = sent.to_address AND received.to_address = sent.from_address
+            WHERE sent.from_address <> sent.to_address
+            GROUP BY sent.from_address
+        )
+        SELECT
+            a.quan_address,
+            COALESCE(c.size, 0) AS funding_cluster_size,
+            COALESCE(b.size, 0) AS burst_size,
+            COALESCE(ci.counterparties, 0) AS circular_counterparties
+        FROM addresses a
+        LEFT JOIN first_funding f ON f.quan_address = a.quan_address
+        LEFT JOIN clusters c ON c.funder = f.funder
+        LEFT JOIN bursts b ON b.quan_address = a.quan_address
+        LEFT JOIN circular ci ON ci.quan_address = a.quan_address
+        "#,
+        )
+        .bind(burst_window_minutes)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(signals)
+    }
+
+    /// Stores the scores of `signals`, replacing earlier ones. Returns the number of rows written.
+    pub async fn upsert_many(&self, signals: &[SybilSignals]) -> DbResult<u64> {
+        if signals.is_empty() {
+            return Ok(0);
+        }
+
+        let mut addresses = Vec::with_capacity(signals.len());
+        let mut scores = Vec::with_capacity(signals.len());
+        let mut cluster_sizes = Vec::with_capacity(signals.len());
+        let mut burst_sizes = Vec::with_capacity(signals.len());
+        let mut circular = Vec::with_capacity(signals.len());
+
+        for signal in signals {
+            addresses.push(signal.quan_address.clone());
+            scores.push(signal.score());
+            cluster_sizes.push(signal.funding_cluster_size);
+            burst_sizes.push(signal.burst_size);
+            circular.push(signal.circular_counterparties);
+        }
+
+        let result = sqlx::query(
+            r#"
+        INSERT INTO address_sybil_scores
+            (quan_address, score, funding_cluster_size, burst_size, circular_counterparties)
+        SELECT * FROM UNNEST($1::VARCHAR[], $2::SMALLINT[], $3::BIGINT[], $4::BIGINT[], $5::BIGINT[])
+        ON CONFLICT (quan_address) DO UPDATE SET
+            score = EXCLUDED.score,
+            funding_cluster_size = EXCLUDED.funding_cluster_size,
+            burst_size = EXCLUDED.burst_size,
+            circular_counterparties = EXCLUDED.circular_counterparties,
+            computed_at = NOW()
+        "#,
+        )
+        .bind(&addresses)
+        .bind(&scores)
+        .bind(&cluster_sizes)
+        .bind(&burst_sizes)
+        .bind(&circular)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        test_app_state::create_test_app_state,
+        test_db::{create_persisted_address, TransferBuilder},
+    };
+
+    async fn stored_score(pool: &PgPool, quan_address: &str) -> i16 {
+        sqlx::query_scalar("SELECT score FROM address_sybil_scores WHERE quan_address = $1")
+            .bind(quan_address)
+            .fetch_one(pool)
+            .await
+            .expect("Failed to load sybil score")
+    }
+
+    #[tokio::test]
+    async fn test_signals_from_transfer_graph() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.sybil_scores;
+
+        let first = create_persisted_address(&state.db.addresses, "first").await;
+        let second = create_persisted_address(&state.db.addresses, "second").await;
+        let loner = create_persisted_address(&state.db.addresses, "loner").await;
+        let early = create_persisted_address(&state.db.addresses, "early").await;
+        let pool = &state.db.pool;
+        sqlx::query("UPDATE addresses SET created_at = NOW() - INTERVAL '2 hours' WHERE quan_address = $1")
+            .bind(&early.quan_address.0)
+            .execute(pool)
+            .await
+            .unwrap();
+
+        // One funder seeds both addresses, which then pass funds back and forth
+        TransferBuilder::new("t1", "funder", &first.quan_address.0)
+            .create(pool)
+            .await;
+        TransferBuilder::new("t2", "funder", &second.quan_address.0)
+            .create(pool)
+            .await;
+        TransferBuilder::new("t3", &first.quan_address.0, &second.quan_address.0)
+            .create(pool)
+            .await;
+        TransferBuilder::new("t4", &second.quan_address.0, &first.quan_address.0)
+            .create(pool)
+            .await;
+        TransferBuilder::new("t5", "exchange", &loner.quan_address.0)
+            .create(pool)
+            .await;
+        // Same funder, but registered outside the burst window
+        TransferBuilder::new("t6", "funder", &early.quan_address.0)
+            .create(pool)
+            .await;
+
+        let signals = repo.find_signals(60).await.unwrap();
+        let of = |address: &str| signals.iter().find(|s| s.quan_address == address).unwrap().clone();
+
+        let first_signals = of(&first.quan_address.0);
+        assert_eq!(first_signals.funding_cluster_size, 3);
+        assert_eq!(first_signals.burst_size, 1);
+        assert_eq!(of(&early.quan_address.0).burst_size, 0);
+        assert_eq!(first_signals.circular_counterparties, 1);
+
+        let loner_signals = of(&loner.quan_address.0);
+        assert_eq!(loner_signals.funding_cluster_size, 1);
+        assert_eq!(loner_signals.burst_size, 0);
+        assert_eq!(loner_signals.circular_counterparties, 0);
+
+        assert_eq!(repo.upsert_many(&signals).await.unwrap(), 4);
+        assert_eq!(stored_score(pool, &first.quan_address.0).await, first_signals.score());
+        assert_eq!(stored_score(pool, &loner.quan_address.0).await, 0);
+    }
+}
@@ -0,0 +1,64 @@
+use sqlx::{PgExecutor, PgPool};
+
+use crate::repositories::DbResult;
+
+#[derive(Clone, Debug)]
+pub struct SyncStateRepository {
+    pool: PgPool,
+}
+
+impl SyncStateRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Cursor the named sync stopped at, `None` if it never ran.
+    pub async fn find_cursor(&self, name: &str) -> DbResult<Option<String>> {
+        let cursor = sqlx::query_scalar::<_, Option<String>>("SELECT cursor FROM sync_state WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(cursor.flatten())
+    }
+
+    /// Takes any executor so the checkpoint can be saved together with the synced data.
+    pub async fn save_cursor_with<'e>(executor: impl PgExecutor<'e>, name: &str, cursor: Option<&str>) -> DbResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO sync_state (name, cursor) VALUES ($1, $2)
+            ON CONFLICT (name) DO UPDATE SET cursor = EXCLUDED.cursor
+            "#,
+        )
+        .bind(name)
+        .bind(cursor)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_app_state::create_test_app_state;
+
+    #[tokio::test]
+    async fn test_save_and_find_cursor() {
+        let state = create_test_app_state().await;
+        let repo = &state.db.sync_state;
+
+        assert_eq!(repo.find_cursor("transfers").await.unwrap(), None);
+
+        SyncStateRepository::save_cursor_with(&state.db.pool, "transfers", Some("100"))
+            .await
+            .unwrap();
+        SyncStateRepository::save_cursor_with(&state.db.pool, "transfers", Some("200"))
+            .await
+            .unwrap();
+
+        assert_eq!(repo.find_cursor("transfers").await.unwrap().as_deref(), Some("200"));
+        assert_eq!(repo.find_cursor("other").await.unwrap(), None);
+    }
+}
@@ -5,17 +5,27 @@ use crate::{
     handlers::ListQueryParams,
     // Make sure these imports match where you put the Author models
     models::tweet_author::{AuthorFilter, AuthorSortColumn, NewAuthorPayload, TweetAuthor},
-    repositories::{calculate_page_offset, DbResult, QueryBuilderExt},
+    repositories::{calculate_page_offset, prefix_tsquery, DbResult, QueryBuilderExt},
 };
 
 #[derive(Clone, Debug)]
 pub struct TweetAuthorRepository {
     pool: PgPool,
+    read_pool: PgPool,
 }
 
 impl TweetAuthorRepository {
     pub fn new(pool: &PgPool) -> Self {
-        Self { pool: pool.clone() }
+        Self {
+            pool: pool.clone(),
+            read_pool: pool.clone(),
+        }
+    }
+
+    /// Serves the author list from `read_pool`, e.g. a read replica.
+    pub fn with_read_pool(mut self, read_pool: &PgPool) -> Self {
+        self.read_pool = read_pool.clone();
+        self
     }
 
     /// Helper to build the dynamic WHERE clause for Authors
@@ -29,17 +39,12 @@ impl TweetAuthorRepository {
 
         let mut where_started = false;
 
-        // ---  Global Text Search ---
-        if let Some(s) = search {
-            if !s.is_empty() {
-                // Use the helper trait
-                query_builder.push_condition(" (ta.username ILIKE ", &mut where_started);
-                query_builder.push_bind(format!("%{}%", s));
-
-                query_builder.push(" OR ta.name ILIKE ");
-                query_builder.push_bind(format!("%{}%", s));
-                query_builder.push(") ");
-            }
+        // ---  Global Text Search, by handle or name prefix ---
+        if let Some(terms) = search.as_deref().and_then(prefix_tsquery) {
+            // Use the helper trait
+            query_builder.push_condition(" ta.search_fts @@ to_tsquery('simple', ", &mut where_started);
+            query_builder.push_bind(terms);
+            query_builder.push(") ");
         }
 
         if let Some(min_likes) = filters.min_likes {
@@ -64,7 +69,7 @@ impl TweetAuthorRepository {
 
         let count = query_builder
             .build_query_scalar()
-            .fetch_one(&self.pool)
+            .fetch_one(&self.read_pool)
             .await
             .map_err(DbError::Database)?;
 
@@ -80,13 +85,22 @@ impl TweetAuthorRepository {
 
         self.build_base_query(&mut query_builder, &params.search, filters);
 
-        // Sorting
+        // Sorting, searches without an explicit sort are ranked by relevance
         query_builder.push(" ORDER BY ");
-        let sort_col = params.sort_by.as_ref().unwrap_or(&AuthorSortColumn::FollowersCount);
+        match (&params.sort_by, params.search.as_deref().and_then(prefix_tsquery)) {
+            (None, Some(terms)) => {
+                query_builder.push("ts_rank(ta.search_fts, to_tsquery('simple', ");
+                query_builder.push_bind(terms);
+                query_builder.push(")) DESC");
+            }
+            (sort_by, _) => {
+                let sort_col = sort_by.as_ref().unwrap_or(&AuthorSortColumn::FollowersCount);
 
-        query_builder.push(sort_col.to_sql_column());
-        query_builder.push(" ");
-        query_builder.push(params.order.to_string());
+                query_builder.push(sort_col.to_sql_column());
+                query_builder.push(" ");
+                query_builder.push(params.order.to_string());
+            }
+        }
 
         // Secondary sort for stability
         query_builder.push(", ta.id ASC");
@@ -100,7 +114,7 @@ impl TweetAuthorRepository {
 
         let authors = query_builder
             .build_query_as::<TweetAuthor>()
-            .fetch_all(&self.pool)
+            .fetch_all(&self.read_pool)
             .await
             .map_err(DbError::Database)?;
 
@@ -1,10 +1,72 @@
-use axum::{handler::Handler, middleware, routing::get, Router};
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, post, put},
+    Router,
+};
 
-use crate::{handlers::address::handle_get_addresses, http_server::AppState, middlewares::jwt_auth};
+use crate::{
+    handlers::{
+        address::{
+            handle_ban_address, handle_export_addresses, handle_get_address_activity, handle_get_addresses,
+            handle_import_addresses, handle_set_referral_code, handle_unban_address,
+        },
+        address_note::{
+            handle_create_address_note, handle_get_address_detail, handle_get_address_note_history,
+            handle_update_address_note,
+        },
+    },
+    http_server::AppState,
+    middlewares::jwt_auth,
+};
 
 pub fn address_routes(state: AppState) -> Router<AppState> {
-    Router::new().route(
-        "/addresses",
-        get(handle_get_addresses.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
-    )
+    Router::new()
+        .route(
+            "/addresses",
+            get(handle_get_addresses.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/admin/addresses/import",
+            post(handle_import_addresses.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/admin/addresses/export",
+            get(handle_export_addresses.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/addresses/:quan_address",
+            get(handle_get_address_detail
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/addresses/:quan_address/activity",
+            get(handle_get_address_activity.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_auth))),
+        )
+        .route(
+            "/addresses/:quan_address/referral-code",
+            put(handle_set_referral_code.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/addresses/:quan_address/ban",
+            put(handle_ban_address.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)))
+                .delete(handle_unban_address.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/addresses/:quan_address/notes",
+            post(
+                handle_create_address_note
+                    .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)),
+            ),
+        )
+        .route(
+            "/addresses/:quan_address/notes/:note_id",
+            put(handle_update_address_note
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/addresses/:quan_address/notes/:note_id/history",
+            get(handle_get_address_note_history
+                .layer(middleware::from_fn_with_state(state, jwt_auth::jwt_admin_auth))),
+        )
 }
@@ -0,0 +1,36 @@
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::{
+    handlers::admin::{
+        handle_create_admin, handle_disable_admin, handle_enable_admin, handle_get_admins, handle_update_admin_role,
+    },
+    http_server::AppState,
+    middlewares::jwt_auth,
+};
+
+pub fn admin_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/admins",
+            get(handle_get_admins.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)))
+                .post(handle_create_admin.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/admins/:id/role",
+            put(handle_update_admin_role
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/admins/:id/disable",
+            post(handle_disable_admin.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/admins/:id/enable",
+            post(handle_enable_admin.layer(middleware::from_fn_with_state(state, jwt_auth::jwt_admin_auth))),
+        )
+}
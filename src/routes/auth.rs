@@ -1,7 +1,15 @@
 use crate::{
-    handlers::auth::{auth_admin, auth_me, handle_admin_login, request_challenge, verify_login},
+    handlers::auth::{
+        auth_admin, auth_me, handle_admin_login, logout, refresh_session, request_challenge, verify_login,
+    },
     http_server::AppState,
-    middlewares::jwt_auth,
+    middlewares::{
+        jwt_auth,
+        rate_limit::{
+            rate_limit, RateLimitScope, RATE_LIMITED_ADMIN_LOGIN, RATE_LIMITED_AUTH_CHALLENGE,
+            RATE_LIMITED_AUTH_REFRESH, RATE_LIMITED_AUTH_VERIFY,
+        },
+    },
 };
 use axum::{
     handler::Handler,
@@ -12,13 +20,42 @@ use axum::{
 
 pub fn auth_routes(state: AppState) -> Router<AppState> {
     Router::new()
-        .route("/auth/request-challenge", post(request_challenge))
-        .route("/auth/verify", post(verify_login))
+        .route(
+            "/auth/request-challenge",
+            post(request_challenge.layer(middleware::from_fn_with_state(
+                RateLimitScope::new(state.clone(), RATE_LIMITED_AUTH_CHALLENGE),
+                rate_limit,
+            ))),
+        )
+        .route(
+            "/auth/verify",
+            post(verify_login.layer(middleware::from_fn_with_state(
+                RateLimitScope::new(state.clone(), RATE_LIMITED_AUTH_VERIFY),
+                rate_limit,
+            ))),
+        )
+        .route(
+            "/auth/refresh",
+            post(refresh_session.layer(middleware::from_fn_with_state(
+                RateLimitScope::new(state.clone(), RATE_LIMITED_AUTH_REFRESH),
+                rate_limit,
+            ))),
+        )
+        .route(
+            "/auth/logout",
+            post(logout.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_auth))),
+        )
         .route(
             "/auth/me",
             get(auth_me.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_auth))),
         )
-        .route("/auth/admin/login", post(handle_admin_login))
+        .route(
+            "/auth/admin/login",
+            post(handle_admin_login.layer(middleware::from_fn_with_state(
+                RateLimitScope::new(state.clone(), RATE_LIMITED_ADMIN_LOGIN),
+                rate_limit,
+            ))),
+        )
         .route(
             "/auth/admin/me",
             get(auth_admin.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
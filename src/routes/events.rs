@@ -0,0 +1,7 @@
+use axum::{routing::get, Router};
+
+use crate::{handlers::events::handle_live_events, http_server::AppState};
+
+pub fn event_routes() -> Router<AppState> {
+    Router::new().route("/events", get(handle_live_events))
+}
@@ -0,0 +1,37 @@
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, put},
+    Router,
+};
+
+use crate::{
+    handlers::feature_flag::{
+        handle_delete_feature_flag, handle_get_feature_flags, handle_get_my_feature_flags, handle_put_feature_flag,
+    },
+    http_server::AppState,
+    middlewares::jwt_auth,
+};
+
+pub fn feature_flag_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/feature-flags",
+            get(handle_get_feature_flags
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/feature-flags/me",
+            get(handle_get_my_feature_flags.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_auth))),
+        )
+        .route(
+            "/feature-flags/:key",
+            put(
+                handle_put_feature_flag
+                    .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)),
+            )
+            .delete(
+                handle_delete_feature_flag.layer(middleware::from_fn_with_state(state, jwt_auth::jwt_admin_auth)),
+            ),
+        )
+}
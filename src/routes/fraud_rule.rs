@@ -0,0 +1,55 @@
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::{
+    handlers::fraud_rule::{
+        handle_create_fraud_rule, handle_evaluate_fraud_rules, handle_get_fraud_flags, handle_get_fraud_rule_versions,
+        handle_get_fraud_rules, handle_update_fraud_rule,
+    },
+    http_server::AppState,
+    middlewares::{
+        jwt_auth,
+        rate_limit::{rate_limit, RateLimitScope, RATE_LIMITED_FRAUD_RULE_EVALUATION},
+    },
+};
+
+pub fn fraud_rule_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/fraud-rules",
+            get(handle_get_fraud_rules.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)))
+                .post(
+                    handle_create_fraud_rule
+                        .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)),
+                ),
+        )
+        .route(
+            "/fraud-rules/evaluate",
+            // Limited per admin, so the layer sits inside the admin auth
+            post(
+                handle_evaluate_fraud_rules
+                    .layer(middleware::from_fn_with_state(
+                        RateLimitScope::new(state.clone(), RATE_LIMITED_FRAUD_RULE_EVALUATION),
+                        rate_limit,
+                    ))
+                    .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)),
+            ),
+        )
+        .route(
+            "/fraud-rules/:id",
+            put(handle_update_fraud_rule.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/fraud-rules/:id/versions",
+            get(handle_get_fraud_rule_versions
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/fraud-flags",
+            get(handle_get_fraud_flags.layer(middleware::from_fn_with_state(state, jwt_auth::jwt_admin_auth))),
+        )
+}
@@ -0,0 +1,25 @@
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    handlers::maintenance::{handle_get_pause_state, handle_pause, handle_resume},
+    http_server::AppState,
+    middlewares::jwt_auth,
+};
+
+pub fn maintenance_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/admin/pause",
+            get(handle_get_pause_state.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)))
+                .post(handle_pause.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/admin/resume",
+            post(handle_resume.layer(middleware::from_fn_with_state(state, jwt_auth::jwt_admin_auth))),
+        )
+}
@@ -7,19 +7,32 @@ use risk_checker::risk_checker_routes;
 use crate::{
     http_server::AppState,
     routes::{
-        address::address_routes, exchange_rate::exchange_rate_routes, raid_quest::raid_quest_routes,
-        relevant_tweet::relevant_tweet_routes, tweet_author::tweet_author_routes,
+        address::address_routes, admin::admin_routes, events::event_routes, exchange_rate::exchange_rate_routes,
+        feature_flag::feature_flag_routes, fraud_rule::fraud_rule_routes, maintenance::maintenance_routes,
+        opt_in_stat::opt_in_stat_routes, program::program_routes, raid_quest::raid_quest_routes,
+        raid_team::raid_team_routes, relevant_tweet::relevant_tweet_routes, setting::setting_routes,
+        transfer::transfer_routes, tweet_author::tweet_author_routes,
     },
 };
 
 pub mod address;
+pub mod admin;
 pub mod auth;
 pub mod config;
+pub mod events;
 pub mod exchange_rate;
+pub mod feature_flag;
+pub mod fraud_rule;
+pub mod maintenance;
+pub mod opt_in_stat;
+pub mod program;
 pub mod raid_quest;
+pub mod raid_team;
 pub mod referral;
 pub mod relevant_tweet;
 pub mod risk_checker;
+pub mod setting;
+pub mod transfer;
 pub mod tweet_author;
 
 pub fn api_routes(state: AppState) -> Router<AppState> {
@@ -27,10 +40,20 @@ pub fn api_routes(state: AppState) -> Router<AppState> {
         .merge(referral_routes(state.clone()))
         .merge(address_routes(state.clone()))
         .merge(auth_routes(state.clone()))
+        .merge(admin_routes(state.clone()))
         .merge(relevant_tweet_routes(state.clone()))
         .merge(tweet_author_routes(state.clone()))
-        .merge(raid_quest_routes(state))
+        .merge(raid_quest_routes(state.clone()))
+        .merge(raid_team_routes(state.clone()))
+        .merge(setting_routes(state.clone()))
+        .merge(feature_flag_routes(state.clone()))
+        .merge(fraud_rule_routes(state.clone()))
+        .merge(maintenance_routes(state.clone()))
+        .merge(opt_in_stat_routes(state.clone()))
+        .merge(transfer_routes(state))
         .merge(config_routes())
         .merge(risk_checker_routes())
         .merge(exchange_rate_routes())
+        .merge(program_routes())
+        .merge(event_routes())
 }
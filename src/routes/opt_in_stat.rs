@@ -0,0 +1,10 @@
+use axum::{handler::Handler, middleware, routing::get, Router};
+
+use crate::{handlers::opt_in_stat::handle_get_opt_in_stats, http_server::AppState, middlewares::jwt_auth};
+
+pub fn opt_in_stat_routes(state: AppState) -> Router<AppState> {
+    Router::new().route(
+        "/opt-ins/stats",
+        get(handle_get_opt_in_stats.layer(middleware::from_fn_with_state(state, jwt_auth::jwt_admin_auth))),
+    )
+}
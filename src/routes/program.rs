@@ -0,0 +1,7 @@
+use axum::{routing::get, Router};
+
+use crate::{handlers::program::handle_get_program_rules, http_server::AppState};
+
+pub fn program_routes() -> Router<AppState> {
+    Router::new().route("/program/rules", get(handle_get_program_rules))
+}
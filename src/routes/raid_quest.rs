@@ -1,14 +1,15 @@
 use axum::{
     handler::Handler,
     middleware,
-    routing::{delete, get, put},
+    routing::{delete, get, post, put},
     Router,
 };
 
 use crate::{
     handlers::raid_quest::{
-        handle_create_raid, handle_delete_raid, handle_finish_raid, handle_get_raid_quests,
-        handle_revert_to_active_raid,
+        handle_create_raid, handle_delete_raid, handle_finish_raid, handle_get_active_raid_quests,
+        handle_get_raid_payouts, handle_get_raid_quests, handle_mark_raid_payouts_paid, handle_preview_raid_payouts,
+        handle_revert_to_active_raid, handle_update_raid_scoring,
     },
     http_server::AppState,
     middlewares::jwt_auth,
@@ -22,6 +23,7 @@ pub fn raid_quest_routes(state: AppState) -> Router<AppState> {
                 handle_create_raid.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)),
             ),
         )
+        .route("/raid-quests/active", get(handle_get_active_raid_quests))
         .route(
             "/raid-quests/:raid_id",
             delete(handle_delete_raid.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
@@ -35,4 +37,25 @@ pub fn raid_quest_routes(state: AppState) -> Router<AppState> {
             put(handle_revert_to_active_raid
                 .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
         )
+        .route(
+            "/raid-quests/:raid_id/scoring",
+            put(handle_update_raid_scoring
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/raid-quests/:raid_id/payouts",
+            get(handle_get_raid_payouts.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/raid-quests/:raid_id/payouts/preview",
+            get(handle_preview_raid_payouts
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/raid-quests/:raid_id/payouts/paid",
+            post(
+                handle_mark_raid_payouts_paid
+                    .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)),
+            ),
+        )
 }
@@ -0,0 +1,36 @@
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{delete, get, post},
+    Router,
+};
+
+use crate::{
+    handlers::raid_team::{
+        handle_create_raid_team, handle_get_raid_teams, handle_get_team_leaderboard, handle_join_raid_team,
+        handle_leave_raid_team,
+    },
+    http_server::AppState,
+    middlewares::jwt_auth,
+};
+
+pub fn raid_team_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/raid-quests/:raid_id/teams",
+            get(handle_get_raid_teams)
+                .post(handle_create_raid_team.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_auth))),
+        )
+        .route(
+            "/raid-quests/:raid_id/teams/leaderboard",
+            get(handle_get_team_leaderboard),
+        )
+        .route(
+            "/raid-quests/:raid_id/teams/membership",
+            delete(handle_leave_raid_team.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_auth))),
+        )
+        .route(
+            "/raid-quests/:raid_id/teams/:team_id/members",
+            post(handle_join_raid_team.layer(middleware::from_fn_with_state(state, jwt_auth::jwt_auth))),
+        )
+}
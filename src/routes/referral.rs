@@ -1,21 +1,51 @@
 use axum::{
     handler::Handler,
     middleware,
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 
 use crate::{
-    handlers::referral::{handle_add_referral, handle_get_referral_by_referee},
+    handlers::referral::{
+        handle_add_referral, handle_get_my_referral_rewards, handle_get_referral_by_referee,
+        handle_get_referral_payouts, handle_mark_referral_rewards_paid, handle_set_vanity_referral_code,
+    },
     http_server::AppState,
-    middlewares::jwt_auth,
+    middlewares::{idempotency::idempotency, jwt_auth},
 };
 
 pub fn referral_routes(state: AppState) -> Router<AppState> {
     Router::new()
         .route(
             "/referrals",
-            post(handle_add_referral.layer(middleware::from_fn_with_state(state, jwt_auth::jwt_auth))),
+            post(
+                handle_add_referral
+                    .layer(middleware::from_fn_with_state(state.clone(), idempotency))
+                    .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_auth)),
+            ),
+        )
+        .route(
+            "/referrals/vanity-code",
+            put(handle_set_vanity_referral_code
+                .layer(middleware::from_fn_with_state(state.clone(), idempotency))
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_auth))),
+        )
+        .route(
+            "/referrals/rewards",
+            get(handle_get_my_referral_rewards
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_auth))),
+        )
+        .route(
+            "/referrals/rewards/payouts",
+            get(handle_get_referral_payouts
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/referrals/rewards/payouts/paid",
+            post(
+                handle_mark_referral_rewards_paid
+                    .layer(middleware::from_fn_with_state(state, jwt_auth::jwt_admin_auth)),
+            ),
         )
         .route("/referrals/:referee_address", get(handle_get_referral_by_referee))
 }
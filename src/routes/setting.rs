@@ -0,0 +1,30 @@
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, put},
+    Router,
+};
+
+use crate::{
+    handlers::setting::{handle_delete_setting, handle_get_settings, handle_get_settings_audit, handle_update_setting},
+    http_server::AppState,
+    middlewares::jwt_auth,
+};
+
+pub fn setting_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/settings",
+            get(handle_get_settings.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/settings/audit",
+            get(handle_get_settings_audit
+                .layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth))),
+        )
+        .route(
+            "/settings/:key",
+            put(handle_update_setting.layer(middleware::from_fn_with_state(state.clone(), jwt_auth::jwt_admin_auth)))
+                .delete(handle_delete_setting.layer(middleware::from_fn_with_state(state, jwt_auth::jwt_admin_auth))),
+        )
+}
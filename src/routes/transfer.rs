@@ -0,0 +1,22 @@
+use axum::{handler::Handler, middleware, routing::get, Router};
+
+use crate::{
+    handlers::transfer::{handle_get_processed_transfer, handle_get_transfer_gaps},
+    http_server::AppState,
+    middlewares::jwt_auth,
+};
+
+pub fn transfer_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/transfers/gaps",
+            get(handle_get_transfer_gaps.layer(middleware::from_fn_with_state(
+                state.clone(),
+                jwt_auth::jwt_admin_auth,
+            ))),
+        )
+        .route(
+            "/transfers/:transfer_id",
+            get(handle_get_processed_transfer.layer(middleware::from_fn_with_state(state, jwt_auth::jwt_admin_auth))),
+        )
+}
@@ -0,0 +1,47 @@
+use crate::{
+    db_persistence::DbError,
+    handlers::{calculate_total_pages, PaginatedResponse, PaginationMetadata},
+    models::activity::{ActivityEvent, ActivityQueryParams},
+    repositories::{activity::ActivityRepository, address::AddressRepository, DbResult},
+};
+
+/// Builds the activity timeline of an address from the transfers, referrals, opt-in and raid submissions stored
+/// for it, merged and paged newest first.
+#[derive(Debug, Clone)]
+pub struct ActivityFeedService {
+    addresses: AddressRepository,
+    activity: ActivityRepository,
+}
+
+impl ActivityFeedService {
+    pub fn new(addresses: AddressRepository, activity: ActivityRepository) -> Self {
+        Self { addresses, activity }
+    }
+
+    pub async fn timeline(
+        &self,
+        quan_address: &str,
+        params: &ActivityQueryParams,
+    ) -> DbResult<PaginatedResponse<ActivityEvent>> {
+        if self.addresses.find_by_id(quan_address).await?.is_none() {
+            return Err(DbError::AddressNotFound(quan_address.to_string()));
+        }
+
+        let total_items = self.activity.count_for_address(quan_address).await? as u32;
+        let offset = (params.page - 1) * params.page_size;
+        let events = self
+            .activity
+            .find_for_address(quan_address, params.page_size, offset)
+            .await?;
+
+        Ok(PaginatedResponse {
+            data: events,
+            meta: PaginationMetadata {
+                page: params.page,
+                page_size: params.page_size,
+                total_items,
+                total_pages: calculate_total_pages(params.page_size, total_items),
+            },
+        })
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    models::address::{
+        Address, AddressDataFormat, AddressImportRow, AddressImportRowError, AddressImportSummary, AddressInput,
+        QuanAddress,
+    },
+    repositories::address::AddressRepository,
+    services::referral_code_service::{ReferralCodeError, ReferralCodeResult, ReferralCodeService},
+    utils::generate_referral_code::generate_referral_code,
+};
+
+/// Rows stored per `create_many` call.
+pub const IMPORT_CHUNK_SIZE: usize = 1000;
+/// Longer lines are rejected without buffering them.
+const MAX_LINE_BYTES: usize = 4096;
+/// Row errors kept for the response, large files with a systematic problem would otherwise echo every row.
+const MAX_REPORTED_ERRORS: usize = 1000;
+
+/// Parses one line of an import body. Blank lines and the CSV header give `None`.
+pub fn parse_import_line(format: AddressDataFormat, line: &str) -> Result<Option<AddressImportRow>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    match format {
+        AddressDataFormat::Ndjson => serde_json::from_str(line)
+            .map(Some)
+            .map_err(|e| format!("Invalid JSON: {}", e)),
+        AddressDataFormat::Csv => {
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim().trim_matches('"')).collect();
+
+            match fields.as_slice() {
+                ["quan_address", ..] => Ok(None),
+                [quan_address] => Ok(Some(AddressImportRow {
+                    quan_address: quan_address.to_string(),
+                    referral_code: None,
+                })),
+                [quan_address, referral_code] => Ok(Some(AddressImportRow {
+                    quan_address: quan_address.to_string(),
+                    referral_code: Some(referral_code.to_string()).filter(|code| !code.is_empty()),
+                })),
+                _ => Err(format!("Expected 1 or 2 columns, got {}", fields.len())),
+            }
+        }
+    }
+}
+
+/// Line of an export body, in the same shape the import accepts.
+pub fn export_line(format: AddressDataFormat, address: &Address) -> String {
+    match format {
+        AddressDataFormat::Ndjson => {
+            let mut line = serde_json::to_string(address).unwrap_or_default();
+            line.push('\n');
+            line
+        }
+        AddressDataFormat::Csv => format!("{},{}\n", address.quan_address.0, address.referral_code),
+    }
+}
+
+pub const CSV_EXPORT_HEADER: &str = "quan_address,referral_code\n";
+
+/// Imports addresses from a request body fed in arbitrary pieces. Rows are validated and stored in chunks of
+/// [`IMPORT_CHUNK_SIZE`], so the body never has to be held in memory. Invalid rows are reported by line and
+/// don't stop the import, existing addresses are left untouched.
+pub struct AddressImporter<'a> {
+    addresses: &'a AddressRepository,
+    format: AddressDataFormat,
+    buffer: Vec<u8>,
+    /// Set while the rest of an overlong line is dropped.
+    discarding: bool,
+    line: usize,
+    pending: Vec<(usize, AddressImportRow)>,
+    summary: AddressImportSummary,
+}
+
+impl<'a> AddressImporter<'a> {
+    pub fn new(addresses: &'a AddressRepository, format: AddressDataFormat) -> Self {
+        Self {
+            addresses,
+            format,
+            buffer: Vec::new(),
+            discarding: false,
+            line: 0,
+            pending: Vec::with_capacity(IMPORT_CHUNK_SIZE),
+            summary: AddressImportSummary::default(),
+        }
+    }
+
+    pub async fn push_bytes(&mut self, bytes: &[u8]) -> ReferralCodeResult<()> {
+        for part in bytes.split_inclusive(|b| *b == b'\n') {
+            let complete = part.ends_with(b"\n");
+
+            if !self.discarding {
+                self.buffer.extend_from_slice(part);
+                if self.buffer.len() > MAX_LINE_BYTES {
+                    self.buffer.clear();
+                    self.discarding = true;
+                    self.line += 1;
+                    self.fail(self.line, format!("Line is longer than {} bytes", MAX_LINE_BYTES));
+                }
+            }
+
+            if complete {
+                if self.discarding {
+                    self.discarding = false;
+                } else {
+                    let line = std::mem::take(&mut self.buffer);
+                    self.push_line(&line).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stores the remaining rows and returns the outcome of the import.
+    pub async fn finish(mut self) -> ReferralCodeResult<AddressImportSummary> {
+        if !self.buffer.is_empty() && !self.discarding {
+            let line = std::mem::take(&mut self.buffer);
+            self.push_line(&line).await?;
+        }
+        self.flush().await?;
+
+        Ok(self.summary)
+    }
+
+    async fn push_line(&mut self, line: &[u8]) -> ReferralCodeResult<()> {
+        self.line += 1;
+
+        let parsed = std::str::from_utf8(line)
+            .map_err(|_| "Line is not valid UTF-8".to_string())
+            .and_then(|line| parse_import_line(self.format, line));
+
+        match parsed {
+            Ok(Some(row)) => {
+                self.summary.total_rows += 1;
+                self.pending.push((self.line, row));
+                if self.pending.len() >= IMPORT_CHUNK_SIZE {
+                    self.flush().await?;
+                }
+            }
+            Ok(None) => {}
+            Err(message) => {
+                self.summary.total_rows += 1;
+                self.fail(self.line, message);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fail(&mut self, line: usize, message: String) {
+        self.summary.failed += 1;
+        if self.summary.errors.len() < MAX_REPORTED_ERRORS {
+            self.summary.errors.push(AddressImportRowError { line, message });
+        }
+    }
+
+    async fn flush(&mut self) -> ReferralCodeResult<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let rows = std::mem::take(&mut self.pending);
+        let mut seen = HashSet::with_capacity(rows.len());
+        // (line, address, code, whether the code was requested), requested codes go first so generated ones
+        // are moved out of their way
+        let mut requested = Vec::new();
+        let mut generated = Vec::new();
+
+        for (line, row) in rows {
+            let quan_address = match QuanAddress::from(row.quan_address.trim()) {
+                Ok(quan_address) => quan_address.0,
+                Err(e) => {
+                    self.fail(line, e);
+                    continue;
+                }
+            };
+            if !seen.insert(quan_address.clone()) {
+                self.fail(line, format!("Duplicate address {}", quan_address));
+                continue;
+            }
+
+            match row.referral_code {
+                Some(code) => match ReferralCodeService::validate_vanity_code(&code, &[], true) {
+                    Ok(code) => requested.push((line, quan_address, code, true)),
+                    Err(e) => self.fail(line, e.to_string()),
+                },
+                None => match generate_referral_code(quan_address.clone()).await {
+                    Ok(code) => generated.push((line, quan_address, code, false)),
+                    Err(e) => self.fail(line, e.to_string()),
+                },
+            }
+        }
+
+        let candidates: Vec<_> = requested.into_iter().chain(generated).collect();
+        let pairs = candidates
+            .iter()
+            .map(|(_, quan_address, code, _)| (quan_address.clone(), code.clone()))
+            .collect();
+        let repaired: HashMap<String, String> = ReferralCodeService::repair_batch(self.addresses, pairs)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut addresses = Vec::with_capacity(candidates.len());
+        for (line, quan_address, code, is_requested) in candidates {
+            let referral_code = match repaired.get(&quan_address) {
+                Some(free) if !is_requested || *free == code => free.clone(),
+                Some(_) => {
+                    self.fail(line, ReferralCodeError::VanityCodeTaken(code).to_string());
+                    continue;
+                }
+                None => {
+                    self.fail(line, ReferralCodeError::Exhausted(quan_address).to_string());
+                    continue;
+                }
+            };
+
+            match Address::new(AddressInput {
+                quan_address,
+                referral_code,
+            }) {
+                Ok(address) => addresses.push(address),
+                Err(e) => self.fail(line, e.to_string()),
+            }
+        }
+
+        let valid = addresses.len() as u64;
+        let imported = self.addresses.create_many(addresses).await?;
+        self.summary.imported += imported;
+        self.summary.skipped_existing += valid - imported;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::create_persisted_address};
+
+    #[test]
+    fn test_parse_import_line() {
+        let csv = AddressDataFormat::Csv;
+        assert_eq!(parse_import_line(csv, "quan_address,referral_code"), Ok(None));
+        assert_eq!(parse_import_line(csv, "  "), Ok(None));
+        assert_eq!(
+            parse_import_line(csv, "qz_address_1, \"promo\"\r"),
+            Ok(Some(AddressImportRow {
+                quan_address: "qz_address_1".to_string(),
+                referral_code: Some("promo".to_string()),
+            }))
+        );
+        assert_eq!(
+            parse_import_line(csv, "qz_address_1,"),
+            Ok(Some(AddressImportRow {
+                quan_address: "qz_address_1".to_string(),
+                referral_code: None,
+            }))
+        );
+        assert!(parse_import_line(csv, "a,b,c").is_err());
+
+        let ndjson = AddressDataFormat::Ndjson;
+        assert_eq!(
+            parse_import_line(ndjson, r#"{"quan_address": "qz_address_1", "referrals_count": 3}"#),
+            Ok(Some(AddressImportRow {
+                quan_address: "qz_address_1".to_string(),
+                referral_code: None,
+            }))
+        );
+        assert!(parse_import_line(ndjson, "not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_reports_rows_and_skips_existing() {
+        let state = create_test_app_state().await;
+        let existing = create_persisted_address(&state.db.addresses, "existing").await;
+
+        let body = format!(
+            "quan_address,referral_code\nqz_import_address_1,promo\n{},\nbad\nqz_import_address_2,promo\nqz_import_address_3\n",
+            existing.quan_address.0
+        );
+        let mut importer = AddressImporter::new(&state.db.addresses, AddressDataFormat::Csv);
+        // Pieces don't have to end on line breaks
+        for piece in body.as_bytes().chunks(7) {
+            importer.push_bytes(piece).await.unwrap();
+        }
+        let summary = importer.finish().await.unwrap();
+
+        assert_eq!(summary.total_rows, 5);
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.skipped_existing, 1);
+        assert_eq!(summary.failed, 2);
+        let failed_lines: Vec<usize> = summary.errors.iter().map(|e| e.line).collect();
+        assert_eq!(failed_lines, vec![4, 5]);
+
+        let promo = state
+            .db
+            .addresses
+            .find_by_referral_code("promo")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(promo.quan_address.0, "qz_import_address_1");
+        assert!(state
+            .db
+            .addresses
+            .find_by_id("qz_import_address_3")
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,298 @@
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+use axum::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::RwLock;
+
+use crate::{
+    config::{AuthConfig, ChallengeStoreMode},
+    http_server::Challenge,
+    metrics::CHALLENGE_STORE_LOOKUPS,
+    repositories::auth_challenge::AuthChallengeRepository,
+    repositories::DbResult,
+    services::settings_service::SettingsService,
+};
+
+/// Where challenges are kept. The shared backend of [`ChallengeStore`] must be reachable by every replica,
+/// [`AuthChallengeRepository`] is the Postgres implementation.
+#[async_trait]
+pub trait ChallengeBackend: Debug + Send + Sync {
+    /// Label of the backend in `challenge_store_lookups_total`.
+    fn name(&self) -> &'static str;
+    async fn insert(&self, temp_session_id: &str, challenge: &Challenge) -> DbResult<()>;
+    async fn get(&self, temp_session_id: &str) -> DbResult<Option<Challenge>>;
+    async fn remove(&self, temp_session_id: &str) -> DbResult<()>;
+    /// Drops challenges created before `cutoff`, returning how many were removed.
+    async fn purge_created_before(&self, cutoff: DateTime<Utc>) -> DbResult<u64>;
+}
+
+#[async_trait]
+impl ChallengeBackend for AuthChallengeRepository {
+    fn name(&self) -> &'static str {
+        "database"
+    }
+
+    async fn insert(&self, temp_session_id: &str, challenge: &Challenge) -> DbResult<()> {
+        self.create(temp_session_id, challenge).await
+    }
+
+    async fn get(&self, temp_session_id: &str) -> DbResult<Option<Challenge>> {
+        self.find_by_id(temp_session_id).await
+    }
+
+    async fn remove(&self, temp_session_id: &str) -> DbResult<()> {
+        self.delete(temp_session_id).await
+    }
+
+    async fn purge_created_before(&self, cutoff: DateTime<Utc>) -> DbResult<u64> {
+        self.delete_created_before(cutoff).await
+    }
+}
+
+/// Challenges in process memory, lost on restart and not shared between replicas.
+#[derive(Debug)]
+pub struct MemoryChallengeBackend {
+    challenges: RwLock<HashMap<String, Challenge>>,
+    /// When full, the oldest challenge is dropped to make room. 0 means unbounded.
+    max_size: usize,
+}
+
+impl MemoryChallengeBackend {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            challenges: RwLock::new(HashMap::new()),
+            max_size,
+        }
+    }
+}
+
+#[async_trait]
+impl ChallengeBackend for MemoryChallengeBackend {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn insert(&self, temp_session_id: &str, challenge: &Challenge) -> DbResult<()> {
+        let mut challenges = self.challenges.write().await;
+
+        if self.max_size > 0 && challenges.len() >= self.max_size {
+            let oldest = challenges
+                .iter()
+                .min_by_key(|(_, challenge)| challenge.created_at)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                tracing::warn!(
+                    "Challenge store is full ({} entries), dropping the oldest",
+                    self.max_size
+                );
+                challenges.remove(&oldest);
+            }
+        }
+
+        challenges.insert(temp_session_id.to_string(), challenge.clone());
+        Ok(())
+    }
+
+    async fn get(&self, temp_session_id: &str) -> DbResult<Option<Challenge>> {
+        Ok(self.challenges.read().await.get(temp_session_id).cloned())
+    }
+
+    async fn remove(&self, temp_session_id: &str) -> DbResult<()> {
+        self.challenges.write().await.remove(temp_session_id);
+        Ok(())
+    }
+
+    async fn purge_created_before(&self, cutoff: DateTime<Utc>) -> DbResult<u64> {
+        let mut challenges = self.challenges.write().await;
+        let before = challenges.len();
+        challenges.retain(|_, challenge| challenge.created_at >= cutoff);
+        Ok((before - challenges.len()) as u64)
+    }
+}
+
+/// Login challenges, kept in memory, in the shared backend or in both depending on `auth.challenge_store`.
+///
+/// The mode is read from the effective settings on every call so a live deployment can be moved from
+/// `memory` to `dual` to `database` through the settings API. Writes go to the store(s) of the current
+/// mode, reads fall back to the other store so challenges issued before a switch keep working. Lookups are
+/// counted per store in `challenge_store_lookups_total` to see when the fallback is no longer hit.
+///
+/// Challenges expire after `auth.challenge_ttl_secs`. Expired ones are never returned and are swept from
+/// both stores whenever a new challenge is issued.
+#[derive(Debug)]
+pub struct ChallengeStore {
+    memory: MemoryChallengeBackend,
+    shared: Arc<dyn ChallengeBackend>,
+    settings: Arc<SettingsService>,
+}
+
+impl ChallengeStore {
+    pub fn new(shared: Arc<dyn ChallengeBackend>, settings: Arc<SettingsService>) -> Self {
+        let max_size = settings.current().auth.max_memory_challenges;
+
+        Self {
+            memory: MemoryChallengeBackend::new(max_size),
+            shared,
+            settings,
+        }
+    }
+
+    fn auth_config(&self) -> AuthConfig {
+        self.settings.current().auth.clone()
+    }
+
+    pub async fn insert(&self, temp_session_id: &str, challenge: Challenge) -> DbResult<()> {
+        let config = self.auth_config();
+        let cutoff = Utc::now() - Duration::seconds(config.challenge_ttl_secs as i64);
+
+        if config.challenge_store != ChallengeStoreMode::Memory {
+            self.shared.insert(temp_session_id, &challenge).await?;
+
+            if let Err(e) = self.shared.purge_created_before(cutoff).await {
+                tracing::warn!("Failed to clean up stale challenges: {}", e);
+            }
+        }
+
+        self.memory.purge_created_before(cutoff).await?;
+        if config.challenge_store != ChallengeStoreMode::Database {
+            self.memory.insert(temp_session_id, &challenge).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Looks up an unexpired challenge.
+    pub async fn get(&self, temp_session_id: &str) -> DbResult<Option<Challenge>> {
+        let config = self.auth_config();
+        let (first, second): (&dyn ChallengeBackend, &dyn ChallengeBackend) = match config.challenge_store {
+            ChallengeStoreMode::Memory => (&self.memory, self.shared.as_ref()),
+            ChallengeStoreMode::Dual | ChallengeStoreMode::Database => (self.shared.as_ref(), &self.memory),
+        };
+
+        let found = match lookup(first, temp_session_id).await? {
+            Some(challenge) => Some(challenge),
+            None => lookup(second, temp_session_id).await?,
+        };
+
+        let expires_before = Utc::now() - Duration::seconds(config.challenge_ttl_secs as i64);
+        Ok(found.filter(|challenge| challenge.created_at >= expires_before))
+    }
+
+    /// Removes the challenge from both stores, whatever the mode.
+    pub async fn remove(&self, temp_session_id: &str) -> DbResult<()> {
+        self.memory.remove(temp_session_id).await?;
+        self.shared.remove(temp_session_id).await
+    }
+}
+
+async fn lookup(backend: &dyn ChallengeBackend, temp_session_id: &str) -> DbResult<Option<Challenge>> {
+    let challenge = backend.get(temp_session_id).await?;
+    CHALLENGE_STORE_LOOKUPS
+        .with_label_values(&[backend.name(), if challenge.is_some() { "hit" } else { "miss" }])
+        .inc();
+    Ok(challenge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_app_state::create_test_app_state;
+    use serde_json::json;
+
+    fn challenge() -> Challenge {
+        Challenge {
+            challenge: "challenge".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_switching_modes_keeps_existing_challenges() {
+        let state = create_test_app_state().await;
+
+        let settings = Arc::new(
+            SettingsService::load(state.config.clone(), state.db.settings.clone())
+                .await
+                .unwrap(),
+        );
+        settings
+            .set("auth.challenge_store", json!("memory"), "admin")
+            .await
+            .unwrap();
+        let store = ChallengeStore::new(Arc::new(state.db.auth_challenges.clone()), settings.clone());
+
+        store.insert("in-memory", challenge()).await.unwrap();
+        assert!(state
+            .db
+            .auth_challenges
+            .find_by_id("in-memory")
+            .await
+            .unwrap()
+            .is_none());
+
+        settings
+            .set("auth.challenge_store", json!("dual"), "admin")
+            .await
+            .unwrap();
+        store.insert("dual", challenge()).await.unwrap();
+        assert!(state.db.auth_challenges.find_by_id("dual").await.unwrap().is_some());
+
+        settings
+            .set("auth.challenge_store", json!("database"), "admin")
+            .await
+            .unwrap();
+        store.insert("in-db", challenge()).await.unwrap();
+
+        // Challenges issued under every previous mode are still found.
+        for id in ["in-memory", "dual", "in-db"] {
+            assert!(store.get(id).await.unwrap().is_some(), "{} not found", id);
+        }
+
+        store.remove("dual").await.unwrap();
+        assert!(store.get("dual").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expired_challenges_are_not_returned() {
+        let state = create_test_app_state().await;
+        let ttl = Duration::seconds(state.config.auth.challenge_ttl_secs as i64);
+
+        let stale = Challenge {
+            challenge: "stale".to_string(),
+            created_at: Utc::now() - ttl - Duration::seconds(1),
+        };
+        state.challenges.insert("stale", stale).await.unwrap();
+        state.challenges.insert("fresh", challenge()).await.unwrap();
+
+        assert!(state.challenges.get("stale").await.unwrap().is_none());
+        assert!(state.challenges.get("fresh").await.unwrap().is_some());
+
+        // Issuing a challenge sweeps the expired ones from the database
+        state.challenges.insert("another", challenge()).await.unwrap();
+        assert!(state.db.auth_challenges.find_by_id("stale").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_drops_oldest_when_full() {
+        let backend = MemoryChallengeBackend::new(2);
+        let now = Utc::now();
+
+        for (i, id) in ["first", "second", "third"].into_iter().enumerate() {
+            let challenge = Challenge {
+                challenge: id.to_string(),
+                created_at: now + Duration::seconds(i as i64),
+            };
+            backend.insert(id, &challenge).await.unwrap();
+        }
+
+        assert!(backend.get("first").await.unwrap().is_none());
+        assert!(backend.get("second").await.unwrap().is_some());
+        assert!(backend.get("third").await.unwrap().is_some());
+
+        assert_eq!(
+            backend.purge_created_before(now + Duration::seconds(2)).await.unwrap(),
+            1
+        );
+        assert!(backend.get("second").await.unwrap().is_none());
+    }
+}
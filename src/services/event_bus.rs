@@ -0,0 +1,112 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Events a subscriber can fall behind by before it starts missing them.
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RaidQuestChange {
+    Created,
+    Finished,
+    Reactivated,
+    Deleted,
+    ScoringChanged,
+}
+
+/// Change pushed to live clients, so dashboards don't have to poll.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    RaidQuestChanged {
+        raid_id: i32,
+        change: RaidQuestChange,
+    },
+    /// Team rankings of the raid have to be fetched again.
+    LeaderboardChanged {
+        raid_id: i32,
+    },
+}
+
+impl LiveEvent {
+    /// SSE event name, matches the `type` field of the payload.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LiveEvent::RaidQuestChanged { .. } => "raid_quest_changed",
+            LiveEvent::LeaderboardChanged { .. } => "leaderboard_changed",
+        }
+    }
+}
+
+/// In-process broadcast channel that repositories publish to after their writes are committed.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<LiveEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Sends the event to current subscribers. Nobody listening is fine, the event is dropped.
+    pub fn publish(&self, event: LiveEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::raid_quest::CreateRaidQuest,
+        utils::{test_app_state::create_test_app_state, test_db::create_persisted_address},
+    };
+
+    #[tokio::test]
+    async fn test_repository_writes_are_published() {
+        let state = create_test_app_state().await;
+        let mut events = state.db.events.subscribe();
+
+        let raid_id = state
+            .db
+            .raid_quests
+            .create(&CreateRaidQuest {
+                name: "Raid".to_string(),
+                scoring: Default::default(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            events.recv().await.unwrap(),
+            LiveEvent::RaidQuestChanged {
+                raid_id,
+                change: RaidQuestChange::Created
+            }
+        );
+
+        let alice = create_persisted_address(&state.db.addresses, "alice").await;
+        state
+            .db
+            .raid_teams
+            .create(raid_id, "Team A", &alice.quan_address.0)
+            .await
+            .unwrap();
+        assert_eq!(events.recv().await.unwrap(), LiveEvent::LeaderboardChanged { raid_id });
+
+        // Failed writes publish nothing
+        assert!(state.db.raid_quests.finish(9999).await.is_err());
+        assert!(events.try_recv().is_err());
+    }
+}
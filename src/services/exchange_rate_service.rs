@@ -7,6 +7,8 @@ use std::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::utils::retry::{Retrier, RetryError, RetryPolicy};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExchangeRateSnapshot {
     pub conversion_rates: HashMap<String, f64>,
@@ -26,6 +28,34 @@ pub enum ExchangeRateError {
 
     #[error("Cache error: {0}")]
     Cache(String),
+
+    #[error("{0}")]
+    Unavailable(String),
+}
+
+impl ExchangeRateError {
+    /// Errors worth retrying: network failures, timeouts, rate limiting and server side errors.
+    fn is_transient(&self) -> bool {
+        match self {
+            ExchangeRateError::Http(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().is_some_and(|status| {
+                        status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+impl From<RetryError<ExchangeRateError>> for ExchangeRateError {
+    fn from(err: RetryError<ExchangeRateError>) -> Self {
+        match err {
+            RetryError::Failed(e) => e,
+            RetryError::CircuitOpen(_) => ExchangeRateError::Unavailable(err.to_string()),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -35,10 +65,11 @@ pub struct ExchangeRateService {
     base_url: String,
     cache: Arc<RwLock<Option<ExchangeRateSnapshot>>>,
     base_currency: String,
+    retrier: Arc<Retrier>,
 }
 
 impl ExchangeRateService {
-    pub fn new(api_key: &str) -> Self {
+    pub fn new(api_key: &str, retry: RetryPolicy) -> Self {
         let base_url = format!("https://v6.exchangerate-api.com/v6/{}", api_key);
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(30))
@@ -50,6 +81,7 @@ impl ExchangeRateService {
             base_url,
             cache: Arc::new(RwLock::new(None)),
             base_currency: "USD".to_string(),
+            retrier: Arc::new(Retrier::new("Exchange rate API", retry)),
         }
     }
 
@@ -68,7 +100,10 @@ impl ExchangeRateService {
             }
         } // guard dropped here, before any await point
 
-        let snapshot = self.fetch_latest(&base).await?;
+        let snapshot = self
+            .retrier
+            .run(ExchangeRateError::is_transient, || self.fetch_latest(&base))
+            .await?;
         let mut write_guard = self
             .cache
             .write()
@@ -79,8 +114,18 @@ impl ExchangeRateService {
 
     async fn fetch_latest(&self, base: &str) -> Result<ExchangeRateSnapshot, ExchangeRateError> {
         let url = format!("{}/latest/{}", self.base_url, base);
-        let response = self.client.get(&url).send().await?.error_for_status()?;
-        let text = response.text().await?;
+        // The URL carries the API key, keep it out of the error since retries log it
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| ExchangeRateError::Http(e.without_url()))?;
+        let text = response
+            .text()
+            .await
+            .map_err(|e| ExchangeRateError::Http(e.without_url()))?;
         let parsed: ExchangeRateApiV6Response = serde_json::from_str(&text)?;
 
         if parsed.result != "success" {
@@ -102,12 +147,32 @@ impl ExchangeRateService {
             time_next_update_unix: time_next_i64,
         })
     }
+
+    /// Asks the API for the remaining quota, which doesn't use any of it.
+    pub async fn check_reachable(&self) -> Result<(), ExchangeRateError> {
+        let url = format!("{}/quota", self.base_url);
+        // The URL carries the API key, keep it out of the error
+        self.client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|e| ExchangeRateError::Http(e.without_url()))
+    }
 }
 
 #[cfg(test)]
 impl ExchangeRateService {
     fn new_test(base_url: String) -> Self {
-        let mut service = Self::new("test-key");
+        let retry = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 2,
+            circuit_breaker_threshold: 0,
+            circuit_breaker_cooldown_secs: 60,
+        };
+        let mut service = Self::new("test-key", retry);
         service.base_url = base_url;
         service
     }
@@ -198,4 +263,28 @@ mod tests {
         let _a = service.get_snapshot().await.unwrap();
         let _b = service.get_snapshot().await.unwrap();
     }
+
+    #[tokio::test]
+    async fn server_errors_are_retried() {
+        let server = MockServer::start().await;
+        let v6 = format!("{}/v6/test-key", server.uri());
+
+        Mock::given(method("GET"))
+            .and(path("/v6/test-key/latest/USD"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v6/test-key/latest/USD"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(success_body()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let service = ExchangeRateService::new_test(v6);
+        let snapshot = service.get_snapshot().await.unwrap();
+        assert_eq!(snapshot.conversion_rates["EUR"], 0.9);
+    }
 }
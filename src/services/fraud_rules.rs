@@ -0,0 +1,39 @@
+use crate::{
+    models::fraud_rule::FraudRuleEvaluation,
+    repositories::{fraud_rule::FraudRuleRepository, DbResult},
+};
+
+/// Runs the enabled fraud rules, flagging matching addresses and quarantining their submissions. Runs after each
+/// `--sync-transfers` run next to the sybil scores, and on demand from the admin API.
+#[derive(Debug, Clone)]
+pub struct FraudRuleService {
+    repository: FraudRuleRepository,
+}
+
+impl FraudRuleService {
+    pub fn new(repository: FraudRuleRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Evaluates every enabled rule, returning the new hits per rule.
+    pub async fn evaluate_enabled(&self) -> DbResult<Vec<FraudRuleEvaluation>> {
+        let rules = self.repository.find_all().await?;
+        let mut evaluations = Vec::new();
+
+        for rule in rules.iter().filter(|rule| rule.enabled) {
+            let evaluation = self.repository.evaluate(rule).await?;
+            if evaluation.new_flags > 0 {
+                tracing::warn!(
+                    "Fraud rule '{}' v{} raised {} new flags ({} submissions quarantined)",
+                    evaluation.name,
+                    evaluation.version,
+                    evaluation.new_flags,
+                    evaluation.quarantined_submissions
+                );
+            }
+            evaluations.push(evaluation);
+        }
+
+        Ok(evaluations)
+    }
+}
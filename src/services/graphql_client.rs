@@ -1,18 +1,34 @@
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tracing::{debug, info, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn, Instrument};
 
 use crate::{
     db_persistence::{DbError, DbPersistence},
-    models::address::{Address, AddressInput},
-    utils::generate_referral_code::generate_referral_code,
+    models::{
+        address::{Address, AddressInput},
+        processed_transfer::{ProcessedTransfer, ProcessedTransferPage},
+    },
+    repositories::{
+        address::AddressRepository, processed_transfer::ProcessedTransferRepository, sync_state::SyncStateRepository,
+    },
+    services::{
+        referral_code_service::{ReferralCodeError, ReferralCodeService},
+        runbook,
+    },
+    utils::{
+        generate_referral_code::generate_referral_code,
+        retry::{Retrier, RetryError, RetryPolicy},
+    },
 };
 
 #[derive(Debug, thiserror::Error)]
 pub enum GraphqlError {
     #[error("HTTP request failed: {0}")]
     RequestError(#[from] reqwest::Error),
+    #[error("HTTP {0} - {1}")]
+    HttpStatus(reqwest::StatusCode, String),
     #[error("GraphQL response error: {0}")]
     GraphqlResponseError(String),
     #[error("JSON parsing error: {0}")]
@@ -21,10 +37,50 @@ pub enum GraphqlError {
     DatabaseError(#[from] DbError),
     #[error("Invalid data format: {0}")]
     InvalidData(String),
+    #[error("Referral code error: {0}")]
+    ReferralCode(#[from] ReferralCodeError),
+    #[error("{0}")]
+    Unavailable(String),
+}
+
+impl GraphqlError {
+    /// Errors worth retrying: network failures, timeouts, rate limiting and server side errors.
+    fn is_transient(&self) -> bool {
+        match self {
+            GraphqlError::RequestError(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            GraphqlError::HttpStatus(status, _) => {
+                status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        }
+    }
+
+    /// Runbook code for failures operators can act on.
+    pub fn runbook_code(&self) -> Option<&'static str> {
+        match self {
+            GraphqlError::RequestError(_) => Some(runbook::INDEXER_UNREACHABLE),
+            GraphqlError::HttpStatus(..) | GraphqlError::GraphqlResponseError(_) => Some(runbook::INDEXER_ERROR),
+            GraphqlError::Unavailable(_) => Some(runbook::INDEXER_CIRCUIT_OPEN),
+            GraphqlError::DatabaseError(_) => Some(runbook::DATABASE_UNREACHABLE),
+            _ => None,
+        }
+    }
+}
+
+impl From<RetryError<GraphqlError>> for GraphqlError {
+    fn from(err: RetryError<GraphqlError>) -> Self {
+        match err {
+            RetryError::Failed(e) => e,
+            RetryError::CircuitOpen(_) => GraphqlError::Unavailable(err.to_string()),
+        }
+    }
 }
 
 pub type GraphqlResult<T> = Result<T, GraphqlError>;
 
+/// Name of the transfer sync checkpoint in `sync_state`.
+const TRANSFERS_SYNC: &str = "transfers";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GraphqlQuery {
     query: String,
@@ -32,6 +88,83 @@ pub struct GraphqlQuery {
     variables: Option<HashMap<String, serde_json::Value>>,
 }
 
+impl GraphqlQuery {
+    /// Builds the request payload for a typed operation.
+    ///
+    /// Variables are serialized from the operation's `Variables` type and
+    /// must form a JSON object. The query document itself isn't checked
+    /// against the indexer schema, a mismatch only shows up when the indexer
+    /// rejects the request.
+    pub fn build<O: GraphqlOperation>(variables: O::Variables) -> GraphqlResult<Self> {
+        let variables = match serde_json::to_value(variables)? {
+            serde_json::Value::Null => None,
+            serde_json::Value::Object(map) => Some(map.into_iter().collect()),
+            other => {
+                return Err(GraphqlError::InvalidData(format!(
+                    "GraphQL variables must serialize to an object, got {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Self {
+            query: O::QUERY.to_string(),
+            variables,
+        })
+    }
+}
+
+/// A GraphQL operation bound to its variables and response data types.
+///
+/// Each query the service sends is declared once as an implementor of this
+/// trait; callers and tests refer to the operation type instead of repeating
+/// the query text.
+pub trait GraphqlOperation {
+    /// Variables sent alongside the query. Use `()` for operations without variables.
+    type Variables: Serialize;
+    /// Shape of the `data` field in a successful response.
+    type ResponseData: DeserializeOwned;
+    /// Operation name, used for logging.
+    const NAME: &'static str;
+    /// The GraphQL document.
+    const QUERY: &'static str;
+}
+
+/// Fetches one page of transfers, oldest first. Cursors are opaque and issued by the indexer.
+pub struct TransfersQuery;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransfersPageVariables {
+    pub first: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+impl GraphqlOperation for TransfersQuery {
+    type Variables = TransfersPageVariables;
+    type ResponseData = TransferData;
+
+    const NAME: &'static str = "transfers";
+    const QUERY: &'static str = r#"
+        query($first: Int!, $after: String) {
+            transfersConnection(orderBy: id_ASC, first: $first, after: $after) {
+                edges {
+                    node {
+                        id
+                        amount
+                        from { id }
+                        to { id }
+                    }
+                }
+                pageInfo {
+                    hasNextPage
+                    endCursor
+                }
+            }
+        }
+        "#;
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GraphqlResponse<T> {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -56,8 +189,45 @@ pub struct GraphqlErrorLocation {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TransferData {
-    transfers: Vec<Transfer>,
+    transfers_connection: TransfersConnection,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransfersConnection {
+    edges: Vec<TransferEdge>,
+    page_info: PageInfo,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransferEdge {
+    node: Transfer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+/// One page of transfers and where the next one starts.
+#[derive(Debug)]
+pub struct TransfersPage {
+    pub transfers: Vec<Transfer>,
+    pub page_info: PageInfo,
+}
+
+impl From<TransferData> for TransfersPage {
+    fn from(data: TransferData) -> Self {
+        let connection = data.transfers_connection;
+        Self {
+            transfers: connection.edges.into_iter().map(|edge| edge.node).collect(),
+            page_info: connection.page_info,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,10 +248,14 @@ pub struct GraphqlClient {
     client: Client,
     db: DbPersistence,
     graphql_url: String,
+    page_size: u32,
+    retrier: Arc<Retrier>,
+    /// Bounds the indexer requests in flight, shared by all clones.
+    limiter: Option<Arc<Semaphore>>,
 }
 
 impl GraphqlClient {
-    pub fn new(db: DbPersistence, graphql_url: String) -> Self {
+    pub fn new(db: DbPersistence, graphql_url: String, page_size: u32, retry: RetryPolicy) -> Self {
         let client = Client::builder()
             .timeout(std::time::Duration::from_secs(30))
             .build()
@@ -91,31 +265,55 @@ impl GraphqlClient {
             client,
             db,
             graphql_url,
+            page_size,
+            retrier: Arc::new(Retrier::new("GraphQL indexer", retry)),
+            limiter: None,
         }
     }
 
-    /// Execute a GraphQL query
+    /// Allows at most `limit` indexer requests at once, 0 leaves them unbounded.
+    pub fn with_concurrency_limit(mut self, limit: usize) -> Self {
+        self.limiter = (limit > 0).then(|| Arc::new(Semaphore::new(limit)));
+        self
+    }
+
+    /// Execute a GraphQL query, retrying transient failures according to the configured policy
     pub async fn execute_query<T>(&self, payload: GraphqlQuery) -> GraphqlResult<T>
     where
         T: for<'de> Deserialize<'de>,
     {
         debug!("Executing GraphQL query: {}", payload.query);
 
+        let data = self
+            .retrier
+            .run(GraphqlError::is_transient, || self.send_query(&payload))
+            .await?;
+
+        Ok(data)
+    }
+
+    async fn send_query<T>(&self, payload: &GraphqlQuery) -> GraphqlResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        // Held per attempt, so backoff between retries doesn't block other callers
+        let _permit = match &self.limiter {
+            Some(limiter) => limiter.acquire().await.ok(),
+            None => None,
+        };
+
         let response = self
             .client
             .post(&self.graphql_url)
             .header("Content-Type", "application/json")
-            .json(&payload)
+            .json(payload)
             .send()
             .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            return Err(GraphqlError::GraphqlResponseError(format!(
-                "HTTP {} - {}",
-                status, text
-            )));
+            return Err(GraphqlError::HttpStatus(status, text));
         }
 
         let graphql_response: GraphqlResponse<T> = response.json().await?;
@@ -130,35 +328,35 @@ impl GraphqlClient {
             .ok_or_else(|| GraphqlError::InvalidData("No data in GraphQL response".to_string()))
     }
 
-    /// Fetch transfers from the GraphQL endpoint
-    pub async fn fetch_transfers(&self) -> GraphqlResult<Vec<Transfer>> {
-        const TRANSFERS_QUERY: &str = r#"
-        {
-            transfers {
-                id
-                amount
-                from { id }
-                to { id }
-            }
-        }
-        "#;
+    /// Execute a typed GraphQL operation
+    pub async fn execute<O: GraphqlOperation>(&self, variables: O::Variables) -> GraphqlResult<O::ResponseData> {
+        debug!("Executing GraphQL operation: {}", O::NAME);
 
-        let payload = GraphqlQuery {
-            query: TRANSFERS_QUERY.to_string(),
-            variables: None,
-        };
+        let payload = GraphqlQuery::build::<O>(variables)?;
+        self.execute_query(payload).await
+    }
 
-        info!("Fetching transfers from GraphQL endpoint: {}", &self.graphql_url);
+    /// Fetch the page of transfers following `after`, or the first page if there is no cursor
+    pub async fn fetch_transfers_page(&self, after: Option<String>) -> GraphqlResult<TransfersPage> {
+        info!(
+            "Fetching transfers from GraphQL endpoint: {} (after: {:?})",
+            &self.graphql_url, after
+        );
 
-        let transfer_data: TransferData = self.execute_query(payload).await?;
+        let variables = TransfersPageVariables {
+            first: self.page_size,
+            after,
+        };
+        let page = TransfersPage::from(self.execute::<TransfersQuery>(variables).await?);
 
-        info!("Successfully fetched {} transfers", transfer_data.transfers.len());
-        debug!("Transfer data: {:?}", transfer_data.transfers);
+        info!("Successfully fetched {} transfers", page.transfers.len());
+        debug!("Transfer data: {:?}", page.transfers);
 
-        Ok(transfer_data.transfers)
+        Ok(page)
     }
 
-    pub async fn store_addresses_from_transfers(&self, transfers: &[Transfer]) -> GraphqlResult<u64> {
+    /// Builds the addresses seen in the transfers, with collision free referral codes
+    async fn prepare_addresses_from_transfers(&self, transfers: &[Transfer]) -> GraphqlResult<Vec<Address>> {
         let mut unique_addresses = std::collections::HashSet::new();
 
         for transfer in transfers {
@@ -171,25 +369,28 @@ impl GraphqlClient {
         let mut tasks = Vec::new();
 
         for addr in unique_addresses {
-            let task = tokio::spawn(async move {
-                if let Ok(referral_code) = generate_referral_code(addr.clone()).await {
-                    let input = AddressInput {
-                        quan_address: addr,
-                        referral_code,
-                    };
-                    Address::new(input).ok()
-                } else {
-                    None
+            let task = tokio::spawn(
+                async move {
+                    if let Ok(referral_code) = generate_referral_code(addr.clone()).await {
+                        let input = AddressInput {
+                            quan_address: addr,
+                            referral_code,
+                        };
+                        Address::new(input).ok()
+                    } else {
+                        None
+                    }
                 }
-            });
+                .in_current_span(),
+            );
             tasks.push(task);
         }
 
-        let mut addresses_to_store = Vec::new();
+        let mut generated = Vec::new();
         for task in tasks {
             match task.await {
                 // Task completed successfully
-                Ok(Some(address)) => addresses_to_store.push(address),
+                Ok(Some(address)) => generated.push((address.quan_address.0, address.referral_code)),
                 // Task completed but returned None (e.g., referral code failed)
                 Ok(None) => (),
                 // Task failed to complete (e.g., panicked)
@@ -197,37 +398,120 @@ impl GraphqlClient {
             }
         }
 
-        if addresses_to_store.is_empty() {
-            warn!("No valid addresses could be processed and stored");
-            return Ok(0);
-        }
+        // Checkphrase codes can collide, which would make the whole batch insert fail on the
+        // referral_code unique constraint, so collisions are repaired before storing.
+        let addresses_to_store: Vec<Address> = ReferralCodeService::repair_batch(&self.db.addresses, generated)
+            .await?
+            .into_iter()
+            .filter_map(|(quan_address, referral_code)| {
+                Address::new(AddressInput {
+                    quan_address,
+                    referral_code,
+                })
+                .ok()
+            })
+            .collect();
+
+        Ok(addresses_to_store)
+    }
 
-        debug!("Storing addresses in database: {:?}", addresses_to_store);
+    /// Drop transfers that a previous sync already ingested
+    pub async fn filter_unprocessed_transfers(&self, transfers: Vec<Transfer>) -> GraphqlResult<Vec<Transfer>> {
+        let ids: Vec<String> = transfers.iter().map(|t| t.id.clone()).collect();
+        let processed = self.db.processed_transfers.find_processed_ids(&ids).await?;
 
-        match self.db.addresses.create_many(addresses_to_store).await {
-            Ok(created_count) => {
-                info!("Successfully stored {} addresses in database", created_count);
-                Ok(created_count)
-            }
-            Err(err) => Err(GraphqlError::DatabaseError(err)),
+        if !processed.is_empty() {
+            info!("Skipping {} already processed transfers", processed.len());
         }
+
+        Ok(transfers.into_iter().filter(|t| !processed.contains(&t.id)).collect())
     }
 
-    /// Fetch transfers and store their addresses in one operation
+    /// Fetch transfers page by page and store their addresses, starting where the last sync stopped.
+    /// Each page is committed together with the sync checkpoint, so an interrupted sync resumes from the
+    /// last completed page. Transfers that were already ingested are skipped, which keeps re-syncs idempotent.
     pub async fn sync_transfers_and_addresses(&self) -> GraphqlResult<(usize, usize)> {
-        info!("Starting transfer sync process");
-
-        let transfers = self.fetch_transfers().await?;
-        let transfer_count = transfers.len();
-
-        let address_count = self.store_addresses_from_transfers(&transfers).await?;
+        let mut cursor = self.db.sync_state.find_cursor(TRANSFERS_SYNC).await?;
+        info!("Starting transfer sync process from cursor {:?}", cursor);
+
+        let mut transfer_count = 0;
+        let mut address_count = 0;
+
+        loop {
+            let page = self.fetch_transfers_page(cursor.clone()).await?;
+            // An empty last page has no end cursor, keep the previous one.
+            let next_cursor = page.page_info.end_cursor.clone().or_else(|| cursor.clone());
+            let has_next_page = page.page_info.has_next_page && next_cursor != cursor;
+            let stored_page = match (page.transfers.first(), page.transfers.last(), &next_cursor) {
+                (Some(first), Some(last), Some(end_cursor)) => Some(ProcessedTransferPage {
+                    after_cursor: cursor.clone(),
+                    end_cursor: end_cursor.clone(),
+                    first_transfer_id: first.id.clone(),
+                    last_transfer_id: last.id.clone(),
+                }),
+                _ => None,
+            };
+
+            let transfers = self.filter_unprocessed_transfers(page.transfers).await?;
+            let (transfers_stored, addresses_stored) = self
+                .store_page(&transfers, stored_page.as_ref(), next_cursor.as_deref())
+                .await?;
+            transfer_count += transfers_stored;
+            address_count += addresses_stored;
+
+            cursor = next_cursor;
+            if !has_next_page {
+                break;
+            }
+        }
 
         info!(
             "Sync completed: {} transfers processed, {} addresses stored",
             transfer_count, address_count
         );
 
-        Ok((transfer_count, address_count as usize))
+        Ok((transfer_count, address_count))
+    }
+
+    /// Writes the addresses and processed transfers of one page together with the page bounds and the new
+    /// checkpoint.
+    async fn store_page(
+        &self,
+        transfers: &[Transfer],
+        page: Option<&ProcessedTransferPage>,
+        cursor: Option<&str>,
+    ) -> GraphqlResult<(usize, usize)> {
+        let addresses_to_store = if transfers.is_empty() {
+            Vec::new()
+        } else {
+            let addresses = self.prepare_addresses_from_transfers(transfers).await?;
+            if addresses.is_empty() {
+                warn!("No valid addresses could be processed from the new transfers");
+            }
+            addresses
+        };
+
+        let processed = transfers
+            .iter()
+            .map(|t| ProcessedTransfer {
+                transfer_id: t.id.clone(),
+                from_address: t.from.id.clone(),
+                to_address: t.to.id.clone(),
+                amount: t.amount.clone(),
+                processed_at: None,
+            })
+            .collect();
+
+        let mut uow = self.db.begin().await?;
+        let address_count = AddressRepository::create_many_with(uow.conn(), addresses_to_store).await?;
+        ProcessedTransferRepository::create_many_with(uow.conn(), processed).await?;
+        if let Some(page) = page {
+            ProcessedTransferRepository::create_page_with(uow.conn(), page).await?;
+        }
+        SyncStateRepository::save_cursor_with(uow.conn(), TRANSFERS_SYNC, cursor).await?;
+        uow.commit().await?;
+
+        Ok((transfers.len(), address_count as usize))
     }
 }
 
@@ -263,34 +547,51 @@ mod tests {
         ]
     }
 
+    fn connection_json(nodes: &str, has_next_page: bool, end_cursor: Option<&str>) -> String {
+        let edges: Vec<serde_json::Value> = serde_json::from_str::<Vec<serde_json::Value>>(nodes)
+            .unwrap()
+            .into_iter()
+            .map(|node| serde_json::json!({ "node": node }))
+            .collect();
+
+        serde_json::json!({
+            "transfersConnection": {
+                "edges": edges,
+                "pageInfo": { "hasNextPage": has_next_page, "endCursor": end_cursor }
+            }
+        })
+        .to_string()
+    }
+
     #[test]
     fn test_transfer_deserialization_single() {
-        let json = r#"
-        {
-            "transfers": [
+        let json = connection_json(
+            r#"[
                 {
                     "id": "0x123",
                     "amount": "1000000000000000000",
                     "from": { "id": "0xabcdef123456" },
                     "to": { "id": "0x987654321abc" }
                 }
-            ]
-        }
-        "#;
+            ]"#,
+            false,
+            Some("1"),
+        );
 
-        let transfer_data: TransferData = serde_json::from_str(json).unwrap();
-        assert_eq!(transfer_data.transfers.len(), 1);
-        assert_eq!(transfer_data.transfers[0].id, "0x123");
-        assert_eq!(transfer_data.transfers[0].amount, "1000000000000000000");
-        assert_eq!(transfer_data.transfers[0].from.id, "0xabcdef123456");
-        assert_eq!(transfer_data.transfers[0].to.id, "0x987654321abc");
+        let page = TransfersPage::from(serde_json::from_str::<TransferData>(&json).unwrap());
+        assert_eq!(page.transfers.len(), 1);
+        assert_eq!(page.transfers[0].id, "0x123");
+        assert_eq!(page.transfers[0].amount, "1000000000000000000");
+        assert_eq!(page.transfers[0].from.id, "0xabcdef123456");
+        assert_eq!(page.transfers[0].to.id, "0x987654321abc");
+        assert!(!page.page_info.has_next_page);
+        assert_eq!(page.page_info.end_cursor.as_deref(), Some("1"));
     }
 
     #[test]
     fn test_transfer_deserialization_multiple() {
-        let json = r#"
-        {
-            "transfers": [
+        let json = connection_json(
+            r#"[
                 {
                     "id": "0x123",
                     "amount": "1000000000000000000",
@@ -303,27 +604,30 @@ mod tests {
                     "from": { "id": "0x111" },
                     "to": { "id": "0x222" }
                 }
-            ]
-        }
-        "#;
+            ]"#,
+            true,
+            Some("2"),
+        );
 
-        let transfer_data: TransferData = serde_json::from_str(json).unwrap();
-        assert_eq!(transfer_data.transfers.len(), 2);
-        assert_eq!(transfer_data.transfers[0].id, "0x123");
-        assert_eq!(transfer_data.transfers[1].id, "0x456");
+        let page = TransfersPage::from(serde_json::from_str::<TransferData>(&json).unwrap());
+        assert_eq!(page.transfers.len(), 2);
+        assert_eq!(page.transfers[0].id, "0x123");
+        assert_eq!(page.transfers[1].id, "0x456");
+        assert!(page.page_info.has_next_page);
     }
 
     #[test]
     fn test_transfer_deserialization_empty() {
-        let json = r#"{"transfers": []}"#;
-        let transfer_data: TransferData = serde_json::from_str(json).unwrap();
-        assert_eq!(transfer_data.transfers.len(), 0);
+        let json = connection_json("[]", false, None);
+        let page = TransfersPage::from(serde_json::from_str::<TransferData>(&json).unwrap());
+        assert_eq!(page.transfers.len(), 0);
+        assert!(page.page_info.end_cursor.is_none());
     }
 
     #[test]
     fn test_transfer_deserialization_invalid_json() {
-        let json = r#"{"transfers": [{"id": "0x123"}]}"#; // Missing required fields
-        let result: Result<TransferData, _> = serde_json::from_str(json);
+        let json = connection_json(r#"[{"id": "0x123"}]"#, false, None); // Missing required fields
+        let result: Result<TransferData, _> = serde_json::from_str(&json);
         assert!(result.is_err());
     }
 
@@ -368,6 +672,85 @@ mod tests {
         assert!(json.contains("offset"));
     }
 
+    #[test]
+    fn test_graphql_query_build_without_variables() {
+        struct AllTransfersQuery;
+        impl GraphqlOperation for AllTransfersQuery {
+            type Variables = ();
+            type ResponseData = TransferData;
+
+            const NAME: &'static str = "all_transfers";
+            const QUERY: &'static str = "{ transfers { id } }";
+        }
+
+        let query = GraphqlQuery::build::<AllTransfersQuery>(()).unwrap();
+
+        assert_eq!(query.query, AllTransfersQuery::QUERY);
+        assert!(query.variables.is_none());
+
+        let json = serde_json::to_string(&query).unwrap();
+        assert!(!json.contains("variables"));
+    }
+
+    #[test]
+    fn test_graphql_query_build_with_variables() {
+        #[derive(Serialize)]
+        struct PageVariables {
+            limit: u32,
+            offset: u32,
+        }
+
+        struct PagedTransfersQuery;
+        impl GraphqlOperation for PagedTransfersQuery {
+            type Variables = PageVariables;
+            type ResponseData = TransferData;
+
+            const NAME: &'static str = "paged_transfers";
+            const QUERY: &'static str =
+                "query($limit: Int, $offset: Int) { transfers(limit: $limit, offset: $offset) { id } }";
+        }
+
+        let query = GraphqlQuery::build::<PagedTransfersQuery>(PageVariables { limit: 10, offset: 0 }).unwrap();
+
+        let variables = query.variables.unwrap();
+        assert_eq!(variables["limit"], serde_json::json!(10));
+        assert_eq!(variables["offset"], serde_json::json!(0));
+    }
+
+    #[test]
+    fn test_graphql_query_build_rejects_non_object_variables() {
+        struct BadQuery;
+        impl GraphqlOperation for BadQuery {
+            type Variables = u32;
+            type ResponseData = TransferData;
+
+            const NAME: &'static str = "bad";
+            const QUERY: &'static str = "{ transfers { id } }";
+        }
+
+        let result = GraphqlQuery::build::<BadQuery>(5);
+        assert!(matches!(result, Err(GraphqlError::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_transfers_query_selects_response_fields() {
+        // The document must select every field TransferData deserializes.
+        for field in [
+            "transfersConnection",
+            "edges",
+            "node",
+            "id",
+            "amount",
+            "from",
+            "to",
+            "pageInfo",
+            "hasNextPage",
+            "endCursor",
+        ] {
+            assert!(TransfersQuery::QUERY.contains(field), "missing field {}", field);
+        }
+    }
+
     #[test]
     fn test_graphql_query_roundtrip() {
         let original = GraphqlQuery {
@@ -388,25 +771,19 @@ mod tests {
 
     #[test]
     fn test_graphql_response_with_data() {
-        let json = r#"{
-            "data": {
-                "transfers": [
-                    {
-                        "id": "0x123",
-                        "amount": "1000",
-                        "from": { "id": "0xabc" },
-                        "to": { "id": "0xdef" }
-                    }
-                ]
-            }
-        }"#;
+        let data = connection_json(
+            r#"[{ "id": "0x123", "amount": "1000", "from": { "id": "0xabc" }, "to": { "id": "0xdef" } }]"#,
+            false,
+            Some("1"),
+        );
+        let json = format!(r#"{{ "data": {} }}"#, data);
 
-        let response: GraphqlResponse<TransferData> = serde_json::from_str(json).unwrap();
+        let response: GraphqlResponse<TransferData> = serde_json::from_str(&json).unwrap();
         assert!(response.data.is_some());
         assert!(response.errors.is_none());
 
-        let data = response.data.unwrap();
-        assert_eq!(data.transfers.len(), 1);
+        let page = TransfersPage::from(response.data.unwrap());
+        assert_eq!(page.transfers.len(), 1);
     }
 
     #[test]
@@ -704,4 +1081,152 @@ mod tests {
         let json = serde_json::to_string(&query).unwrap();
         assert!(json.contains("amount_gt"));
     }
+
+    // ============================================================================
+    // Sync Idempotency Tests
+    // ============================================================================
+
+    #[tokio::test]
+    async fn test_sync_skips_processed_transfers() {
+        use crate::utils::test_app_state::create_test_app_state;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let state = create_test_app_state().await;
+
+        let server = MockServer::start().await;
+        let body = serde_json::json!({
+            "data": {
+                "transfersConnection": {
+                    "edges": [{
+                        "node": {
+                            "id": "0000000001-abcde-000001",
+                            "amount": "1000",
+                            "from": { "id": "qz_sync_test_sender_01" },
+                            "to": { "id": "qz_sync_test_receiver_01" }
+                        }
+                    }],
+                    "pageInfo": { "hasNextPage": false, "endCursor": "1" }
+                }
+            }
+        });
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+
+        let client = GraphqlClient::new(
+            (*state.db).clone(),
+            server.uri(),
+            state.config.candidates.sync_page_size,
+            state.config.retry.graphql.clone(),
+        );
+
+        let (transfer_count, address_count) = client.sync_transfers_and_addresses().await.unwrap();
+        assert_eq!((transfer_count, address_count), (1, 2));
+
+        let (transfer_count, address_count) = client.sync_transfers_and_addresses().await.unwrap();
+        assert_eq!((transfer_count, address_count), (0, 0));
+
+        let ingested = state
+            .db
+            .processed_transfers
+            .find_by_id("0000000001-abcde-000001")
+            .await
+            .unwrap();
+        assert!(ingested.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_execute_retries_transient_http_errors() {
+        use crate::utils::test_app_state::create_test_app_state;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let state = create_test_app_state().await;
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "transfersConnection": {
+                        "edges": [],
+                        "pageInfo": { "hasNextPage": false, "endCursor": null }
+                    }
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = GraphqlClient::new(
+            (*state.db).clone(),
+            server.uri(),
+            state.config.candidates.sync_page_size,
+            state.config.retry.graphql.clone(),
+        );
+        assert!(client.fetch_transfers_page(None).await.unwrap().transfers.is_empty());
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = GraphqlClient::new(
+            (*state.db).clone(),
+            server.uri(),
+            state.config.candidates.sync_page_size,
+            state.config.retry.graphql.clone(),
+        );
+        let err = client.fetch_transfers_page(None).await.unwrap_err();
+        assert!(matches!(err, GraphqlError::HttpStatus(status, _) if status == reqwest::StatusCode::BAD_REQUEST));
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_queues_requests() {
+        use crate::utils::test_app_state::create_test_app_state;
+        use std::time::{Duration, Instant};
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let state = create_test_app_state().await;
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "data": {
+                            "transfersConnection": {
+                                "edges": [],
+                                "pageInfo": { "hasNextPage": false, "endCursor": null }
+                            }
+                        }
+                    }))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = GraphqlClient::new(
+            (*state.db).clone(),
+            server.uri(),
+            state.config.candidates.sync_page_size,
+            state.config.retry.graphql.clone(),
+        )
+        .with_concurrency_limit(1);
+
+        let started = Instant::now();
+        let (first, second) = tokio::join!(client.fetch_transfers_page(None), client.fetch_transfers_page(None));
+        first.unwrap();
+        second.unwrap();
+
+        // With a single slot the second request only starts once the first one is answered
+        assert!(started.elapsed() >= Duration::from_millis(400));
+    }
 }
@@ -0,0 +1,528 @@
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use sqlx::PgPool;
+use tokio::{sync::RwLock, task::JoinSet};
+
+use crate::services::{exchange_rate_service::ExchangeRateService, risk_checker_service::RiskCheckerService, runbook};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Reports are reused for this long so that polling the health endpoint doesn't hammer integrations.
+const REPORT_TTL: Duration = Duration::from_secs(10);
+
+/// What a failing integration means for the service as a whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DegradationPolicy {
+    /// The service can't do its job without it.
+    Critical,
+    /// Some features stop working, the rest of the service is fine.
+    Degraded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// Health check for one outbound integration.
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn policy(&self) -> DegradationPolicy;
+    /// Runbook code reported when the check fails.
+    fn failure_code(&self) -> &'static str;
+    async fn check(&self) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrationHealth {
+    pub name: &'static str,
+    pub policy: DegradationPolicy,
+    pub healthy: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    pub code: Option<&'static str>,
+    pub remediation: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub status: ServiceStatus,
+    pub checked_at: DateTime<Utc>,
+    pub integrations: Vec<IntegrationHealth>,
+}
+
+impl HealthReport {
+    fn from_results(integrations: Vec<IntegrationHealth>) -> Self {
+        let failing = |policy| integrations.iter().any(|i| !i.healthy && i.policy == policy);
+
+        let status = if failing(DegradationPolicy::Critical) {
+            ServiceStatus::Unhealthy
+        } else if failing(DegradationPolicy::Degraded) {
+            ServiceStatus::Degraded
+        } else {
+            ServiceStatus::Healthy
+        };
+
+        Self {
+            status,
+            checked_at: Utc::now(),
+            integrations,
+        }
+    }
+}
+
+/// Single place where outbound integrations register their health probes. Anything that needs to know
+/// whether an integration is up should read from here instead of running its own checks.
+#[derive(Default)]
+pub struct HealthRegistry {
+    probes: Vec<Arc<dyn HealthProbe>>,
+    last_report: RwLock<Option<(Instant, HealthReport)>>,
+}
+
+impl std::fmt::Debug for HealthRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HealthRegistry")
+            .field("probes", &self.probes.iter().map(|p| p.name()).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl HealthRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the integrations every deployment has.
+    pub fn with_default_probes(
+        pool: PgPool,
+        graphql_url: String,
+        twitter_calls: Arc<LastCallOutcome>,
+        exchange_rates: Arc<ExchangeRateService>,
+        risk_checker: Arc<RiskCheckerService>,
+    ) -> Self {
+        let mut registry = Self::new();
+        registry.register(DatabaseProbe { pool });
+        registry.register(IndexerProbe::new(graphql_url));
+        registry.register(TwitterProbe { calls: twitter_calls });
+        registry.register(ExchangeRateProbe {
+            service: exchange_rates,
+        });
+        registry.register(EtherscanProbe { service: risk_checker });
+        registry
+    }
+
+    pub fn register(&mut self, probe: impl HealthProbe + 'static) {
+        self.probes.push(Arc::new(probe));
+    }
+
+    /// Runs all probes concurrently, or returns the previous report if it is recent enough.
+    pub async fn report(&self) -> HealthReport {
+        if let Some((at, report)) = self.last_report.read().await.as_ref() {
+            if at.elapsed() < REPORT_TTL {
+                return report.clone();
+            }
+        }
+
+        let report = self.check(|_| true).await;
+        *self.last_report.write().await = Some((Instant::now(), report.clone()));
+
+        report
+    }
+
+    /// Runs only the critical probes, uncached. Readiness depends on nothing else.
+    pub async fn critical_report(&self) -> HealthReport {
+        self.check(|probe| probe.policy() == DegradationPolicy::Critical).await
+    }
+
+    async fn check(&self, include: impl Fn(&dyn HealthProbe) -> bool) -> HealthReport {
+        let mut checks = JoinSet::new();
+
+        let probes = self.probes.iter().filter(|probe| include(probe.as_ref()));
+        for (index, probe) in probes.cloned().enumerate() {
+            checks.spawn(async move {
+                let started = Instant::now();
+                let (result, code) = match tokio::time::timeout(PROBE_TIMEOUT, probe.check()).await {
+                    Ok(result) => (result, probe.failure_code()),
+                    Err(_) => (
+                        Err(format!("Timed out after {}s", PROBE_TIMEOUT.as_secs())),
+                        runbook::PROBE_TIMEOUT,
+                    ),
+                };
+                let code = result.is_err().then_some(code);
+
+                if let Err(e) = &result {
+                    tracing::warn!("Health probe '{}' failed: {}", probe.name(), e);
+                }
+
+                let health = IntegrationHealth {
+                    name: probe.name(),
+                    policy: probe.policy(),
+                    healthy: result.is_ok(),
+                    latency_ms: started.elapsed().as_millis() as u64,
+                    error: result.err(),
+                    code,
+                    remediation: code.and_then(runbook::lookup).map(|entry| entry.remediation),
+                };
+                (index, health)
+            });
+        }
+
+        let mut results = Vec::with_capacity(self.probes.len());
+        while let Some(joined) = checks.join_next().await {
+            match joined {
+                Ok(result) => results.push(result),
+                Err(e) => tracing::error!("Health probe task failed: {}", e),
+            }
+        }
+        // Keep registration order so the output is stable.
+        results.sort_by_key(|(index, _)| *index);
+
+        HealthReport::from_results(results.into_iter().map(|(_, health)| health).collect())
+    }
+}
+
+pub struct DatabaseProbe {
+    pool: PgPool,
+}
+
+#[async_trait]
+impl HealthProbe for DatabaseProbe {
+    fn name(&self) -> &'static str {
+        "database"
+    }
+
+    fn policy(&self) -> DegradationPolicy {
+        DegradationPolicy::Critical
+    }
+
+    fn failure_code(&self) -> &'static str {
+        runbook::DATABASE_UNREACHABLE
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// The chain indexer, only needed for transfer syncs.
+pub struct IndexerProbe {
+    client: Client,
+    graphql_url: String,
+}
+
+impl IndexerProbe {
+    pub fn new(graphql_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            graphql_url,
+        }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for IndexerProbe {
+    fn name(&self) -> &'static str {
+        "indexer"
+    }
+
+    fn policy(&self) -> DegradationPolicy {
+        DegradationPolicy::Degraded
+    }
+
+    fn failure_code(&self) -> &'static str {
+        runbook::INDEXER_UNREACHABLE
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        let response = self
+            .client
+            .post(&self.graphql_url)
+            .json(&serde_json::json!({ "query": "{ __typename }" }))
+            .send()
+            .await
+            .map_err(|e| e.without_url().to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", response.status()))
+        }
+    }
+}
+
+/// Outcome of the most recent call to an integration, for probes that must not call it themselves.
+#[derive(Debug, Default)]
+pub struct LastCallOutcome {
+    error: Mutex<Option<String>>,
+}
+
+impl LastCallOutcome {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record<T, E: Display>(&self, result: &Result<T, E>) {
+        *self.error.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            result.as_ref().err().map(|e| e.to_string());
+    }
+
+    /// The error of the last call, `Ok` if it succeeded or nothing was called yet.
+    fn outcome(&self) -> Result<(), String> {
+        match self
+            .error
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+        {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The X API, only needed for tweet syncs and adding tweet authors. Every X call counts against the API quota,
+/// so the probe reports how the last real call went instead of calling X.
+pub struct TwitterProbe {
+    calls: Arc<LastCallOutcome>,
+}
+
+#[async_trait]
+impl HealthProbe for TwitterProbe {
+    fn name(&self) -> &'static str {
+        "twitter"
+    }
+
+    fn policy(&self) -> DegradationPolicy {
+        DegradationPolicy::Degraded
+    }
+
+    fn failure_code(&self) -> &'static str {
+        runbook::TWITTER_UNREACHABLE
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        self.calls.outcome()
+    }
+}
+
+/// The exchange rate API, rates keep being served from the last snapshot while it is down.
+pub struct ExchangeRateProbe {
+    service: Arc<ExchangeRateService>,
+}
+
+#[async_trait]
+impl HealthProbe for ExchangeRateProbe {
+    fn name(&self) -> &'static str {
+        "exchange_rate"
+    }
+
+    fn policy(&self) -> DegradationPolicy {
+        DegradationPolicy::Degraded
+    }
+
+    fn failure_code(&self) -> &'static str {
+        runbook::EXCHANGE_RATE_UNREACHABLE
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        self.service.check_reachable().await.map_err(|e| e.to_string())
+    }
+}
+
+/// Etherscan, used by the risk checker.
+pub struct EtherscanProbe {
+    service: Arc<RiskCheckerService>,
+}
+
+#[async_trait]
+impl HealthProbe for EtherscanProbe {
+    fn name(&self) -> &'static str {
+        "etherscan"
+    }
+
+    fn policy(&self) -> DegradationPolicy {
+        DegradationPolicy::Degraded
+    }
+
+    fn failure_code(&self) -> &'static str {
+        runbook::ETHERSCAN_UNREACHABLE
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        self.service.check_reachable().await.map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    struct StaticProbe {
+        name: &'static str,
+        policy: DegradationPolicy,
+        healthy: bool,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl HealthProbe for StaticProbe {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn policy(&self) -> DegradationPolicy {
+            self.policy
+        }
+
+        fn failure_code(&self) -> &'static str {
+            runbook::INDEXER_ERROR
+        }
+
+        async fn check(&self) -> Result<(), String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.healthy {
+                Ok(())
+            } else {
+                Err("down".to_string())
+            }
+        }
+    }
+
+    fn probe(name: &'static str, policy: DegradationPolicy, healthy: bool) -> StaticProbe {
+        StaticProbe {
+            name,
+            policy,
+            healthy,
+            calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_follows_policies() {
+        let mut registry = HealthRegistry::new();
+        registry.register(probe("a", DegradationPolicy::Critical, true));
+        registry.register(probe("b", DegradationPolicy::Degraded, false));
+        assert_eq!(registry.report().await.status, ServiceStatus::Degraded);
+
+        let mut registry = HealthRegistry::new();
+        registry.register(probe("a", DegradationPolicy::Critical, false));
+        registry.register(probe("b", DegradationPolicy::Degraded, true));
+        let report = registry.report().await;
+        assert_eq!(report.status, ServiceStatus::Unhealthy);
+        assert_eq!(report.integrations[0].name, "a");
+        assert_eq!(report.integrations[0].error.as_deref(), Some("down"));
+        assert_eq!(report.integrations[0].code, Some(runbook::INDEXER_ERROR));
+        assert!(report.integrations[0].remediation.is_some());
+        assert_eq!(report.integrations[1].code, None);
+    }
+
+    #[tokio::test]
+    async fn test_report_is_cached() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut registry = HealthRegistry::new();
+        registry.register(StaticProbe {
+            calls: calls.clone(),
+            ..probe("a", DegradationPolicy::Critical, true)
+        });
+
+        registry.report().await;
+        registry.report().await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_critical_report_skips_other_probes() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut registry = HealthRegistry::new();
+        registry.register(probe("a", DegradationPolicy::Critical, true));
+        registry.register(StaticProbe {
+            calls: calls.clone(),
+            ..probe("b", DegradationPolicy::Degraded, false)
+        });
+
+        let report = registry.critical_report().await;
+        assert_eq!(report.status, ServiceStatus::Healthy);
+        assert_eq!(report.integrations.len(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_twitter_probe_reports_the_last_call() {
+        let calls = Arc::new(LastCallOutcome::new());
+        let probe = TwitterProbe { calls: calls.clone() };
+        assert!(probe.check().await.is_ok());
+
+        calls.record::<(), _>(&Err("rate limited"));
+        assert_eq!(probe.check().await, Err("rate limited".to_string()));
+
+        calls.record::<_, String>(&Ok(()));
+        assert!(probe.check().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_indexer_probe() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": {"__typename": "Query"}})),
+            )
+            .mount(&server)
+            .await;
+
+        let probe = IndexerProbe::new(format!("{}/graphql", server.uri()));
+        assert!(probe.check().await.is_ok());
+
+        let probe = IndexerProbe::new(format!("{}/missing", server.uri()));
+        assert!(probe.check().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_etherscan_probe() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x10"})),
+            )
+            .mount(&server)
+            .await;
+
+        let probe = EtherscanProbe {
+            service: Arc::new(RiskCheckerService::new_with_base_url(
+                &server.uri(),
+                "http://unused-infura",
+            )),
+        };
+        assert!(probe.check().await.is_ok());
+
+        let probe = EtherscanProbe {
+            service: Arc::new(RiskCheckerService::new_with_base_url(
+                "http://127.0.0.1:1",
+                "http://unused-infura",
+            )),
+        };
+        assert!(probe.check().await.is_err());
+    }
+}
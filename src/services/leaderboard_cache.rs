@@ -0,0 +1,270 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use sha2::{Digest, Sha256};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    models::raid_team::{TeamLeaderboardEntry, TeamScoreNormalization},
+    repositories::raid_team::RaidTeamRepository,
+    services::{
+        event_bus::{EventBus, LiveEvent},
+        settings_service::SettingsService,
+    },
+};
+
+/// How often the refresh task wakes up while caching is disabled, to notice it being turned back on.
+const DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A team leaderboard request: raid, normalization and limit.
+pub type LeaderboardKey = (i32, TeamScoreNormalization, u32);
+
+#[derive(Debug, Clone)]
+pub struct CachedLeaderboard {
+    pub entries: Vec<TeamLeaderboardEntry>,
+    /// Quoted strong validator of `entries`, for `ETag`/`If-None-Match`.
+    pub etag: String,
+    cached_at: Instant,
+}
+
+#[derive(Debug)]
+struct Slot {
+    leaderboard: CachedLeaderboard,
+    last_read: Instant,
+}
+
+/// Team leaderboards kept in process memory, so polling clients don't rerun the ranking query. Entries expire
+/// after the configured TTL and are dropped early when [`invalidate_on_events`] sees their raid change.
+/// [`refresh_in_background`] recomputes the ones still being read before they expire.
+#[derive(Debug, Default)]
+pub struct LeaderboardCache {
+    leaderboards: Mutex<HashMap<LeaderboardKey, Slot>>,
+}
+
+impl LeaderboardCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached leaderboard for `key` if it is younger than `ttl`.
+    pub fn get(&self, key: &LeaderboardKey, ttl: Duration, now: Instant) -> Option<CachedLeaderboard> {
+        let mut leaderboards = self
+            .leaderboards
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let slot = leaderboards
+            .get_mut(key)
+            .filter(|slot| now.saturating_duration_since(slot.leaderboard.cached_at) < ttl)?;
+        slot.last_read = now;
+
+        Some(slot.leaderboard.clone())
+    }
+
+    pub fn insert(&self, key: LeaderboardKey, entries: Vec<TeamLeaderboardEntry>, now: Instant) -> CachedLeaderboard {
+        let cached = CachedLeaderboard {
+            etag: etag(&entries),
+            entries,
+            cached_at: now,
+        };
+
+        let mut leaderboards = self
+            .leaderboards
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        leaderboards.insert(
+            key,
+            Slot {
+                leaderboard: cached.clone(),
+                last_read: now,
+            },
+        );
+
+        cached
+    }
+
+    /// Leaderboards read within the last `ttl` that are past half of it, with when they were computed. Those
+    /// nobody read for a whole `ttl` are dropped instead.
+    pub fn due_for_refresh(&self, ttl: Duration, now: Instant) -> Vec<(LeaderboardKey, Instant)> {
+        let mut leaderboards = self
+            .leaderboards
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        leaderboards.retain(|_, slot| now.saturating_duration_since(slot.last_read) < ttl);
+
+        leaderboards
+            .iter()
+            .filter(|(_, slot)| now.saturating_duration_since(slot.leaderboard.cached_at) >= ttl / 2)
+            .map(|(key, slot)| (*key, slot.leaderboard.cached_at))
+            .collect()
+    }
+
+    /// Replaces the leaderboard computed at `cached_at` with `entries`. Does nothing if it was invalidated or
+    /// replaced meanwhile, since `entries` may predate that change.
+    pub fn refresh(&self, key: &LeaderboardKey, cached_at: Instant, entries: Vec<TeamLeaderboardEntry>, now: Instant) {
+        let mut leaderboards = self
+            .leaderboards
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(slot) = leaderboards.get_mut(key) {
+            if slot.leaderboard.cached_at == cached_at {
+                slot.leaderboard = CachedLeaderboard {
+                    etag: etag(&entries),
+                    entries,
+                    cached_at: now,
+                };
+            }
+        }
+    }
+
+    pub fn invalidate_raid(&self, raid_id: i32) {
+        let mut leaderboards = self
+            .leaderboards
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        leaderboards.retain(|(cached_raid_id, _, _), _| *cached_raid_id != raid_id);
+    }
+
+    pub fn clear(&self) {
+        let mut leaderboards = self
+            .leaderboards
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        leaderboards.clear();
+    }
+}
+
+fn etag(entries: &[TeamLeaderboardEntry]) -> String {
+    let body = serde_json::to_vec(entries).unwrap_or_default();
+    let digest = Sha256::digest(&body);
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+/// Drops the cached leaderboards of a raid as soon as its teams or quest change. Missed events clear the whole
+/// cache.
+pub async fn invalidate_on_events(cache: Arc<LeaderboardCache>, events: EventBus, token: CancellationToken) {
+    let mut receiver = events.subscribe();
+
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(LiveEvent::LeaderboardChanged { raid_id }) | Ok(LiveEvent::RaidQuestChanged { raid_id, .. }) => {
+                    cache.invalidate_raid(raid_id)
+                }
+                Err(RecvError::Lagged(_)) => cache.clear(),
+                Err(RecvError::Closed) => return,
+            },
+            _ = token.cancelled() => return,
+        }
+    }
+}
+
+/// Recomputes the leaderboards clients keep polling before they expire, so their requests don't wait on the
+/// ranking query.
+pub async fn refresh_in_background(
+    cache: Arc<LeaderboardCache>,
+    raid_teams: RaidTeamRepository,
+    settings: Arc<SettingsService>,
+    token: CancellationToken,
+) {
+    loop {
+        let ttl = Duration::from_secs(settings.current().leaderboard_cache.ttl_seconds);
+        let interval = if ttl.is_zero() {
+            DISABLED_POLL_INTERVAL
+        } else {
+            (ttl / 2).max(Duration::from_secs(1))
+        };
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = token.cancelled() => return,
+        }
+        if ttl.is_zero() {
+            cache.clear();
+            continue;
+        }
+
+        for ((raid_id, normalization, limit), cached_at) in cache.due_for_refresh(ttl, Instant::now()) {
+            match raid_teams.find_leaderboard(raid_id, normalization, limit).await {
+                Ok(entries) => cache.refresh(&(raid_id, normalization, limit), cached_at, entries, Instant::now()),
+                Err(e) => tracing::warn!("Failed to refresh the team leaderboard of raid {}: {}", raid_id, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(team_id: i32, score: f64) -> TeamLeaderboardEntry {
+        TeamLeaderboardEntry {
+            rank: 1,
+            team_id,
+            name: format!("Team {}", team_id),
+            member_count: 1,
+            total_submissions: 1,
+            total_impressions: score as i64,
+            total_replies: 0,
+            total_retweets: 0,
+            total_likes: 0,
+            score,
+        }
+    }
+
+    #[test]
+    fn test_cache_expires_and_invalidates_per_raid() {
+        let cache = LeaderboardCache::new();
+        let ttl = Duration::from_secs(10);
+        let start = Instant::now();
+        let raid_1 = (1, TeamScoreNormalization::None, 50);
+        let raid_2 = (2, TeamScoreNormalization::None, 50);
+
+        let cached = cache.insert(raid_1, vec![entry(1, 100.0)], start);
+        cache.insert(raid_2, vec![entry(2, 100.0)], start);
+        assert_eq!(cache.get(&raid_1, ttl, start).unwrap().etag, cached.etag);
+        assert!(cache.get(&raid_1, ttl, start + ttl).is_none());
+
+        cache.invalidate_raid(1);
+        assert!(cache.get(&raid_1, ttl, start).is_none());
+        assert!(cache.get(&raid_2, ttl, start).is_some());
+
+        // Same rankings give the same validator
+        let changed = cache.insert(raid_1, vec![entry(1, 200.0)], start);
+        assert_ne!(changed.etag, cached.etag);
+        assert_eq!(cache.insert(raid_1, vec![entry(1, 100.0)], start).etag, cached.etag);
+    }
+
+    #[test]
+    fn test_refresh_keeps_read_leaderboards_warm() {
+        let cache = LeaderboardCache::new();
+        let ttl = Duration::from_secs(10);
+        let start = Instant::now();
+        let read = (1, TeamScoreNormalization::None, 50);
+        let unread = (2, TeamScoreNormalization::None, 50);
+
+        cache.insert(read, vec![entry(1, 100.0)], start);
+        cache.insert(unread, vec![entry(2, 100.0)], start);
+        assert!(cache.due_for_refresh(ttl, start).is_empty());
+
+        let later = start + Duration::from_secs(6);
+        cache.get(&read, ttl, later).unwrap();
+        let due = cache.due_for_refresh(ttl, start + ttl);
+        assert_eq!(due, vec![(read, start)]);
+        assert!(cache.get(&unread, ttl, start).is_none());
+
+        cache.refresh(&read, start, vec![entry(1, 200.0)], start + ttl);
+        let refreshed = cache.get(&read, ttl, start + ttl).unwrap();
+        assert_eq!(refreshed.entries[0].score, 200.0);
+
+        // A leaderboard invalidated while it was being recomputed stays out
+        cache.invalidate_raid(1);
+        cache.refresh(&read, start + ttl, vec![entry(1, 300.0)], start + ttl);
+        assert!(cache.get(&read, ttl, start + ttl).is_none());
+    }
+}
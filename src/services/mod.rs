@@ -1,5 +1,19 @@
+pub mod activity_feed;
+pub mod address_import;
+pub mod challenge_store;
+pub mod event_bus;
 pub mod exchange_rate_service;
+pub mod fraud_rules;
 pub mod graphql_client;
+pub mod health_registry;
+pub mod leaderboard_cache;
+pub mod raid_payout;
+pub mod referral_code_service;
+pub mod referral_rewards;
 pub mod risk_checker_service;
+pub mod runbook;
+pub mod secrets;
+pub mod settings_service;
 pub mod signature_service;
+pub mod sybil_detector;
 pub mod wallet_config_service;
@@ -0,0 +1,246 @@
+use sqlx::PgConnection;
+
+use crate::{
+    config::{PayoutCurve, RaidPayoutConfig},
+    db_persistence::DbError,
+    models::raid_payout::{RaidPayoutShare, RaiderScore},
+    repositories::{raid_payout::RaidPayoutRepository, raid_quest::RaidQuestRepository, DbResult},
+};
+
+/// Proportional shares use scores with this many decimals, so the split can stay in integer math.
+const SCORE_PRECISION: f64 = 1_000_000.0;
+
+/// Splits a raid's prize pool among its top raiders when the raid is finished. Payouts are recorded for the
+/// payout run and marked paid afterwards, the same way as referral rewards.
+#[derive(Debug, Clone)]
+pub struct RaidPayoutService {
+    repository: RaidPayoutRepository,
+    config: RaidPayoutConfig,
+}
+
+impl RaidPayoutService {
+    pub fn new(repository: RaidPayoutRepository, config: RaidPayoutConfig) -> Self {
+        Self { repository, config }
+    }
+
+    /// Payouts the raid would get from its current leaderboard. Empty when raid payouts are disabled.
+    pub async fn preview(&self, raid_id: i32) -> DbResult<Vec<RaidPayoutShare>> {
+        if !self.is_enabled() {
+            return Ok(Vec::new());
+        }
+
+        let raiders = self.repository.find_top_raiders(raid_id, self.config.winners).await?;
+
+        Ok(split_prize_pool(self.config.prize_pool, self.config.curve, &raiders))
+    }
+
+    /// Finishes the raid and records its payouts on `conn`, which should be a
+    /// [`crate::db_persistence::UnitOfWork`] so a failed split leaves the raid running. Unpaid payouts of an
+    /// earlier finish are replaced. Returns the number of payouts recorded.
+    pub async fn finish(&self, conn: &mut PgConnection, raid_id: i32) -> DbResult<u64> {
+        // Updating the raid first locks it against a concurrent finish or revert
+        RaidQuestRepository::finish_with(&mut *conn, raid_id).await?;
+        ensure_nothing_paid(&mut *conn, raid_id).await?;
+
+        let shares = if self.is_enabled() {
+            let raiders = RaidPayoutRepository::find_top_raiders_with(&mut *conn, raid_id, self.config.winners).await?;
+            split_prize_pool(self.config.prize_pool, self.config.curve, &raiders)
+        } else {
+            Vec::new()
+        };
+        let created = RaidPayoutRepository::replace_unpaid_with(conn, raid_id, &shares).await?;
+
+        if created > 0 {
+            tracing::info!("Recorded {} payouts for raid {}", created, raid_id);
+        }
+
+        Ok(created)
+    }
+
+    /// Makes a finished raid active again and drops its unpaid payouts, they are split again when it's finished.
+    pub async fn reactivate(&self, conn: &mut PgConnection, raid_id: i32) -> DbResult<()> {
+        RaidQuestRepository::make_active_with(&mut *conn, raid_id).await?;
+        ensure_nothing_paid(&mut *conn, raid_id).await?;
+        RaidPayoutRepository::delete_unpaid_with(conn, raid_id).await?;
+
+        Ok(())
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.prize_pool > 0 && self.config.winners > 0
+    }
+}
+
+/// Once a payout went out the split is final, the raid can't be finished or reverted again.
+async fn ensure_nothing_paid(conn: &mut PgConnection, raid_id: i32) -> DbResult<()> {
+    if RaidPayoutRepository::has_paid_with(conn, raid_id).await? {
+        return Err(DbError::PayoutsAlreadyPaid(raid_id));
+    }
+
+    Ok(())
+}
+
+/// Splits `prize_pool` among `raiders`, which are ranked best first. Amounts are rounded down and the rounding
+/// remainder goes to first place, so the shares always add up to the pool.
+pub fn split_prize_pool(prize_pool: u64, curve: PayoutCurve, raiders: &[RaiderScore]) -> Vec<RaidPayoutShare> {
+    if raiders.is_empty() {
+        return Vec::new();
+    }
+
+    let count = raiders.len() as u128;
+    let mut weights: Vec<u128> = raiders
+        .iter()
+        .enumerate()
+        .map(|(i, raider)| match curve {
+            PayoutCurve::Equal => 1,
+            PayoutCurve::Linear => count - i as u128,
+            PayoutCurve::Proportional => (raider.score.max(0.0) * SCORE_PRECISION).round() as u128,
+        })
+        .collect();
+
+    // Scores too small to register fall back to an equal split
+    if weights.iter().sum::<u128>() == 0 {
+        weights.iter_mut().for_each(|w| *w = 1);
+    }
+    let total_weight: u128 = weights.iter().sum();
+
+    let mut amounts: Vec<u128> = weights.iter().map(|w| prize_pool as u128 * w / total_weight).collect();
+    amounts[0] += prize_pool as u128 - amounts.iter().sum::<u128>();
+
+    raiders
+        .iter()
+        .zip(amounts)
+        .enumerate()
+        .map(|(i, (raider, amount))| RaidPayoutShare {
+            rank: i as i32 + 1,
+            raider_id: raider.raider_id.clone(),
+            score: raider.score,
+            amount: amount.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db_persistence::DbPersistence,
+        utils::{
+            test_app_state::create_test_app_state,
+            test_db::{AddressBuilder, RaidBuilder, SubmissionBuilder},
+        },
+    };
+
+    fn raiders(scores: &[f64]) -> Vec<RaiderScore> {
+        scores
+            .iter()
+            .enumerate()
+            .map(|(i, score)| RaiderScore {
+                raider_id: format!("raider{}", i),
+                score: *score,
+            })
+            .collect()
+    }
+
+    fn amounts(shares: &[RaidPayoutShare]) -> Vec<&str> {
+        shares.iter().map(|share| share.amount.as_str()).collect()
+    }
+
+    #[test]
+    fn test_split_prize_pool_curves() {
+        let top = raiders(&[300.0, 200.0, 100.0]);
+
+        assert_eq!(
+            amounts(&split_prize_pool(100, PayoutCurve::Equal, &top)),
+            ["34", "33", "33"]
+        );
+        assert_eq!(
+            amounts(&split_prize_pool(600, PayoutCurve::Linear, &top)),
+            ["300", "200", "100"]
+        );
+        assert_eq!(
+            amounts(&split_prize_pool(1000, PayoutCurve::Proportional, &top)),
+            ["501", "333", "166"]
+        );
+
+        let shares = split_prize_pool(600, PayoutCurve::Linear, &top);
+        assert_eq!(shares[2].rank, 3);
+        assert_eq!(shares[2].raider_id, "raider2");
+
+        assert!(split_prize_pool(100, PayoutCurve::Linear, &[]).is_empty());
+    }
+
+    async fn finish(db: &DbPersistence, service: &RaidPayoutService, raid_id: i32) -> DbResult<u64> {
+        let mut uow = db.begin().await?;
+        let created = service.finish(uow.conn(), raid_id).await?;
+        uow.commit().await?;
+        Ok(created)
+    }
+
+    async fn reactivate(db: &DbPersistence, service: &RaidPayoutService, raid_id: i32) -> DbResult<()> {
+        let mut uow = db.begin().await?;
+        service.reactivate(uow.conn(), raid_id).await?;
+        uow.commit().await
+    }
+
+    #[tokio::test]
+    async fn test_finish_again_replaces_split_until_paid() {
+        let state = create_test_app_state().await;
+        let service = RaidPayoutService::new(
+            state.db.raid_payouts.clone(),
+            RaidPayoutConfig {
+                prize_pool: 1000,
+                winners: 10,
+                curve: PayoutCurve::Equal,
+            },
+        );
+
+        let raid_id = RaidBuilder::new("Raid").create(&state.db).await;
+        let a = AddressBuilder::new("a")
+            .create(&state.db.addresses)
+            .await
+            .quan_address
+            .0;
+        let b = AddressBuilder::new("b")
+            .create(&state.db.addresses)
+            .await
+            .quan_address
+            .0;
+        SubmissionBuilder::new("s1", raid_id, &a)
+            .impressions(100)
+            .create(&state.db.pool)
+            .await;
+
+        assert_eq!(finish(&state.db, &service, raid_id).await.unwrap(), 1);
+        reactivate(&state.db, &service, raid_id).await.unwrap();
+        assert!(state.db.raid_payouts.find_by_raid(raid_id).await.unwrap().is_empty());
+
+        SubmissionBuilder::new("s2", raid_id, &b)
+            .impressions(50)
+            .create(&state.db.pool)
+            .await;
+        assert_eq!(finish(&state.db, &service, raid_id).await.unwrap(), 2);
+        // Finishing an already finished raid splits it again rather than adding to the split
+        assert_eq!(finish(&state.db, &service, raid_id).await.unwrap(), 2);
+
+        let payouts = state.db.raid_payouts.find_by_raid(raid_id).await.unwrap();
+        let total: u64 = payouts.iter().map(|p| p.amount.parse::<u64>().unwrap()).sum();
+        assert_eq!(total, 1000);
+
+        state
+            .db
+            .raid_payouts
+            .mark_paid(raid_id, &[payouts[0].id], "admin")
+            .await
+            .unwrap();
+        assert!(matches!(
+            reactivate(&state.db, &service, raid_id).await,
+            Err(DbError::PayoutsAlreadyPaid(_))
+        ));
+        assert!(matches!(
+            finish(&state.db, &service, raid_id).await,
+            Err(DbError::PayoutsAlreadyPaid(_))
+        ));
+        assert_eq!(state.db.raid_payouts.find_by_raid(raid_id).await.unwrap().len(), 2);
+    }
+}
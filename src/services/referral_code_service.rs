@@ -0,0 +1,219 @@
+use std::collections::{HashMap, HashSet};
+
+use thiserror::Error;
+
+use crate::{
+    db_persistence::DbError, models::ModelError, repositories::address::AddressRepository,
+    utils::generate_referral_code::generate_referral_code,
+};
+
+/// How many suffixed candidates are tried before giving up on a colliding code.
+const MAX_COLLISION_ATTEMPTS: u32 = 16;
+pub const VANITY_CODE_MIN_LEN: usize = 3;
+pub const VANITY_CODE_MAX_LEN: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum ReferralCodeError {
+    #[error("Failed to generate referral code: {0}")]
+    Checkphrase(#[from] ModelError),
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+    #[error("Could not find a free referral code for {0}")]
+    Exhausted(String),
+    #[error("{0}")]
+    InvalidVanityCode(String),
+    #[error("Referral code '{0}' uses a reserved prefix")]
+    ReservedVanityCode(String),
+    #[error("Referral code '{0}' is already taken")]
+    VanityCodeTaken(String),
+    #[error("A vanity referral code was already set for this address")]
+    VanityCodeAlreadySet,
+}
+
+pub type ReferralCodeResult<T> = Result<T, ReferralCodeError>;
+
+pub struct ReferralCodeService;
+
+impl ReferralCodeService {
+    /// Generates the checkphrase based referral code for `address`, appending a numeric suffix
+    /// when the base code is already owned by a different address.
+    pub async fn generate_unique(addresses: &AddressRepository, address: &str) -> ReferralCodeResult<String> {
+        let base = generate_referral_code(address.to_string()).await?.to_lowercase();
+
+        for attempt in 0..MAX_COLLISION_ATTEMPTS {
+            let candidate = collision_candidate(&base, attempt);
+
+            match addresses.find_by_referral_code(&candidate).await? {
+                Some(owner) if owner.quan_address.0 != address => {
+                    tracing::warn!(
+                        "Referral code '{}' for {} collides with {}, retrying",
+                        candidate,
+                        address,
+                        owner.quan_address.0
+                    );
+                }
+                _ => return Ok(candidate),
+            }
+        }
+
+        Err(ReferralCodeError::Exhausted(address.to_string()))
+    }
+
+    /// Resolves collisions for a batch of `(address, code)` pairs, both inside the batch and against
+    /// codes already stored for other addresses. Pairs that cannot be repaired are dropped.
+    pub async fn repair_batch(
+        addresses: &AddressRepository,
+        pairs: Vec<(String, String)>,
+    ) -> ReferralCodeResult<Vec<(String, String)>> {
+        let mut candidates = Vec::with_capacity(pairs.len() * MAX_COLLISION_ATTEMPTS as usize);
+        for (_, code) in &pairs {
+            for attempt in 0..MAX_COLLISION_ATTEMPTS {
+                candidates.push(collision_candidate(code, attempt));
+            }
+        }
+        let owners: HashMap<String, String> = addresses.find_owners_by_referral_codes(&candidates).await?;
+
+        let mut taken: HashSet<String> = HashSet::new();
+        let mut repaired = Vec::with_capacity(pairs.len());
+
+        for (address, code) in pairs {
+            let free = (0..MAX_COLLISION_ATTEMPTS)
+                .map(|attempt| collision_candidate(&code, attempt))
+                .find(|candidate| {
+                    !taken.contains(candidate) && owners.get(candidate).is_none_or(|owner| *owner == address)
+                });
+
+            match free {
+                Some(candidate) => {
+                    if candidate != code {
+                        tracing::warn!(
+                            "Referral code '{}' for {} collided, using '{}'",
+                            code,
+                            address,
+                            candidate
+                        );
+                    }
+                    taken.insert(candidate.clone());
+                    repaired.push((address, candidate));
+                }
+                None => tracing::error!("Could not find a free referral code for {}, skipping", address),
+            }
+        }
+
+        Ok(repaired)
+    }
+
+    /// Normalizes and validates a requested vanity code. Reserved prefixes are only accepted when
+    /// `allow_reserved` is set, which is the case for admin grants.
+    pub fn validate_vanity_code(
+        code: &str,
+        reserved_prefixes: &[String],
+        allow_reserved: bool,
+    ) -> ReferralCodeResult<String> {
+        let code = code.trim().to_lowercase();
+
+        if code.len() < VANITY_CODE_MIN_LEN || code.len() > VANITY_CODE_MAX_LEN {
+            return Err(ReferralCodeError::InvalidVanityCode(format!(
+                "Referral code must be between {} and {} characters",
+                VANITY_CODE_MIN_LEN, VANITY_CODE_MAX_LEN
+            )));
+        }
+
+        if !code
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(ReferralCodeError::InvalidVanityCode(String::from(
+                "Referral code may only contain letters, digits and hyphens",
+            )));
+        }
+
+        if code.starts_with('-') || code.ends_with('-') || code.contains("--") {
+            return Err(ReferralCodeError::InvalidVanityCode(String::from(
+                "Referral code can't start or end with a hyphen or contain consecutive hyphens",
+            )));
+        }
+
+        if !allow_reserved
+            && reserved_prefixes
+                .iter()
+                .any(|prefix| code.starts_with(&prefix.to_lowercase()))
+        {
+            return Err(ReferralCodeError::ReservedVanityCode(code));
+        }
+
+        Ok(code)
+    }
+}
+
+fn collision_candidate(base: &str, attempt: u32) -> String {
+    if attempt == 0 {
+        base.to_string()
+    } else {
+        format!("{}-{}", base, attempt + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_app_state::create_test_app_state, test_db::create_persisted_address};
+
+    fn reserved() -> Vec<String> {
+        vec!["quantus".to_string(), "admin".to_string()]
+    }
+
+    #[test]
+    fn test_collision_candidate_suffixes() {
+        assert_eq!(collision_candidate("alpha-beta", 0), "alpha-beta");
+        assert_eq!(collision_candidate("alpha-beta", 1), "alpha-beta-2");
+        assert_eq!(collision_candidate("alpha-beta", 4), "alpha-beta-5");
+    }
+
+    #[test]
+    fn test_validate_vanity_code() {
+        assert_eq!(
+            ReferralCodeService::validate_vanity_code(" Moon-Shot ", &reserved(), false).unwrap(),
+            "moon-shot"
+        );
+
+        for invalid in ["ab", "has space", "emoji🚀", "-lead", "trail-", "dou--ble"] {
+            assert!(matches!(
+                ReferralCodeService::validate_vanity_code(invalid, &reserved(), false),
+                Err(ReferralCodeError::InvalidVanityCode(_))
+            ));
+        }
+
+        assert!(matches!(
+            ReferralCodeService::validate_vanity_code("QuantusFan", &reserved(), false),
+            Err(ReferralCodeError::ReservedVanityCode(_))
+        ));
+        assert_eq!(
+            ReferralCodeService::validate_vanity_code("QuantusFan", &reserved(), true).unwrap(),
+            "quantusfan"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repair_batch_resolves_collisions() {
+        let state = create_test_app_state().await;
+
+        let existing = create_persisted_address(&state.db.addresses, "owner").await;
+        let code = existing.referral_code.clone();
+
+        let repaired = ReferralCodeService::repair_batch(
+            &state.db.addresses,
+            vec![
+                (existing.quan_address.0.clone(), code.clone()),
+                ("qz_test_address_other_1".to_string(), code.clone()),
+                ("qz_test_address_other_2".to_string(), code.clone()),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(repaired[0].1, code);
+        assert_eq!(repaired[1].1, format!("{}-2", code));
+        assert_eq!(repaired[2].1, format!("{}-3", code));
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    config::ReferralRewardsConfig,
+    repositories::{referral_reward::ReferralRewardRepository, DbResult},
+};
+
+/// Turns referrals into rewards. A referrer earns `reward_amount` for each referee that made at least
+/// `min_referee_transfers` transfers among those ingested by the transfer sync, so rewards are computed right
+/// after each `--sync-transfers` run.
+#[derive(Debug, Clone)]
+pub struct ReferralRewardsService {
+    repository: ReferralRewardRepository,
+    config: ReferralRewardsConfig,
+}
+
+impl ReferralRewardsService {
+    pub fn new(repository: ReferralRewardRepository, config: ReferralRewardsConfig) -> Self {
+        Self { repository, config }
+    }
+
+    /// Grants rewards for newly active referees, returning how many were granted. A `reward_amount` of 0
+    /// disables rewards.
+    pub async fn compute(&self) -> DbResult<u64> {
+        if self.config.reward_amount == 0 {
+            return Ok(0);
+        }
+
+        let granted = self
+            .repository
+            .grant_for_active_referees(
+                self.config.min_referee_transfers.max(1) as i64,
+                &self.config.reward_amount.to_string(),
+                self.config.max_referee_sybil_score as i16,
+            )
+            .await?;
+
+        if granted > 0 {
+            tracing::info!("Granted {} referral rewards", granted);
+        }
+
+        Ok(granted)
+    }
+}
@@ -5,7 +5,10 @@ use std::{str::FromStr, sync::Arc, time::Duration};
 use thiserror::Error;
 use tokio::sync::Semaphore;
 
-use crate::config::RiskCheckerConfig;
+use crate::{
+    config::RiskCheckerConfig,
+    utils::retry::{Retrier, RetryError, RetryPolicy},
+};
 
 #[derive(Debug, Error)]
 pub enum RiskCheckerError {
@@ -23,6 +26,23 @@ pub enum RiskCheckerError {
     Other(String),
 }
 
+impl RiskCheckerError {
+    /// Etherscan's rate limit is per second, so it's retried along with network failures.
+    fn is_transient(&self) -> bool {
+        matches!(self, RiskCheckerError::RateLimit | RiskCheckerError::NetworkError)
+    }
+}
+
+impl From<RetryError<RiskCheckerError>> for RiskCheckerError {
+    fn from(err: RetryError<RiskCheckerError>) -> Self {
+        match err {
+            RetryError::Failed(e) => e,
+            // The breaker logs when it opens, clients see the same error as when Etherscan is down
+            RetryError::CircuitOpen(_) => RiskCheckerError::NetworkError,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RiskReport {
@@ -82,10 +102,11 @@ pub struct RiskCheckerService {
     /// executions across all inbound requests, preventing outbound Etherscan
     /// fan-out from overwhelming the API quota.
     concurrency_limiter: Arc<Semaphore>,
+    retrier: Retrier,
 }
 
 impl RiskCheckerService {
-    pub fn new(config: &RiskCheckerConfig) -> Self {
+    pub fn new(config: &RiskCheckerConfig, retry: RetryPolicy) -> Self {
         let infura_rpc_url = format!(
             "{}/{}",
             config.infura_base_url.trim_end_matches('/'),
@@ -108,6 +129,7 @@ impl RiskCheckerService {
             infura_rpc_url,
             etherscan_call_delay,
             concurrency_limiter: Arc::new(Semaphore::new(max_concurrent)),
+            retrier: Retrier::new("Etherscan", retry),
         }
     }
 
@@ -179,7 +201,17 @@ impl RiskCheckerService {
         }
     }
 
+    /// Calls Etherscan, retrying transient failures according to the configured policy.
     async fn fetch_etherscan(&self, params: &[(&str, &str)]) -> Result<EtherscanResponse, RiskCheckerError> {
+        let data = self
+            .retrier
+            .run(RiskCheckerError::is_transient, || self.send_etherscan(params))
+            .await?;
+
+        Ok(data)
+    }
+
+    async fn send_etherscan(&self, params: &[(&str, &str)]) -> Result<EtherscanResponse, RiskCheckerError> {
         let mut query: Vec<(&str, &str)> = params.to_vec();
         query.push(("apikey", &self.etherscan_api_key));
 
@@ -250,6 +282,14 @@ impl RiskCheckerService {
         Ok(data)
     }
 
+    /// Asks Etherscan for the latest block number, the cheapest call that goes through the API key.
+    /// Not retried, so the health probe reports how Etherscan is doing right now.
+    pub async fn check_reachable(&self) -> Result<(), RiskCheckerError> {
+        self.send_etherscan(&[("module", "proxy"), ("action", "eth_blockNumber")])
+            .await
+            .map(|_| ())
+    }
+
     pub async fn get_balance(&self, address: &str) -> Result<String, RiskCheckerError> {
         let data = self
             .fetch_etherscan(&[
@@ -347,6 +387,16 @@ impl RiskCheckerService {
             infura_rpc_url: infura_rpc_url.to_string(),
             etherscan_call_delay: Duration::ZERO,
             concurrency_limiter: Arc::new(Semaphore::new(1)),
+            retrier: Retrier::new(
+                "Etherscan",
+                RetryPolicy {
+                    max_attempts: 1,
+                    initial_backoff_ms: 1,
+                    max_backoff_ms: 2,
+                    circuit_breaker_threshold: 0,
+                    circuit_breaker_cooldown_secs: 60,
+                },
+            ),
         }
     }
 
@@ -623,6 +673,45 @@ mod tests {
         assert!(matches!(result, Err(RiskCheckerError::RateLimit)));
     }
 
+    #[tokio::test]
+    async fn test_get_balance_retries_rate_limit() {
+        // Arrange
+        let mock_server = MockServer::start().await;
+        let address = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+
+        Mock::given(method("GET"))
+            .and(query_param("action", "balance"))
+            .respond_with(ResponseTemplate::new(429))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("action", "balance"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(etherscan_ok(serde_json::json!("42"))))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut service = setup_service(&mock_server).await;
+        service.retrier = Retrier::new(
+            "Etherscan",
+            RetryPolicy {
+                max_attempts: 2,
+                initial_backoff_ms: 1,
+                max_backoff_ms: 2,
+                circuit_breaker_threshold: 0,
+                circuit_breaker_cooldown_secs: 60,
+            },
+        );
+
+        // Act
+        let result = service.get_balance(address).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), "42");
+    }
+
     #[tokio::test]
     async fn test_has_any_transactions_true() {
         // Arrange
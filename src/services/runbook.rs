@@ -0,0 +1,82 @@
+//! Known failure modes, keyed by the error codes reported by health checks and jobs, with what on-call should
+//! check first.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RunbookEntry {
+    pub code: &'static str,
+    pub summary: &'static str,
+    pub remediation: &'static str,
+}
+
+pub const DATABASE_UNREACHABLE: &str = "database_unreachable";
+pub const INDEXER_UNREACHABLE: &str = "indexer_unreachable";
+pub const INDEXER_ERROR: &str = "indexer_error";
+pub const INDEXER_CIRCUIT_OPEN: &str = "indexer_circuit_open";
+pub const PROBE_TIMEOUT: &str = "probe_timeout";
+pub const TWITTER_UNREACHABLE: &str = "twitter_unreachable";
+pub const EXCHANGE_RATE_UNREACHABLE: &str = "exchange_rate_unreachable";
+pub const ETHERSCAN_UNREACHABLE: &str = "etherscan_unreachable";
+
+pub const RUNBOOK: &[RunbookEntry] = &[
+    RunbookEntry {
+        code: DATABASE_UNREACHABLE,
+        summary: "Postgres is not answering queries",
+        remediation:
+            "Check the Postgres instance and connection limits, and that DATABASE_URL points at the right host",
+    },
+    RunbookEntry {
+        code: INDEXER_UNREACHABLE,
+        summary: "The chain indexer can't be reached",
+        remediation: "Check the subsquid pod and that candidates.graphql_url is reachable from this service",
+    },
+    RunbookEntry {
+        code: INDEXER_ERROR,
+        summary: "The chain indexer answered with an error",
+        remediation: "Check the subsquid pod logs; a schema change upstream usually shows up as GraphQL errors",
+    },
+    RunbookEntry {
+        code: INDEXER_CIRCUIT_OPEN,
+        summary: "Indexer calls are paused after repeated failures",
+        remediation: "Fix the indexer first; calls resume on their own once the cooldown in [retry.graphql] has passed",
+    },
+    RunbookEntry {
+        code: PROBE_TIMEOUT,
+        summary: "A health probe did not answer in time",
+        remediation: "The integration is up but slow; check its load and the network path before restarting anything",
+    },
+    RunbookEntry {
+        code: TWITTER_UNREACHABLE,
+        summary: "The X API is failing, tweet syncs and new tweet authors are on hold",
+        remediation: "Check the X API status page and that the x_oauth credentials are valid and not rate limited",
+    },
+    RunbookEntry {
+        code: EXCHANGE_RATE_UNREACHABLE,
+        summary: "The exchange rate API is failing, rates are served from the last snapshot",
+        remediation: "Check exchange_rate.api_key and the remaining quota on the exchangerate-api dashboard",
+    },
+    RunbookEntry {
+        code: ETHERSCAN_UNREACHABLE,
+        summary: "Etherscan is failing, risk reports can't be generated",
+        remediation: "Check risk_checker.etherscan_api_key and its rate limit, and the Etherscan status page",
+    },
+];
+
+pub fn lookup(code: &str) -> Option<&'static RunbookEntry> {
+    RUNBOOK.iter().find(|entry| entry.code == code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_codes_are_unique() {
+        let codes: HashSet<_> = RUNBOOK.iter().map(|entry| entry.code).collect();
+        assert_eq!(codes.len(), RUNBOOK.len());
+        assert!(lookup(INDEXER_ERROR).is_some());
+        assert!(lookup("unknown").is_none());
+    }
+}
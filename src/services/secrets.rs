@@ -0,0 +1,372 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use reqwest::Client;
+
+use crate::config::SecretsConfig;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretsError {
+    #[error("Environment variable {0} is not set")]
+    MissingEnv(String),
+    #[error("Failed to read secret file {0}: {1}")]
+    File(String, std::io::Error),
+    #[error("Vault secret {0} requested but secrets.vault_addr is not set")]
+    VaultNotConfigured(String),
+    #[error("Vault secret {0}: {1}")]
+    Vault(String, String),
+    #[error("Secret {0} is empty")]
+    Empty(String),
+    #[error("Malformed secret reference {0}, expected env:NAME, file:PATH or vault:PATH#KEY")]
+    Malformed(String),
+}
+
+pub type SecretsResult<T> = Result<T, SecretsError>;
+
+/// Where a secret setting takes its value from, written as the setting's value:
+/// `env:NAME`, `file:/run/secrets/name`, `vault:secret/data/task-master#key`, or the secret itself. A value with
+/// one of the prefixes that doesn't parse is an error, never taken as the secret.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretSource {
+    Literal(String),
+    Env(String),
+    File(PathBuf),
+    /// KV path in Vault, including the mount, and the key within it.
+    Vault {
+        path: String,
+        key: String,
+    },
+}
+
+impl SecretSource {
+    pub fn parse(reference: &str) -> SecretsResult<Self> {
+        let required = |part: &str| match part {
+            "" => Err(SecretsError::Malformed(reference.to_string())),
+            part => Ok(part.to_string()),
+        };
+
+        let source = if let Some(name) = reference.strip_prefix("env:") {
+            SecretSource::Env(required(name)?)
+        } else if let Some(path) = reference.strip_prefix("file:") {
+            SecretSource::File(PathBuf::from(required(path)?))
+        } else if let Some(rest) = reference.strip_prefix("vault:") {
+            let (path, key) = rest.split_once('#').unwrap_or((rest, ""));
+            SecretSource::Vault {
+                path: required(path.trim_matches('/'))?,
+                key: required(key)?,
+            }
+        } else {
+            SecretSource::Literal(reference.to_string())
+        };
+
+        Ok(source)
+    }
+}
+
+#[derive(Clone)]
+struct CachedSecret {
+    value: String,
+    /// Value the secret rotated from and when.
+    previous: Option<(String, Instant)>,
+    fetched_at: Instant,
+}
+
+/// Resolves secret settings and re-reads them every `refresh_interval_secs`, so a secret rotated in its file
+/// or in Vault applies without a restart. After a rotation the old value stays accepted for
+/// `rotation_grace_secs`, tokens signed with it keep working until they expire.
+pub struct SecretStore {
+    client: Client,
+    vault_addr: Option<String>,
+    refresh_interval: Duration,
+    rotation_grace: Duration,
+    secrets: Mutex<HashMap<String, CachedSecret>>,
+    /// Held while fetching, so concurrent requests for a stale secret send one request to the backend.
+    fetching: tokio::sync::Mutex<()>,
+}
+
+// Leaves the values out, since AppState derives Debug
+impl std::fmt::Debug for SecretStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretStore")
+            .field("vault_addr", &self.vault_addr)
+            .field("refresh_interval", &self.refresh_interval)
+            .field("rotation_grace", &self.rotation_grace)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SecretStore {
+    pub fn new(config: &SecretsConfig) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self {
+            client,
+            vault_addr: config
+                .vault_addr
+                .as_ref()
+                .map(|addr| addr.trim_end_matches('/').to_string()),
+            refresh_interval: Duration::from_secs(config.refresh_interval_secs),
+            rotation_grace: Duration::from_secs(config.rotation_grace_secs),
+            secrets: Mutex::new(HashMap::new()),
+            fetching: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Value to sign with.
+    pub async fn current(&self, reference: &str) -> SecretsResult<String> {
+        Ok(self.resolve(reference).await?.value)
+    }
+
+    /// Values to verify with, the current one first and then the one it rotated from while in its grace period.
+    pub async fn accepted(&self, reference: &str) -> SecretsResult<Vec<String>> {
+        let secret = self.resolve(reference).await?;
+        let previous = secret
+            .previous
+            .filter(|(_, rotated_at)| rotated_at.elapsed() < self.rotation_grace)
+            .map(|(value, _)| value);
+
+        Ok(std::iter::once(secret.value).chain(previous).collect())
+    }
+
+    async fn resolve(&self, reference: &str) -> SecretsResult<CachedSecret> {
+        let source = SecretSource::parse(reference)?;
+        if let SecretSource::Literal(value) = source {
+            return Ok(CachedSecret {
+                value,
+                previous: None,
+                fetched_at: Instant::now(),
+            });
+        }
+
+        let cached = self.lock().get(reference).cloned();
+        if let Some(cached) = cached.as_ref().filter(|cached| self.is_fresh(cached)) {
+            return Ok(cached.clone());
+        }
+
+        let _fetching = match (self.fetching.try_lock(), cached) {
+            (Ok(guard), _) => guard,
+            // Another request is refreshing it, the value it replaces is still good until then
+            (Err(_), Some(cached)) => return Ok(cached),
+            (Err(_), None) => self.fetching.lock().await,
+        };
+        // It may have been fetched while waiting
+        let fresh = self
+            .lock()
+            .get(reference)
+            .filter(|cached| self.is_fresh(cached))
+            .cloned();
+        if let Some(cached) = fresh {
+            return Ok(cached);
+        }
+
+        let fetched = self.fetch(&source).await;
+        let now = Instant::now();
+        let mut secrets = self.lock();
+
+        let secret = match (fetched, secrets.remove(reference)) {
+            (Ok(value), Some(mut cached)) => {
+                if cached.value != value {
+                    tracing::info!("Secret {} was rotated", describe(&source));
+                    cached.previous = Some((std::mem::replace(&mut cached.value, value), now));
+                }
+                cached.fetched_at = now;
+                cached
+            }
+            (Ok(value), None) => CachedSecret {
+                value,
+                previous: None,
+                fetched_at: now,
+            },
+            // A failed refresh keeps the known value, an unreachable backend shouldn't lock everyone out
+            (Err(e), Some(mut cached)) => {
+                tracing::warn!("Failed to refresh secret, keeping the current value: {}", e);
+                cached.fetched_at = now;
+                cached
+            }
+            (Err(e), None) => return Err(e),
+        };
+        secrets.insert(reference.to_string(), secret.clone());
+
+        Ok(secret)
+    }
+
+    async fn fetch(&self, source: &SecretSource) -> SecretsResult<String> {
+        let value = match source {
+            SecretSource::Literal(value) => value.clone(),
+            SecretSource::Env(name) => std::env::var(name).map_err(|_| SecretsError::MissingEnv(name.clone()))?,
+            SecretSource::File(path) => std::fs::read_to_string(path)
+                .map_err(|e| SecretsError::File(path.display().to_string(), e))?
+                .trim_end_matches(['\n', '\r'])
+                .to_string(),
+            SecretSource::Vault { path, key } => self.fetch_from_vault(path, key).await?,
+        };
+
+        if value.is_empty() {
+            return Err(SecretsError::Empty(describe(source)));
+        }
+        Ok(value)
+    }
+
+    /// Reads `key` of a KV secret, v2 mounts nest the values under `data.data`, v1 mounts under `data`.
+    async fn fetch_from_vault(&self, path: &str, key: &str) -> SecretsResult<String> {
+        let name = format!("{}#{}", path, key);
+        let vault_error = |message: String| SecretsError::Vault(name.clone(), message);
+
+        let addr = self
+            .vault_addr
+            .as_ref()
+            .ok_or_else(|| SecretsError::VaultNotConfigured(name.clone()))?;
+        let token = std::env::var("VAULT_TOKEN").map_err(|_| SecretsError::MissingEnv("VAULT_TOKEN".to_string()))?;
+
+        let response = self
+            .client
+            .get(format!("{}/v1/{}", addr, path))
+            .header("X-Vault-Token", token)
+            .send()
+            .await
+            .map_err(|e| vault_error(e.without_url().to_string()))?;
+        if !response.status().is_success() {
+            return Err(vault_error(format!("HTTP {}", response.status())));
+        }
+
+        let body: serde_json::Value = response.json().await.map_err(|e| vault_error(e.to_string()))?;
+        body.pointer(&format!("/data/data/{}", key))
+            .or_else(|| body.pointer(&format!("/data/{}", key)))
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| vault_error(format!("no string value for key {}", key)))
+    }
+
+    fn is_fresh(&self, cached: &CachedSecret) -> bool {
+        cached.fetched_at.elapsed() < self.refresh_interval
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedSecret>> {
+        self.secrets.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Names a secret for logs without its value.
+fn describe(source: &SecretSource) -> String {
+    match source {
+        SecretSource::Literal(_) => "from config".to_string(),
+        SecretSource::Env(name) => format!("env:{}", name),
+        SecretSource::File(path) => format!("file:{}", path.display()),
+        SecretSource::Vault { path, key } => format!("vault:{}#{}", path, key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secret_source() {
+        assert_eq!(
+            SecretSource::parse("env:JWT_SECRET").unwrap(),
+            SecretSource::Env("JWT_SECRET".to_string())
+        );
+        assert_eq!(
+            SecretSource::parse("file:/run/secrets/jwt").unwrap(),
+            SecretSource::File(PathBuf::from("/run/secrets/jwt"))
+        );
+        assert_eq!(
+            SecretSource::parse("vault:secret/data/task-master#jwt_secret").unwrap(),
+            SecretSource::Vault {
+                path: "secret/data/task-master".to_string(),
+                key: "jwt_secret".to_string(),
+            }
+        );
+        assert_eq!(
+            SecretSource::parse("plain-secret").unwrap(),
+            SecretSource::Literal("plain-secret".to_string())
+        );
+
+        // A reference that doesn't parse must never end up used as the secret
+        for malformed in [
+            "vault:secret/data/tm",
+            "vault:secret/data/tm#",
+            "vault:#key",
+            "env:",
+            "file:",
+        ] {
+            assert!(matches!(
+                SecretSource::parse(malformed),
+                Err(SecretsError::Malformed(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rotated_file_secret_keeps_previous_value_accepted() {
+        let path = std::env::temp_dir().join(format!("task-master-secret-{}", std::process::id()));
+        std::fs::write(&path, "first\n").unwrap();
+        let reference = format!("file:{}", path.display());
+
+        let store = SecretStore::new(&SecretsConfig {
+            refresh_interval_secs: 0,
+            rotation_grace_secs: 60,
+            vault_addr: None,
+        });
+        assert_eq!(store.current(&reference).await.unwrap(), "first");
+
+        std::fs::write(&path, "second").unwrap();
+        assert_eq!(store.current(&reference).await.unwrap(), "second");
+        assert_eq!(store.accepted(&reference).await.unwrap(), vec!["second", "first"]);
+
+        // The file going away keeps the last value
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(store.current(&reference).await.unwrap(), "second");
+
+        assert!(matches!(
+            store.current("env:TASK_MASTER_UNSET_SECRET").await,
+            Err(SecretsError::MissingEnv(_))
+        ));
+        assert_eq!(store.accepted("literal").await.unwrap(), vec!["literal"]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_fetch_vault_secret_once() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let vault = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/task-master"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "data": { "data": { "jwt": "from-vault" } } }))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&vault)
+            .await;
+        std::env::set_var("VAULT_TOKEN", "test-token");
+
+        let store = SecretStore::new(&SecretsConfig {
+            refresh_interval_secs: 3600,
+            rotation_grace_secs: 60,
+            vault_addr: Some(vault.uri()),
+        });
+        let reference = "vault:secret/data/task-master#jwt";
+        let (a, b, c) = tokio::join!(
+            store.current(reference),
+            store.current(reference),
+            store.current(reference)
+        );
+
+        assert_eq!(
+            [a.unwrap(), b.unwrap(), c.unwrap()],
+            ["from-vault", "from-vault", "from-vault"]
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    db_persistence::DbError,
+    models::setting::{Setting, SettingView},
+    repositories::setting::SettingRepository,
+    Config,
+};
+
+/// Config paths that admins may override at runtime, and that are re-read from the config file on SIGHUP.
+/// Everything else requires a restart.
+pub const OVERRIDABLE_SETTINGS: &[&str] = &[
+    "auth.challenge_store",
+    "jwt.exp_in_hours",
+    "jwt.refresh_exp_in_hours",
+    "leaderboard_cache.ttl_seconds",
+    "raid_payout.curve",
+    "raid_payout.prize_pool",
+    "raid_payout.winners",
+    "rate_limit.routes",
+    "referral_codes.reserved_prefixes",
+    "request_logging.default",
+    "request_logging.groups",
+];
+
+/// Map settings whose overrides are merged into the file value per entry, so an override for one route group
+/// keeps the file's other groups, e.g. `auth = "none"`.
+const MERGED_SETTINGS: &[&str] = &["request_logging.groups"];
+
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("Setting '{0}' can't be overridden at runtime")]
+    NotOverridable(String),
+    #[error("Invalid value for '{0}': {1}")]
+    InvalidValue(String, String),
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+    #[error("Settings lock error")]
+    Lock,
+}
+
+pub type SettingsResult<T> = Result<T, SettingsError>;
+
+/// Holds the effective configuration: the file config with persisted overrides applied on top.
+/// Overrides always take precedence over the file value.
+#[derive(Debug)]
+pub struct SettingsService {
+    /// File config, its overridable settings are replaced when the file is reloaded.
+    base: RwLock<Arc<Config>>,
+    repository: SettingRepository,
+    overrides: RwLock<HashMap<String, Value>>,
+    effective: RwLock<Arc<Config>>,
+}
+
+impl SettingsService {
+    /// Loads persisted overrides. Overrides that are no longer whitelisted or fail to apply are skipped,
+    /// so a bad row can't prevent the server from starting.
+    pub async fn load(base: Arc<Config>, repository: SettingRepository) -> SettingsResult<Self> {
+        let mut overrides = HashMap::new();
+
+        for Setting { key, value, .. } in repository.find_all().await? {
+            if !OVERRIDABLE_SETTINGS.contains(&key.as_str()) {
+                tracing::warn!("Ignoring persisted override for non-overridable setting '{}'", key);
+                continue;
+            }
+
+            let mut candidate = overrides.clone();
+            candidate.insert(key.clone(), value);
+            match apply_overrides(&base, &candidate) {
+                Ok(_) => overrides = candidate,
+                Err(e) => tracing::warn!("Ignoring persisted override: {}", e),
+            }
+        }
+
+        if !overrides.is_empty() {
+            tracing::info!("Loaded {} configuration override(s)", overrides.len());
+        }
+
+        let effective = Arc::new(apply_overrides(&base, &overrides)?);
+
+        Ok(Self {
+            base: RwLock::new(base),
+            repository,
+            overrides: RwLock::new(overrides),
+            effective: RwLock::new(effective),
+        })
+    }
+
+    /// The configuration currently in effect. Cheap to call, callers should not hold on to it for long
+    /// so that overrides are picked up.
+    pub fn current(&self) -> Arc<Config> {
+        match self.effective.read() {
+            Ok(guard) => guard.clone(),
+            Err(_) => self.base(),
+        }
+    }
+
+    fn base(&self) -> Arc<Config> {
+        self.base
+            .read()
+            .map(|guard| guard.clone())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+    }
+
+    pub fn list(&self) -> SettingsResult<Vec<SettingView>> {
+        let base = config_tree(&self.base())?;
+        let effective = config_tree(&self.current())?;
+        let overrides = self.overrides.read().map_err(|_| SettingsError::Lock)?;
+
+        Ok(OVERRIDABLE_SETTINGS
+            .iter()
+            .map(|key| SettingView {
+                key: key.to_string(),
+                file_value: base.pointer(&to_pointer(key)).cloned().unwrap_or(Value::Null),
+                override_value: overrides.get(*key).cloned(),
+                effective_value: effective.pointer(&to_pointer(key)).cloned().unwrap_or(Value::Null),
+            })
+            .collect())
+    }
+
+    pub async fn set(&self, key: &str, value: Value, changed_by: &str) -> SettingsResult<Arc<Config>> {
+        ensure_overridable(key)?;
+
+        let mut candidate = self.overrides.read().map_err(|_| SettingsError::Lock)?.clone();
+        candidate.insert(key.to_string(), value.clone());
+        // Validate before persisting so a bad value never reaches the table.
+        apply_overrides(&self.base(), &candidate)?;
+
+        self.repository.upsert(key, &value, changed_by).await?;
+        tracing::info!("Setting '{}' overridden by {}", key, changed_by);
+
+        self.refresh(|overrides| {
+            overrides.insert(key.to_string(), value);
+        })
+    }
+
+    pub async fn clear(&self, key: &str, changed_by: &str) -> SettingsResult<Arc<Config>> {
+        ensure_overridable(key)?;
+
+        self.repository.delete(key, changed_by).await?;
+        tracing::info!("Setting override '{}' removed by {}", key, changed_by);
+
+        self.refresh(|overrides| {
+            overrides.remove(key);
+        })
+    }
+
+    /// Takes the overridable settings of a freshly loaded config file as the new file values, returning the keys
+    /// that changed. Other settings of `file` are ignored, they only apply after a restart. Overrides still win
+    /// over the new file values.
+    pub fn reload_file(&self, file: &Config) -> SettingsResult<Vec<&'static str>> {
+        let file_tree = config_tree(file)?;
+        let current = self.base();
+        let mut tree = config_tree(&current)?;
+
+        let mut changed = Vec::new();
+        for key in OVERRIDABLE_SETTINGS {
+            let pointer = to_pointer(key);
+            let (Some(new_value), Some(slot)) = (file_tree.pointer(&pointer), tree.pointer_mut(&pointer)) else {
+                continue;
+            };
+            if slot != new_value {
+                *slot = new_value.clone();
+                changed.push(*key);
+            }
+        }
+
+        if changed.is_empty() {
+            return Ok(changed);
+        }
+
+        let base: Config =
+            serde_json::from_value(tree).map_err(|e| SettingsError::InvalidValue(changed.join(", "), e.to_string()))?;
+        let overrides = self.overrides.write().map_err(|_| SettingsError::Lock)?;
+        let effective = Arc::new(apply_overrides(&base, &overrides)?);
+
+        *self.base.write().map_err(|_| SettingsError::Lock)? = Arc::new(base);
+        *self.effective.write().map_err(|_| SettingsError::Lock)? = effective;
+
+        Ok(changed)
+    }
+
+    fn refresh(&self, update: impl FnOnce(&mut HashMap<String, Value>)) -> SettingsResult<Arc<Config>> {
+        let mut overrides = self.overrides.write().map_err(|_| SettingsError::Lock)?;
+        update(&mut overrides);
+
+        let effective = Arc::new(apply_overrides(&self.base(), &overrides)?);
+        *self.effective.write().map_err(|_| SettingsError::Lock)? = effective.clone();
+
+        Ok(effective)
+    }
+}
+
+fn ensure_overridable(key: &str) -> SettingsResult<()> {
+    if OVERRIDABLE_SETTINGS.contains(&key) {
+        Ok(())
+    } else {
+        Err(SettingsError::NotOverridable(key.to_string()))
+    }
+}
+
+fn to_pointer(key: &str) -> String {
+    format!("/{}", key.replace('.', "/"))
+}
+
+fn config_tree(config: &Config) -> SettingsResult<Value> {
+    serde_json::to_value(config).map_err(|e| SettingsError::InvalidValue("*".to_string(), e.to_string()))
+}
+
+/// Applies the overrides onto a copy of `base`. Values are type checked by round-tripping through `Config`.
+fn apply_overrides(base: &Config, overrides: &HashMap<String, Value>) -> SettingsResult<Config> {
+    let mut tree = config_tree(base)?;
+
+    for (key, value) in overrides {
+        let slot = tree
+            .pointer_mut(&to_pointer(key))
+            .ok_or_else(|| SettingsError::NotOverridable(key.clone()))?;
+        match (slot, value) {
+            (Value::Object(entries), Value::Object(overridden)) if MERGED_SETTINGS.contains(&key.as_str()) => {
+                entries.extend(overridden.clone());
+            }
+            (slot, value) => *slot = value.clone(),
+        }
+    }
+
+    serde_json::from_value(tree).map_err(|e| {
+        let key = overrides.keys().cloned().collect::<Vec<_>>().join(", ");
+        SettingsError::InvalidValue(key, e.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::RequestLogMode, utils::test_app_state::create_test_app_state};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_overrides_take_precedence_and_persist() {
+        let state = create_test_app_state().await;
+
+        let service = SettingsService::load(state.config.clone(), state.db.settings.clone())
+            .await
+            .unwrap();
+        assert_eq!(service.current().jwt.exp_in_hours, state.config.jwt.exp_in_hours);
+
+        service.set("jwt.exp_in_hours", json!(2), "admin").await.unwrap();
+        assert_eq!(service.current().jwt.exp_in_hours, 2);
+
+        // A fresh service (e.g. after a restart) picks the override up from the database.
+        let reloaded = SettingsService::load(state.config.clone(), state.db.settings.clone())
+            .await
+            .unwrap();
+        assert_eq!(reloaded.current().jwt.exp_in_hours, 2);
+
+        let view = reloaded.list().unwrap();
+        let exp = view.iter().find(|s| s.key == "jwt.exp_in_hours").unwrap();
+        assert_eq!(exp.override_value, Some(json!(2)));
+        assert_eq!(exp.file_value, json!(state.config.jwt.exp_in_hours));
+
+        reloaded.clear("jwt.exp_in_hours", "admin").await.unwrap();
+        assert_eq!(reloaded.current().jwt.exp_in_hours, state.config.jwt.exp_in_hours);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_keys_and_bad_values() {
+        let state = create_test_app_state().await;
+
+        let service = SettingsService::load(state.config.clone(), state.db.settings.clone())
+            .await
+            .unwrap();
+
+        let err = service.set("jwt.secret", json!("leaked"), "admin").await.unwrap_err();
+        assert!(matches!(err, SettingsError::NotOverridable(_)));
+
+        let err = service
+            .set("jwt.exp_in_hours", json!("a day"), "admin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidValue(_, _)));
+
+        assert!(state.db.settings.find_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reload_file_applies_tunable_settings_only() {
+        let state = create_test_app_state().await;
+
+        let service = SettingsService::load(state.config.clone(), state.db.settings.clone())
+            .await
+            .unwrap();
+        service
+            .set("referral_codes.reserved_prefixes", json!(["admin"]), "admin")
+            .await
+            .unwrap();
+
+        let mut file = (*state.config).clone();
+        file.jwt.exp_in_hours += 1;
+        file.referral_codes.reserved_prefixes = vec!["staff".to_string()];
+        file.server.port += 1;
+
+        let changed = service.reload_file(&file).unwrap();
+        assert_eq!(changed, vec!["jwt.exp_in_hours", "referral_codes.reserved_prefixes"]);
+
+        let current = service.current();
+        assert_eq!(current.jwt.exp_in_hours, file.jwt.exp_in_hours);
+        // The override still wins, and settings outside the whitelist need a restart
+        assert_eq!(current.referral_codes.reserved_prefixes, vec!["admin".to_string()]);
+        assert_eq!(current.server.port, state.config.server.port);
+
+        assert!(service.reload_file(&file).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_request_logging_groups_override() {
+        let state = create_test_app_state().await;
+
+        let service = SettingsService::load(state.config.clone(), state.db.settings.clone())
+            .await
+            .unwrap();
+
+        service
+            .set("request_logging.groups", json!({"addresses": "full"}), "admin")
+            .await
+            .unwrap();
+        let logging = service.current().request_logging.clone();
+        assert_eq!(logging.mode_for("addresses"), RequestLogMode::Full);
+        // Groups of the file that weren't overridden are kept
+        assert_eq!(logging.mode_for("auth"), RequestLogMode::None);
+
+        let err = service
+            .set("request_logging.default", json!("everything"), "admin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidValue(_, _)));
+    }
+}
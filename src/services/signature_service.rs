@@ -1,7 +1,12 @@
+use alloy::primitives::{Address as EthAddress, Signature as EthSignature};
 use quantus_cli::qp_dilithium_crypto::{traits::verify as dilithium_verify, types::DilithiumPublic};
-use sp_core::crypto::{AccountId32, Ss58Codec};
+use serde::{Deserialize, Serialize};
+use sp_core::{
+    crypto::{AccountId32, Ss58Codec},
+    ed25519, sr25519, Pair,
+};
 use sp_runtime::traits::IdentifyAccount;
-use std::convert::TryFrom;
+use std::{convert::TryFrom, str::FromStr};
 use tracing::info;
 
 #[derive(Debug, thiserror::Error)]
@@ -16,16 +21,140 @@ pub enum SigServiceError {
 
 pub type SigServiceResult<T> = Result<T, SigServiceError>;
 
+/// Signature schemes accepted from wallets, selected by the `scheme` field of a payload.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureScheme {
+    /// ML-DSA keys of the Quantus reference wallet.
+    #[default]
+    Dilithium,
+    Sr25519,
+    Ed25519,
+    /// `personal_sign` of an Ethereum wallet. The public key is the signer's `0x` address.
+    Ethereum,
+}
+
+impl SignatureScheme {
+    /// Whether keys of the scheme map to an SS58 account, and so can log in as a quan address.
+    pub fn derives_quan_address(&self) -> bool {
+        !matches!(self, SignatureScheme::Ethereum)
+    }
+}
+
+/// Verification of one signature scheme. Keys and signatures are hex, with or without `0x`.
+pub trait SignatureVerifier: Send + Sync {
+    fn verify_message(&self, message: &[u8], signature: &[u8], public_key: &[u8]) -> SigServiceResult<bool>;
+
+    /// Whether `address` belongs to `public_key`.
+    fn verify_address(&self, public_key: &[u8], address: &str) -> SigServiceResult<bool>;
+}
+
+fn decode_hex(value: &str) -> SigServiceResult<Vec<u8>> {
+    Ok(hex::decode(value.strip_prefix("0x").unwrap_or(value))?)
+}
+
+fn expected_account(address_ss58: &str) -> SigServiceResult<AccountId32> {
+    AccountId32::from_ss58check(address_ss58).map_err(|e| SigServiceError::InvalidAddress(format!("{:?}", e)))
+}
+
+/// Polkadot extensions sign `<Bytes>message</Bytes>` rather than the message itself.
+fn wrap_bytes(message: &[u8]) -> Vec<u8> {
+    [b"<Bytes>".as_slice(), message, b"</Bytes>".as_slice()].concat()
+}
+
+pub struct DilithiumVerifier;
+
+impl SignatureVerifier for DilithiumVerifier {
+    fn verify_message(&self, message: &[u8], signature: &[u8], public_key: &[u8]) -> SigServiceResult<bool> {
+        Ok(dilithium_verify(public_key, message, signature))
+    }
+
+    fn verify_address(&self, public_key: &[u8], address: &str) -> SigServiceResult<bool> {
+        let expected = expected_account(address)?;
+        let dil = DilithiumPublic::try_from(public_key).map_err(|_| SigServiceError::VerifyFailed)?;
+        Ok(dil.into_account() == expected)
+    }
+}
+
+pub struct Sr25519Verifier;
+
+impl SignatureVerifier for Sr25519Verifier {
+    fn verify_message(&self, message: &[u8], signature: &[u8], public_key: &[u8]) -> SigServiceResult<bool> {
+        let public = sr25519::Public::try_from(public_key).map_err(|_| SigServiceError::VerifyFailed)?;
+        let signature = sr25519::Signature::try_from(signature).map_err(|_| SigServiceError::VerifyFailed)?;
+        Ok(sr25519::Pair::verify(&signature, message, &public)
+            || sr25519::Pair::verify(&signature, wrap_bytes(message), &public))
+    }
+
+    fn verify_address(&self, public_key: &[u8], address: &str) -> SigServiceResult<bool> {
+        let expected = expected_account(address)?;
+        let public = sr25519::Public::try_from(public_key).map_err(|_| SigServiceError::VerifyFailed)?;
+        Ok(AccountId32::from(public) == expected)
+    }
+}
+
+pub struct Ed25519Verifier;
+
+impl SignatureVerifier for Ed25519Verifier {
+    fn verify_message(&self, message: &[u8], signature: &[u8], public_key: &[u8]) -> SigServiceResult<bool> {
+        let public = ed25519::Public::try_from(public_key).map_err(|_| SigServiceError::VerifyFailed)?;
+        let signature = ed25519::Signature::try_from(signature).map_err(|_| SigServiceError::VerifyFailed)?;
+        Ok(ed25519::Pair::verify(&signature, message, &public)
+            || ed25519::Pair::verify(&signature, wrap_bytes(message), &public))
+    }
+
+    fn verify_address(&self, public_key: &[u8], address: &str) -> SigServiceResult<bool> {
+        let expected = expected_account(address)?;
+        let public = ed25519::Public::try_from(public_key).map_err(|_| SigServiceError::VerifyFailed)?;
+        Ok(AccountId32::from(public) == expected)
+    }
+}
+
+/// `personal_sign` signatures are recovered to the signer's address, which is what the public key holds.
+pub struct EthereumVerifier;
+
+impl SignatureVerifier for EthereumVerifier {
+    fn verify_message(&self, message: &[u8], signature: &[u8], public_key: &[u8]) -> SigServiceResult<bool> {
+        if public_key.len() != 20 {
+            return Err(SigServiceError::VerifyFailed);
+        }
+        let signer = EthAddress::from_slice(public_key);
+        let signature = EthSignature::from_raw(signature).map_err(|_| SigServiceError::VerifyFailed)?;
+        let recovered = signature
+            .recover_address_from_msg(message)
+            .map_err(|_| SigServiceError::VerifyFailed)?;
+        Ok(recovered == signer)
+    }
+
+    fn verify_address(&self, public_key: &[u8], address: &str) -> SigServiceResult<bool> {
+        let expected = EthAddress::from_str(address).map_err(|e| SigServiceError::InvalidAddress(e.to_string()))?;
+        Ok(public_key == expected.as_slice())
+    }
+}
+
 pub struct SignatureService;
 
 impl SignatureService {
-    pub fn verify_message(message: &[u8], signature_hex: &str, public_key_hex: &str) -> SigServiceResult<bool> {
-        let sig_hex = signature_hex.strip_prefix("0x").unwrap_or(signature_hex);
-        let pk_hex = public_key_hex.strip_prefix("0x").unwrap_or(public_key_hex);
-        let sig = hex::decode(sig_hex)?;
-        let pk = hex::decode(pk_hex)?;
-        let ok = dilithium_verify(&pk, message, &sig);
+    pub fn verifier(scheme: SignatureScheme) -> &'static dyn SignatureVerifier {
+        match scheme {
+            SignatureScheme::Dilithium => &DilithiumVerifier,
+            SignatureScheme::Sr25519 => &Sr25519Verifier,
+            SignatureScheme::Ed25519 => &Ed25519Verifier,
+            SignatureScheme::Ethereum => &EthereumVerifier,
+        }
+    }
+
+    pub fn verify_message(
+        scheme: SignatureScheme,
+        message: &[u8],
+        signature_hex: &str,
+        public_key_hex: &str,
+    ) -> SigServiceResult<bool> {
+        let sig = decode_hex(signature_hex)?;
+        let pk = decode_hex(public_key_hex)?;
+        let ok = Self::verifier(scheme).verify_message(message, &sig, &pk)?;
         info!(
+            scheme = ?scheme,
             message_len = message.len(),
             signature_len = sig.len(),
             public_key_len = pk.len(),
@@ -35,15 +164,10 @@ impl SignatureService {
         Ok(ok)
     }
 
-    pub fn verify_address(public_key_hex: &str, address_ss58: &str) -> SigServiceResult<bool> {
-        let pk_hex = public_key_hex.strip_prefix("0x").unwrap_or(public_key_hex);
-        let pk = hex::decode(pk_hex)?;
-        let expected = AccountId32::from_ss58check(address_ss58)
-            .map_err(|e| SigServiceError::InvalidAddress(format!("{:?}", e)))?;
-        let dil = DilithiumPublic::try_from(pk.as_slice()).map_err(|_| SigServiceError::VerifyFailed)?;
-        let derived = dil.into_account();
-        let ok = derived == expected;
-        info!(public_key_len = pk.len(), ok = ok, "SignatureService::verify_address");
+    pub fn verify_address(scheme: SignatureScheme, public_key_hex: &str, address: &str) -> SigServiceResult<bool> {
+        let pk = decode_hex(public_key_hex)?;
+        let ok = Self::verifier(scheme).verify_address(&pk, address)?;
+        info!(scheme = ?scheme, public_key_len = pk.len(), ok = ok, "SignatureService::verify_address");
         Ok(ok)
     }
 }
@@ -62,7 +186,8 @@ mod tests {
         let msg = b"hello world";
         let signature_hex = "00".repeat(16);
         let public_key_hex = "11".repeat(32);
-        let ok = SignatureService::verify_message(msg, &signature_hex, &public_key_hex).unwrap();
+        let ok =
+            SignatureService::verify_message(SignatureScheme::Dilithium, msg, &signature_hex, &public_key_hex).unwrap();
         assert!(!ok);
     }
 
@@ -76,12 +201,47 @@ mod tests {
         let sig = kp.sign(msg, None, Some(hedge)).unwrap();
         let pk_hex = hex::encode(pk);
         let sig_hex = hex::encode(sig);
-        assert!(SignatureService::verify_message(msg, &sig_hex, &pk_hex).unwrap());
+        assert!(SignatureService::verify_message(SignatureScheme::Dilithium, msg, &sig_hex, &pk_hex).unwrap());
 
         let addr = DilithiumPublic::try_from(hex::decode(&pk_hex).unwrap().as_slice())
             .unwrap()
             .into_account()
             .to_ss58check();
-        assert!(SignatureService::verify_address(&pk_hex, &addr).unwrap());
+        assert!(SignatureService::verify_address(SignatureScheme::Dilithium, &pk_hex, &addr).unwrap());
+    }
+
+    #[test]
+    fn sr25519_and_ed25519_roundtrip() {
+        let msg = b"quantus-signature-test";
+
+        let sr = sr25519::Pair::from_seed(&[5u8; 32]);
+        let sr_pk_hex = hex::encode(sr.public());
+        let sr_addr = AccountId32::from(sr.public()).to_ss58check();
+        let sr_sig_hex = hex::encode(sr.sign(msg));
+        assert!(SignatureService::verify_message(SignatureScheme::Sr25519, msg, &sr_sig_hex, &sr_pk_hex).unwrap());
+        assert!(SignatureService::verify_address(SignatureScheme::Sr25519, &sr_pk_hex, &sr_addr).unwrap());
+        // Wallet extensions wrap the message
+        let wrapped_sig_hex = hex::encode(sr.sign(&wrap_bytes(msg)));
+        assert!(SignatureService::verify_message(SignatureScheme::Sr25519, msg, &wrapped_sig_hex, &sr_pk_hex).unwrap());
+
+        let ed = ed25519::Pair::from_seed(&[6u8; 32]);
+        let ed_pk_hex = hex::encode(ed.public());
+        let ed_sig_hex = hex::encode(ed.sign(msg));
+        assert!(SignatureService::verify_message(SignatureScheme::Ed25519, msg, &ed_sig_hex, &ed_pk_hex).unwrap());
+        // Keys of one scheme don't verify under another
+        assert!(!SignatureService::verify_message(SignatureScheme::Sr25519, msg, &ed_sig_hex, &ed_pk_hex).unwrap());
+        assert!(!SignatureService::verify_address(SignatureScheme::Ed25519, &ed_pk_hex, &sr_addr).unwrap());
+    }
+
+    #[test]
+    fn ethereum_rejects_wrong_signer() {
+        let msg = b"quantus-signature-test";
+        let signer = "0x00000000219ab540356cBB839Cbe05303d7705Fa";
+        let signature_hex = format!("{}1b", "11".repeat(64));
+        assert!(
+            !SignatureService::verify_message(SignatureScheme::Ethereum, msg, &signature_hex, signer).unwrap_or(false)
+        );
+        assert!(SignatureService::verify_address(SignatureScheme::Ethereum, signer, &signer.to_lowercase()).unwrap());
+        assert!(!SignatureScheme::Ethereum.derives_quan_address());
     }
 }
@@ -0,0 +1,32 @@
+use crate::{
+    config::SybilDetectorConfig,
+    repositories::{sybil_score::SybilScoreRepository, DbResult},
+};
+
+/// Scores registered addresses for sybil risk from the transfer graph ingested by the transfer sync: shared
+/// first funders, registrations in bursts within a funding cluster, and funds sent back and forth. Reward
+/// computations skip addresses scoring above their configured threshold, so scores are refreshed right
+/// before them after each `--sync-transfers` run.
+#[derive(Debug, Clone)]
+pub struct SybilDetector {
+    repository: SybilScoreRepository,
+    config: SybilDetectorConfig,
+}
+
+impl SybilDetector {
+    pub fn new(repository: SybilScoreRepository, config: SybilDetectorConfig) -> Self {
+        Self { repository, config }
+    }
+
+    /// Recomputes and stores the score of every registered address, returning how many were scored.
+    pub async fn compute(&self) -> DbResult<u64> {
+        let window = self.config.burst_window_minutes.min(i32::MAX as u32) as i32;
+        let signals = self.repository.find_signals(window).await?;
+        let scored = self.repository.upsert_many(&signals).await?;
+
+        let flagged = signals.iter().filter(|s| s.score() > 0).count();
+        tracing::info!("Scored {} addresses for sybil risk, {} with signals", scored, flagged);
+
+        Ok(scored)
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+
+use crate::{services::settings_service::SettingsService, Config};
+
+/// Re-reads `config_path` on every SIGHUP and applies the runtime-tunable settings, so they can be changed
+/// without a restart. A file that fails to load or apply is logged and the running config is kept.
+pub async fn reload_on_sighup(config_path: String, settings: Arc<SettingsService>, token: CancellationToken) {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGHUP, config reload is disabled: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            tokio::select! {
+                _ = hangup.recv() => reload(&config_path, &settings),
+                _ = token.cancelled() => return,
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (config_path, settings, token);
+    }
+}
+
+fn reload(config_path: &str, settings: &SettingsService) {
+    tracing::info!("Received SIGHUP, reloading configuration from {}", config_path);
+
+    let file = match Config::load(config_path) {
+        Ok(file) => file,
+        Err(e) => {
+            tracing::error!("Failed to reload configuration, keeping the current one: {}", e);
+            return;
+        }
+    };
+
+    match settings.reload_file(&file) {
+        Ok(changed) if changed.is_empty() => tracing::info!("Configuration reloaded, no tunable setting changed"),
+        Ok(changed) => tracing::info!("Configuration reloaded, updated: {}", changed.join(", ")),
+        Err(e) => tracing::error!("Failed to apply reloaded configuration, keeping the current one: {}", e),
+    }
+}
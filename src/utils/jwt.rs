@@ -1,23 +1,26 @@
-use axum::{
-    extract::Request,
-    http::{header, StatusCode},
-    Json,
-};
+use axum::{extract::Request, http::header};
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
 
-use crate::{handlers::ErrorResponse, http_server::AppState};
+use crate::{
+    handlers::{auth::AuthHandlerError, HandlerError},
+    http_server::AppState,
+    AppError,
+};
 
 pub fn get_default_jwt_config(state: &AppState) -> (usize, usize) {
     let now = chrono::Utc::now();
     let iat = now.timestamp() as usize;
     let exp = now
-        .checked_add_signed(state.config.get_jwt_expiration())
+        .checked_add_signed(state.settings.current().get_jwt_expiration())
         .expect("valid timestamp")
         .timestamp() as usize;
 
     (iat, exp)
 }
 
-pub fn extract_jwt_token_from_request(req: &Request) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+pub fn extract_jwt_token_from_request(req: &Request) -> Result<String, AppError> {
     let token = req
         .headers()
         .get(header::AUTHORIZATION)
@@ -25,11 +28,18 @@ pub fn extract_jwt_token_from_request(req: &Request) -> Result<String, (StatusCo
         .and_then(|auth_value| auth_value.strip_prefix("Bearer ").map(|s| s.to_owned()));
 
     token.ok_or_else(|| {
-        let json_error = ErrorResponse {
-            status: "fail",
-            message: "You are not logged in, please provide token".to_string(),
-        };
-
-        (StatusCode::UNAUTHORIZED, Json(json_error))
+        AppError::Handler(HandlerError::Auth(AuthHandlerError::Unauthorized(
+            "You are not logged in, please provide token".to_string(),
+        )))
     })
 }
+
+/// Random refresh token, 244 bits from two v4 UUIDs.
+pub fn generate_refresh_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Refresh tokens are only stored as this hash.
+pub fn hash_refresh_token(refresh_token: &str) -> String {
+    hex::encode(Sha256::digest(refresh_token.as_bytes()))
+}
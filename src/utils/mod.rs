@@ -1,7 +1,10 @@
+pub mod config_reload;
 pub mod generate_referral_code;
 pub mod jwt;
+pub mod retry;
+pub mod shutdown;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-util"))]
 pub mod test_app_state;
-#[cfg(test)]
+#[cfg(any(test, feature = "test-util"))]
 pub mod test_db;
@@ -0,0 +1,225 @@
+use std::{
+    collections::hash_map::RandomState,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Retry and circuit breaker settings for one external service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total attempts per call, including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Consecutive failed calls (after retries) that open the circuit. 0 disables the breaker.
+    pub circuit_breaker_threshold: u32,
+    /// How long an open circuit rejects calls before letting one through again.
+    pub circuit_breaker_cooldown_secs: u64,
+}
+
+impl RetryPolicy {
+    /// Exponential backoff for the given retry (1-based), with the upper half randomized so that
+    /// callers failing at the same time don't retry in lockstep.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exp = self
+            .initial_backoff_ms
+            .saturating_mul(1u64 << retry.saturating_sub(1).min(32))
+            .min(self.max_backoff_ms);
+        let half = exp / 2;
+        let jitter = if half == 0 { 0 } else { random_u64() % (half + 1) };
+
+        Duration::from_millis(half + jitter)
+    }
+}
+
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetryError<E> {
+    #[error("{0} is unavailable, circuit breaker is open")]
+    CircuitOpen(&'static str),
+    #[error(transparent)]
+    Failed(E),
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Runs calls to one external service with retries and a shared circuit breaker.
+#[derive(Debug)]
+pub struct Retrier {
+    service: &'static str,
+    policy: RetryPolicy,
+    breaker: Mutex<BreakerState>,
+}
+
+impl Retrier {
+    pub fn new(service: &'static str, policy: RetryPolicy) -> Self {
+        Self {
+            service,
+            policy,
+            breaker: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Runs `op` until it succeeds, fails with an error that `is_transient` rejects, or runs out of
+    /// attempts. Only transient failures count towards opening the circuit.
+    pub async fn run<T, E, F, Fut>(&self, is_transient: impl Fn(&E) -> bool, mut op: F) -> Result<T, RetryError<E>>
+    where
+        E: std::fmt::Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        if self.is_open() {
+            return Err(RetryError::CircuitOpen(self.service));
+        }
+
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Ok(value) => {
+                    self.record_success();
+                    return Ok(value);
+                }
+                Err(e) if is_transient(&e) && attempt < self.policy.max_attempts => {
+                    let delay = self.policy.backoff(attempt);
+                    tracing::warn!(
+                        "{} call failed (attempt {}/{}), retrying in {}ms: {}",
+                        self.service,
+                        attempt,
+                        self.policy.max_attempts,
+                        delay.as_millis(),
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    if is_transient(&e) {
+                        self.record_failure();
+                    }
+                    return Err(RetryError::Failed(e));
+                }
+            }
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        let state = self.breaker.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.open_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    fn record_success(&self) {
+        *self.breaker.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = BreakerState::default();
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.breaker.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.consecutive_failures += 1;
+
+        let threshold = self.policy.circuit_breaker_threshold;
+        if threshold > 0 && state.consecutive_failures >= threshold {
+            let cooldown = Duration::from_secs(self.policy.circuit_breaker_cooldown_secs);
+            state.open_until = Some(Instant::now() + cooldown);
+            tracing::error!(
+                "{} failed {} times in a row, rejecting calls for {}s",
+                self.service,
+                state.consecutive_failures,
+                cooldown.as_secs()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy(max_attempts: u32, circuit_breaker_threshold: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 2,
+            circuit_breaker_threshold,
+            circuit_breaker_cooldown_secs: 60,
+        }
+    }
+
+    #[test]
+    fn test_backoff_is_capped_and_jittered() {
+        let policy = RetryPolicy {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1000,
+            ..policy(5, 0)
+        };
+
+        for _ in 0..20 {
+            let first = policy.backoff(1).as_millis();
+            assert!((50..=100).contains(&first));
+            let capped = policy.backoff(10).as_millis();
+            assert!((500..=1000).contains(&capped));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors_only() {
+        let retrier = Retrier::new("test", policy(3, 0));
+        let calls = AtomicU32::new(0);
+
+        let result: Result<u32, _> = retrier
+            .run(
+                |_: &String| true,
+                || async {
+                    match calls.fetch_add(1, Ordering::SeqCst) {
+                        0 | 1 => Err("flaky".to_string()),
+                        n => Ok(n),
+                    }
+                },
+            )
+            .await;
+        assert_eq!(result.unwrap(), 2);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = retrier
+            .run(
+                |_: &String| false,
+                || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Err("bad request".to_string())
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(RetryError::Failed(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_after_consecutive_failures() {
+        let retrier = Retrier::new("test", policy(2, 2));
+        let calls = AtomicU32::new(0);
+        let failing = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>("down".to_string())
+        };
+
+        for _ in 0..2 {
+            let result = retrier.run(|_: &String| true, failing).await;
+            assert!(matches!(result, Err(RetryError::Failed(_))));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        let result = retrier.run(|_: &String| true, failing).await;
+        assert!(matches!(result, Err(RetryError::CircuitOpen("test"))));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
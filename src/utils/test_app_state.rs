@@ -1,25 +1,57 @@
 use crate::{
-    db_persistence::DbPersistence,
     http_server::AppState,
     metrics::Metrics,
+    middlewares::rate_limit::RateLimiter,
     models::auth::TokenClaims,
     services::{
-        exchange_rate_service::ExchangeRateService, risk_checker_service::RiskCheckerService,
+        challenge_store::ChallengeStore,
+        exchange_rate_service::ExchangeRateService,
+        health_registry::{HealthRegistry, LastCallOutcome},
+        leaderboard_cache::LeaderboardCache,
+        risk_checker_service::RiskCheckerService,
+        secrets::SecretStore,
+        settings_service::SettingsService,
         wallet_config_service::WalletConfigService,
     },
+    utils::{retry::Retrier, test_db::isolated_db},
     Config,
 };
 use jsonwebtoken::{encode, EncodingKey, Header};
-use rusx::RusxGateway;
+use rusx::{RusxGateway, TwitterGateway};
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 pub async fn create_test_app_state() -> AppState {
     let config = Config::load_test_env().expect("Failed to load test configuration");
-    let db = DbPersistence::new(config.get_database_url()).await.unwrap();
-    let twitter_gateway = RusxGateway::new(config.x_oauth.clone(), None).unwrap();
-    let risk_checker_service = RiskCheckerService::new(&config.risk_checker);
-    let exchange_rate_service = ExchangeRateService::new(&config.exchange_rate.api_key);
+    let db = isolated_db(config.get_database_url()).await;
+    let twitter_gateway: Arc<dyn TwitterGateway> = Arc::new(RusxGateway::new(config.x_oauth.clone(), None).unwrap());
+    let risk_checker_service = Arc::new(RiskCheckerService::new(
+        &config.risk_checker,
+        config.retry.etherscan.clone(),
+    ));
+    let exchange_rate_service = Arc::new(ExchangeRateService::new(
+        &config.exchange_rate.api_key,
+        config.retry.exchange_rate.clone(),
+    ));
     let db = Arc::new(db);
+    let config = Arc::new(config);
+    let settings = Arc::new(
+        SettingsService::load(config.clone(), db.settings.clone())
+            .await
+            .unwrap(),
+    );
+    let challenges = ChallengeStore::new(Arc::new(db.auth_challenges.clone()), settings.clone());
+
+    let secrets = SecretStore::new(&config.secrets);
+    let twitter_calls = Arc::new(LastCallOutcome::new());
+    let twitter_retrier = Arc::new(Retrier::new("X API", config.retry.x_api.clone()));
+    let health = HealthRegistry::with_default_probes(
+        db.pool.clone(),
+        config.candidates.graphql_url.clone(),
+        twitter_calls.clone(),
+        exchange_rate_service.clone(),
+        risk_checker_service.clone(),
+    );
 
     AppState {
         db,
@@ -27,11 +59,19 @@ pub async fn create_test_app_state() -> AppState {
         wallet_config_service: Arc::new(
             WalletConfigService::new(config.remote_configs.wallet_configs_file.clone()).unwrap(),
         ),
-        risk_checker_service: Arc::new(risk_checker_service),
-        exchange_rate_service: Arc::new(exchange_rate_service),
-        config: Arc::new(config),
-        twitter_gateway: Arc::new(twitter_gateway),
-        challenges: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+        risk_checker_service,
+        exchange_rate_service,
+        health: Arc::new(health),
+        config,
+        settings,
+        twitter_gateway,
+        twitter_calls,
+        twitter_retrier,
+        challenges: Arc::new(challenges),
+        rate_limiter: Arc::new(RateLimiter::new()),
+        leaderboard_cache: Arc::new(LeaderboardCache::new()),
+        secrets: Arc::new(secrets),
+        shutdown: CancellationToken::new(),
     }
 }
 
@@ -40,6 +80,7 @@ pub fn generate_test_token(secret: &str, user_id: &str) -> String {
         sub: user_id.to_string(),
         iat: 1,
         exp: 9999999999,
+        sid: None,
     };
 
     encode(
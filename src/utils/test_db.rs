@@ -1,30 +1,235 @@
 use chrono::Utc;
-use sqlx::PgPool;
+use sqlx::{postgres::PgPoolOptions, PgPool};
 use uuid::Uuid;
 
 use crate::{
+    db_persistence::DbPersistence,
     models::{
         address::{Address, AddressInput},
-        admin::Admin,
+        admin::{Admin, AdminRole},
+        raid_quest::{CreateRaidQuest, RaidScoringRules},
     },
     repositories::address::AddressRepository,
 };
 
-pub async fn reset_database(pool: &PgPool) {
-    sqlx::query("TRUNCATE referrals, opt_ins, addresses, admins, eth_associations, x_associations, relevant_tweets, tweet_authors, raid_quests, raid_submissions RESTART IDENTITY CASCADE")
-        .execute(pool)
+/// Test schemas are named `test_<unix seconds>_<uuid>`. They are dropped with their pool, ones left behind by
+/// an aborted run are dropped by later runs once older than this.
+const STALE_TEST_SCHEMA_SECS: i64 = 3600;
+
+/// A test schema, dropped when this is.
+struct TestSchema {
+    name: String,
+    database_url: String,
+}
+
+impl Drop for TestSchema {
+    fn drop(&mut self) {
+        let drop_schema = format!("DROP SCHEMA IF EXISTS {} CASCADE", self.name);
+        let database_url = self.database_url.clone();
+
+        // Drop can't await, and the test's runtime may be shutting down already
+        let _ = std::thread::spawn(move || {
+            let Ok(runtime) = tokio::runtime::Builder::new_current_thread().enable_all().build() else {
+                return;
+            };
+            runtime.block_on(async {
+                if let Ok(pool) = PgPool::connect(&database_url).await {
+                    let _ = sqlx::query(&drop_schema).execute(&pool).await;
+                    pool.close().await;
+                }
+            });
+        })
+        .join();
+    }
+}
+
+/// Pool on a schema of its own with the migrations applied, so tests don't see each other's rows and can run
+/// in parallel against one Postgres. `public` stays on the search path for the extensions. The schema is
+/// dropped once the pool and all its clones are.
+pub async fn isolated_pool(database_url: &str) -> PgPool {
+    let admin = PgPool::connect(database_url)
+        .await
+        .expect("Failed to connect to the test database");
+    drop_stale_test_schemas(&admin).await;
+    // Installed up front, tests migrating concurrently could otherwise race to create it
+    let _ = sqlx::query("CREATE EXTENSION IF NOT EXISTS btree_gist")
+        .execute(&admin)
+        .await;
+
+    let schema = TestSchema {
+        name: format!("test_{}_{}", Utc::now().timestamp(), Uuid::new_v4().simple()),
+        database_url: database_url.to_string(),
+    };
+    sqlx::query(&format!("CREATE SCHEMA {}", schema.name))
+        .execute(&admin)
+        .await
+        .expect("Failed to create test schema");
+    admin.close().await;
+
+    // The pool keeps this callback, and with it the schema, until the pool is gone
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .after_connect(move |conn, _| {
+            let set_search_path = format!("SET search_path TO {},public", schema.name);
+            Box::pin(async move { sqlx::query(&set_search_path).execute(conn).await.map(|_| ()) })
+        })
+        .connect(database_url)
+        .await
+        .expect("Failed to connect to the test schema");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Failed to migrate the test schema");
+
+    pool
+}
+
+/// [`DbPersistence`] on an [`isolated_pool`].
+pub async fn isolated_db(database_url: &str) -> DbPersistence {
+    DbPersistence::from_pools(isolated_pool(database_url).await, None)
         .await
-        .expect("Failed to truncate tables for tests");
+        .expect("Failed to set up the test database")
+}
+
+async fn drop_stale_test_schemas(pool: &PgPool) {
+    let schemas: Vec<String> =
+        sqlx::query_scalar("SELECT nspname::TEXT FROM pg_namespace WHERE nspname LIKE 'test\\_%'")
+            .fetch_all(pool)
+            .await
+            .unwrap_or_default();
+    let cutoff = Utc::now().timestamp() - STALE_TEST_SCHEMA_SECS;
+
+    for schema in schemas {
+        let created_at = schema.split('_').nth(1).and_then(|secs| secs.parse::<i64>().ok());
+        if created_at.is_some_and(|created_at| created_at < cutoff) {
+            let _ = sqlx::query(&format!("DROP SCHEMA IF EXISTS {} CASCADE", schema))
+                .execute(pool)
+                .await;
+        }
+    }
 }
 
 pub async fn create_persisted_address(repo: &AddressRepository, id: &str) -> Address {
-    let input = AddressInput {
-        quan_address: format!("qz_test_address_{}", id),
-        referral_code: format!("REF{}", id),
-    };
-    let address = Address::new(input).unwrap();
-    repo.create(&address).await.unwrap();
-    address
+    AddressBuilder::new(id).create(repo).await
+}
+
+/// Address named `qz_test_address_<id>` with referral code `REF<id>` unless another one is given.
+pub struct AddressBuilder {
+    quan_address: String,
+    referral_code: String,
+}
+
+impl AddressBuilder {
+    pub fn new(id: &str) -> Self {
+        Self {
+            quan_address: format!("qz_test_address_{}", id),
+            referral_code: format!("REF{}", id),
+        }
+    }
+
+    pub fn referral_code(mut self, referral_code: &str) -> Self {
+        self.referral_code = referral_code.to_string();
+        self
+    }
+
+    pub async fn create(self, repo: &AddressRepository) -> Address {
+        let address = Address::new(AddressInput {
+            quan_address: self.quan_address,
+            referral_code: self.referral_code,
+        })
+        .unwrap();
+        repo.create(&address).await.unwrap();
+        address
+    }
+}
+
+/// Raid with default scoring, `create` returns its id.
+pub struct RaidBuilder {
+    quest: CreateRaidQuest,
+}
+
+impl RaidBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            quest: CreateRaidQuest {
+                name: name.to_string(),
+                scoring: RaidScoringRules::default(),
+            },
+        }
+    }
+
+    pub fn scoring(mut self, scoring: RaidScoringRules) -> Self {
+        self.quest.scoring = scoring;
+        self
+    }
+
+    pub async fn create(self, db: &DbPersistence) -> i32 {
+        db.raid_quests.create(&self.quest).await.expect("Failed to create raid")
+    }
+}
+
+/// Raid submission without engagement counts unless set.
+pub struct SubmissionBuilder {
+    id: String,
+    raid_id: i32,
+    raider_id: String,
+    impression_count: i32,
+}
+
+impl SubmissionBuilder {
+    pub fn new(id: &str, raid_id: i32, raider_id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            raid_id,
+            raider_id: raider_id.to_string(),
+            impression_count: 0,
+        }
+    }
+
+    pub fn impressions(mut self, impression_count: i32) -> Self {
+        self.impression_count = impression_count;
+        self
+    }
+
+    pub async fn create(self, pool: &PgPool) {
+        sqlx::query("INSERT INTO raid_submissions (id, raid_id, raider_id, impression_count) VALUES ($1, $2, $3, $4)")
+            .bind(self.id)
+            .bind(self.raid_id)
+            .bind(self.raider_id)
+            .bind(self.impression_count)
+            .execute(pool)
+            .await
+            .expect("Failed to create raid submission");
+    }
+}
+
+/// Processed transfer of one unit between two addresses.
+pub struct TransferBuilder {
+    id: String,
+    from: String,
+    to: String,
+}
+
+impl TransferBuilder {
+    pub fn new(id: &str, from: &str, to: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    pub async fn create(self, pool: &PgPool) {
+        sqlx::query(
+            "INSERT INTO processed_transfers (transfer_id, from_address, to_address, amount) VALUES ($1, $2, $3, '1')",
+        )
+        .bind(self.id)
+        .bind(self.from)
+        .bind(self.to)
+        .execute(pool)
+        .await
+        .expect("Failed to create processed transfer");
+    }
 }
 
 pub async fn create_persisted_opt_in(pool: &PgPool, quan_address: &str) {
@@ -58,6 +263,8 @@ pub fn create_mock_admin() -> Admin {
         id: Uuid::new_v4(),
         username: "admin_tester".to_string(),
         password: "hash".to_string(),
+        role: AdminRole::Superadmin,
+        disabled_at: None,
         updated_at: Utc::now(),
         created_at: Utc::now(),
     }